@@ -0,0 +1,32 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Stamps the git commit into the build so `--version`, the server banner,
+/// and `/version` can report it. Builds outside a checkout leave it out.
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty());
+
+    let version = std::env::var("CARGO_PKG_VERSION").expect("cargo sets CARGO_PKG_VERSION");
+    let full_version = match &commit {
+        Some(commit) => {
+            println!("cargo:rustc-env=REMINDER_GIT_COMMIT={}", commit);
+            format!("{} ({})", version, commit)
+        }
+        None => version,
+    };
+    println!("cargo:rustc-env=REMINDER_FULL_VERSION={}", full_version);
+
+    // Re-stamp after commits and checkouts; a missing path would rerun every build
+    for path in [".git/HEAD", ".git/refs"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// How long a signed token stays valid, in seconds.
+pub const TOKEN_TTL_SECS: i64 = 300;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Builds a bearer token `<username>.<unix-timestamp>.<hex hmac>` proving the
+/// caller holds the shared secret and is acting as `username`.
+pub fn sign_token(username: &str, secret: &str, now: DateTime<Utc>) -> String {
+    let payload = format!("{}.{}", username, now.timestamp());
+    format!("{}.{}", payload, signature(&payload, secret))
+}
+
+/// Checks a token produced by `sign_token` and returns the username it was
+/// issued for.
+pub fn verify_token(token: &str, secret: &str, now: DateTime<Utc>) -> Result<String> {
+    let mut parts = token.rsplitn(3, '.');
+    let (Some(sig), Some(timestamp), Some(username)) = (parts.next(), parts.next(), parts.next()) else {
+        bail!("Malformed token");
+    };
+
+    let issued: i64 = timestamp.parse().context("Malformed token timestamp")?;
+    if (now.timestamp() - issued).abs() > TOKEN_TTL_SECS {
+        bail!("Token expired");
+    }
+
+    let expected = hex::decode(sig).context("Malformed token signature")?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}.{}", username, timestamp).as_bytes());
+    mac.verify_slice(&expected).map_err(|_| anyhow::anyhow!("Invalid token signature"))?;
+
+    Ok(username.to_string())
+}
+
+fn signature(payload: &str, secret: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -1,15 +1,38 @@
 use chrono::{DateTime, Local, Utc};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use prettytable::{Cell, Row, Table};
-use reqwest;
-use serde::{Deserialize, Serialize};
+use reminder_service::build_info;
+use reminder_service::config;
+use reminder_service::due_time;
+use reminder_service::durations;
+use reminder_service::{errln, outln, output};
+use reminder_service::models::{
+    CreateReminderRequest, CreateTemplateRequest, InstantiateTemplateRequest, PreferencesRequest, PurgeRequest, QuietHours,
+    RecurrenceAnchor, Reminder, ReminderListQuery, ReminderPreferences, TaskImportFormat, TaskImportQuery, TaskImportResponse, TaskImportStatus, TestNotifyRequest,
+};
+use reminder_service::reminder_client::ReminderClient;
+use std::path::PathBuf;
 
 const API_URL: &str = "http://localhost:3000";
+/// Sent as `X-Client-Source` so reminders record that they came from here.
+const CLIENT_SOURCE: &str = "reminder-cli";
+
+static CLIENT: std::sync::OnceLock<ReminderClient> = std::sync::OnceLock::new();
 
 #[derive(Parser)]
 #[command(name = "reminder")]
+#[command(version = build_info::FULL_VERSION)]
 #[command(about = "A CLI tool for managing reminders", long_about = None)]
 struct Cli {
+    #[arg(long, global = true, help = "Reminder service URL, http or https (default: REMINDER_API_URL, then http://localhost:3000)")]
+    url: Option<String>,
+
+    #[arg(long, global = true, help = "Accept self-signed or otherwise invalid TLS certificates (testing only)")]
+    insecure: bool,
+
+    #[arg(long, global = true, help = "ASCII-only output without emoji or borders, one line per record (also QUIZ_PLAIN=1 or NO_COLOR)")]
+    plain: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -18,162 +41,1294 @@ struct Cli {
 enum Commands {
     #[command(about = "Create a new reminder")]
     Create {
-        #[arg(short, long, help = "The reminder message")]
-        message: String,
+        #[arg(short, long, required_unless_present = "message_file", conflicts_with = "message_file", help = "The reminder message (use '-' to read from stdin)")]
+        message: Option<String>,
 
-        #[arg(short = 't', long, help = "Due time in ISO 8601 format (e.g., 2025-11-04T15:30:00Z)")]
-        time: String,
+        #[arg(long, help = "Read the reminder message from a file")]
+        message_file: Option<String>,
+
+        #[arg(short = 't', long, value_parser = resolve_due_time, required_unless_present = "now", conflicts_with = "now", help = "Due time (e.g., 2025-11-04T15:30:00Z, 2025-11-04 15:30, or 2025-11-04; without a zone your local time is used)")]
+        time: Option<String>,
+
+        #[arg(long, help = "Fire the first occurrence right away; a recurrence then counts on from now")]
+        now: bool,
+
+        #[arg(long, value_delimiter = ',', help = "Tags (comma-separated)")]
+        tags: Vec<String>,
+
+        #[arg(long, help = "Low priority: log each fire as one short line")]
+        quiet: bool,
+
+        #[arg(long, help = "http(s) URL to open when the reminder fires, e.g. a meeting link")]
+        link: Option<String>,
+
+        #[arg(long, help = "Fire even during the server's blackout window")]
+        ignore_blackout: bool,
+
+        #[arg(long, help = "Add the reminder to a group, so it can be listed, completed, or deleted with the rest")]
+        group: Option<String>,
 
         #[arg(short, long, help = "Optional username")]
         username: Option<String>,
+
+        #[arg(short, long, help = "Recurrence (e.g., daily, weekly, 30 minutes, 3 days, after_complete:3d)")]
+        recurrence: Option<String>,
+
+        #[arg(long, value_enum, requires = "recurrence", help = "Count the interval from the original schedule (default) or from when the reminder last fired")]
+        anchor: Option<RecurrenceAnchor>,
+
+        #[arg(long, value_name = "COUNT", num_args = 0..=1, default_missing_value = "5", help = "Show the first occurrences (default 5) without creating the reminder")]
+        preview: Option<usize>,
     },
 
     #[command(about = "View upcoming reminders")]
-    View,
+    View {
+        #[arg(long, help = "Also show each reminder's link")]
+        long: bool,
+    },
+
+    #[command(about = "Show a single reminder")]
+    Show {
+        #[arg(help = "Reminder ID (or a unique prefix)")]
+        id: String,
+
+        #[arg(long, value_name = "COUNT", help = "Also list its next COUNT occurrences")]
+        occurrences: Option<usize>,
+    },
+
+    #[command(about = "Search upcoming reminders by a word in their message")]
+    Search {
+        #[arg(help = "Text to look for (case-insensitive)")]
+        query: String,
+
+        #[arg(long, help = "Print matches as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "Mark a reminder as done before it fires")]
+    Complete {
+        #[arg(help = "Reminder ID (or a unique prefix)")]
+        id: String,
+
+        #[arg(long, help = "For recurring reminders, skip to the next occurrence instead")]
+        skip_occurrence: bool,
+    },
+
+    #[command(about = "Skip the upcoming occurrence of a recurring reminder")]
+    SkipNext {
+        #[arg(help = "Reminder ID (or a unique prefix)")]
+        id: String,
+    },
+
+    #[command(about = "Push a reminder back by some minutes")]
+    Snooze {
+        #[arg(help = "Reminder ID (or a unique prefix)")]
+        id: String,
+
+        #[arg(short, long, help = "Minutes to snooze for (default: 30)")]
+        minutes: Option<i64>,
+    },
+
+    #[command(about = "Acknowledge a reminder, recording who signed it off")]
+    Ack {
+        #[arg(help = "Reminder ID (or a unique prefix)")]
+        id: String,
+
+        #[arg(short, long, help = "Who is acknowledging it")]
+        by: String,
+    },
+
+    #[command(about = "Hand a reminder, or all of one user's reminders, to another user")]
+    Reassign {
+        #[arg(required_unless_present = "from", conflicts_with = "from", help = "Reminder ID (or a unique prefix)")]
+        id: Option<String>,
+
+        #[arg(long, help = "Username to give the reminders to")]
+        to: String,
+
+        #[arg(long, help = "Reassign every reminder of this user instead of a single one")]
+        from: Option<String>,
+    },
+
+    #[command(about = "Delete old sent one-time reminders (recurring and pending ones are kept)")]
+    Purge {
+        #[arg(long, value_parser = parse_age_days, help = "Only reminders due longer ago than this, in days (e.g., 30d or 2w)")]
+        older_than: u32,
+
+        #[arg(short, long, help = "Only reminders for this user")]
+        user: Option<String>,
+
+        #[arg(long, help = "List what would be deleted without deleting it")]
+        dry_run: bool,
+    },
+
+    #[command(about = "Show the server's version, limits, and accepted formats")]
+    Capabilities,
+
+    #[command(about = "Send a test notification through the server's delivery path")]
+    TestNotify {
+        #[arg(short, long, help = "Optional test message")]
+        message: Option<String>,
+    },
+
+    #[command(about = "Save and reuse reminder templates")]
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+
+    #[command(about = "Show or change a user's defaults for new reminders")]
+    Prefs {
+        #[command(subcommand)]
+        action: PrefsAction,
+    },
+
+    #[command(about = "List, complete, or delete a group of reminders")]
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
+
+    #[command(about = "Export all reminders to a JSON file")]
+    Export {
+        #[arg(short, long, help = "Path of the JSON file to write")]
+        output: String,
+    },
+
+    #[command(about = "Write a commented .env.example listing every recognized environment variable")]
+    InitConfig {
+        #[arg(short, long, default_value = config::ENV_EXAMPLE_FILE, help = "File to write")]
+        output: PathBuf,
+
+        #[arg(long, help = "Overwrite the file if it exists")]
+        force: bool,
+    },
+
+    #[command(about = "Import reminders from an exported JSON file, tasks from a Todoist or Google Tasks CSV export, or events from an .ics calendar")]
+    Import {
+        #[arg(short, long, help = "Path of the file to read")]
+        input: String,
+
+        #[arg(long, value_enum, default_value_t = ImportFormat::Json, help = "What the file is")]
+        format: ImportFormat,
+
+        #[arg(long, help = "Keep the original reminder IDs instead of generating new ones (json only)")]
+        preserve_ids: bool,
+
+        #[arg(long, help = "Show how each task would be converted without importing anything (task exports only)")]
+        dry_run: bool,
+
+        #[arg(long, help = "Also import completed tasks, as completed reminders (task exports only)")]
+        include_completed: bool,
+
+        #[arg(short, long, help = "User the imported tasks belong to (task exports only)")]
+        username: Option<String>,
+
+        #[arg(long, help = "Remind this long before each task or event, e.g. 30m or 1d (task exports only)")]
+        before: Option<String>,
+    },
 }
 
-#[derive(Debug, Serialize)]
-struct CreateReminderRequest {
-    message: String,
-    due_time: String,
-    username: Option<String>,
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ImportFormat {
+    /// A file written by 'export'
+    Json,
+    /// Todoist's CSV export
+    Todoist,
+    /// Google Tasks exported to CSV
+    GoogleTasks,
+    /// An iCalendar (.ics) file; importing it again updates the same events
+    Ics,
 }
 
-#[derive(Debug, Deserialize)]
-struct CreateReminderResponse {
-    id: String,
-    message: String,
-    due_time: String,
+#[derive(Subcommand)]
+enum TemplateAction {
+    #[command(about = "Save a named template")]
+    Save {
+        #[arg(help = "Template name")]
+        name: String,
+
+        #[arg(short, long, help = "The reminder message")]
+        message: String,
+
+        #[arg(short, long, help = "Default recurrence (e.g., daily, weekly, 30 minutes)")]
+        recurrence: Option<String>,
+
+        #[arg(long, value_delimiter = ',', help = "Extra reminders before the due time (comma-separated, e.g., 1d,2h)")]
+        lead: Vec<String>,
+
+        #[arg(long, value_delimiter = ',', help = "Default tags (comma-separated)")]
+        tags: Vec<String>,
+
+        #[arg(short, long, help = "Optional username")]
+        username: Option<String>,
+    },
+
+    #[command(about = "List saved templates")]
+    List {
+        #[arg(short, long, help = "Optional username")]
+        username: Option<String>,
+    },
+
+    #[command(about = "Create a reminder from a template")]
+    Use {
+        #[arg(help = "Template name")]
+        name: String,
+
+        #[arg(short = 't', long, value_parser = resolve_due_time, help = "Due time (e.g., 2025-11-04T15:30:00Z, 2025-11-04 15:30, or 2025-11-04; without a zone your local time is used)")]
+        time: String,
+
+        #[arg(short, long, help = "Optional username")]
+        username: Option<String>,
+    },
+
+    #[command(about = "Delete a template")]
+    Delete {
+        #[arg(help = "Template name")]
+        name: String,
+
+        #[arg(short, long, help = "Optional username")]
+        username: Option<String>,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-struct Reminder {
-    id: String,
-    message: String,
-    due_time: String,
-    username: Option<String>,
-    sent: bool,
-    created_at: String,
+#[derive(Subcommand)]
+enum PrefsAction {
+    #[command(about = "Show the saved preferences")]
+    Show {
+        #[arg(short, long, help = "Whose preferences")]
+        username: String,
+    },
+
+    #[command(about = "Change preferences; options left out keep their value, an empty value clears one")]
+    Set {
+        #[arg(short, long, help = "Whose preferences")]
+        username: String,
+
+        #[arg(long, help = "Timezone for due times without one, e.g. Europe/Berlin")]
+        timezone: Option<String>,
+
+        #[arg(long, value_delimiter = ',', help = "Notification channels (comma-separated: stdout, log)")]
+        channels: Option<Vec<String>>,
+
+        #[arg(long, value_delimiter = ',', help = "Extra reminders before each new reminder (comma-separated, e.g., 10m,1d)")]
+        lead: Option<Vec<String>>,
+
+        #[arg(long, value_enum, help = "Recurrence anchor for new fixed-interval reminders")]
+        anchor: Option<RecurrenceAnchor>,
+
+        #[arg(long, value_name = "START-END", help = "Hold notifications during these hours, e.g. 22:00-07:00")]
+        quiet_hours: Option<String>,
+    },
 }
 
-#[derive(Debug, Deserialize)]
-struct ReminderListResponse {
-    reminders: Vec<Reminder>,
+#[derive(Subcommand)]
+enum GroupAction {
+    #[command(about = "List the group's pending reminders, overdue ones included")]
+    List {
+        #[arg(help = "Group name")]
+        group: String,
+    },
+
+    #[command(about = "Mark every reminder in the group as completed")]
+    Complete {
+        #[arg(help = "Group name")]
+        group: String,
+    },
+
+    #[command(about = "Delete every reminder in the group")]
+    Delete {
+        #[arg(help = "Group name")]
+        group: String,
+    },
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    output::set_plain(output::plain_requested(cli.plain));
+
+    let url = cli
+        .url
+        .or_else(|| std::env::var("REMINDER_API_URL").ok().filter(|u| !u.is_empty()))
+        .unwrap_or_else(|| API_URL.to_string());
+    CLIENT.get_or_init(|| ReminderClient::new(url).insecure(cli.insecure).with_source(CLIENT_SOURCE));
 
     match cli.command {
         Commands::Create {
             message,
+            message_file,
             time,
+            now,
+            tags,
+            quiet,
+            link,
+            ignore_blackout,
+            group,
             username,
+            recurrence,
+            anchor,
+            preview,
         } => {
-            if let Err(e) = create_reminder(message, time, username).await {
-                eprintln!("❌ Error: {}", e);
+            let message = match read_message(message, message_file) {
+                Ok(message) => message,
+                Err(e) => {
+                    errln!("❌ Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let payload = CreateReminderRequest {
+                message,
+                due_time: time.unwrap_or_default(),
+                username,
+                recurrence,
+                recurrence_anchor: anchor,
+                fire_immediately: now,
+                tags,
+                quiet,
+                link,
+                ignore_blackout,
+                group_id: group,
+                ..Default::default()
+            };
+
+            let result = match preview {
+                Some(count) => preview_reminder(payload, count).await,
+                None => create_reminder(payload).await,
+            };
+            if let Err(e) = result {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::View { long } => {
+            if let Err(e) = view_reminders(long).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Show { id, occurrences } => {
+            if let Err(e) = show_reminder(id, occurrences).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Search { query, json } => {
+            if let Err(e) = search_reminders(query, json).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Complete { id, skip_occurrence } => {
+            if let Err(e) = complete_reminder(id, skip_occurrence).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::SkipNext { id } => {
+            if let Err(e) = skip_next(id).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Snooze { id, minutes } => {
+            if let Err(e) = snooze_reminder(id, minutes).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Ack { id, by } => {
+            if let Err(e) = ack_reminder(id, by).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Reassign { id, to, from } => {
+            if let Err(e) = reassign_reminders(id, to, from).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Purge { older_than, user, dry_run } => {
+            if let Err(e) = purge_reminders(older_than, user, dry_run).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Capabilities => {
+            if let Err(e) = show_capabilities().await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::TestNotify { message } => {
+            if let Err(e) = test_notify(message).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Template { action } => {
+            if let Err(e) = run_template(action).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Prefs { action } => {
+            if let Err(e) = run_prefs(action).await {
+                errln!("❌ Error: {}", e);
                 std::process::exit(1);
             }
         }
-        Commands::View => {
-            if let Err(e) = view_reminders().await {
-                eprintln!("❌ Error: {}", e);
+        Commands::Group { action } => {
+            if let Err(e) = run_group(action).await {
+                errln!("❌ Error: {}", e);
                 std::process::exit(1);
             }
         }
+        Commands::Export { output } => {
+            if let Err(e) = export_reminders(output).await {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Import { input, format, preserve_ids, dry_run, include_completed, username, before } => {
+            let task_format = match format {
+                ImportFormat::Json => None,
+                ImportFormat::Todoist => Some(TaskImportFormat::TodoistCsv),
+                ImportFormat::GoogleTasks => Some(TaskImportFormat::GoogleTasksCsv),
+                ImportFormat::Ics => Some(TaskImportFormat::Ics),
+            };
+            let result = match task_format {
+                None => import_reminders(input, preserve_ids).await,
+                Some(format) => {
+                    let query = TaskImportQuery { format, dry_run, include_completed, username, before };
+                    import_tasks(input, query).await
+                }
+            };
+            if let Err(e) = result {
+                errln!("❌ Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::InitConfig { output, force } => {
+            if let Err(e) = config::write_env_example(&output, force) {
+                errln!("❌ Error: {:#}", e);
+                std::process::exit(1);
+            }
+            outln!("✅ Wrote {}", output.display());
+            outln!("💡 Edit it, then load it into your shell with: set -a; . {}; set +a", output.display());
+        }
     }
 }
 
-async fn create_reminder(
-    message: String,
-    time: String,
-    username: Option<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
+/// Resolves `--time` in the local timezone and passes it on as RFC 3339, so
+/// the server never has to guess which zone was meant.
+fn resolve_due_time(time: &str) -> Result<String, String> {
+    due_time::parse_local_due_time(time, due_time::DEFAULT_HOUR)
+        .map(|due| due.to_rfc3339())
+        .map_err(|e| format!("{}. Accepted formats: {}", e, due_time::accepted_examples()))
+}
 
-    let payload = CreateReminderRequest {
-        message: message.clone(),
-        due_time: time.clone(),
-        username: username.clone(),
-    };
+/// Whole days from `30`, `30d`, or `2w`.
+fn parse_age_days(age: &str) -> Result<u32, String> {
+    if let Ok(days) = age.trim().parse::<u32>() {
+        return Ok(days);
+    }
 
-    let response = client
-        .post(format!("{}/reminders", API_URL))
-        .json(&payload)
-        .send()
-        .await?;
+    let duration = durations::parse_offset(age).map_err(|e| e.to_string())?;
+    if duration.num_seconds() % 86_400 != 0 {
+        return Err(format!("'{}' is not a whole number of days (use d or w)", age));
+    }
+    u32::try_from(duration.num_days()).map_err(|_| format!("'{}' is too long", age))
+}
+
+/// The client for the URL and TLS options given on the command line.
+fn api_client() -> ReminderClient {
+    CLIENT.get().cloned().unwrap_or_else(|| ReminderClient::new(API_URL).with_source(CLIENT_SOURCE))
+}
+
+/// Resolves the message from `--message`, `--message -` (stdin), or `--message-file`,
+/// dropping a single trailing newline.
+fn read_message(message: Option<String>, message_file: Option<String>) -> Result<String, Box<dyn std::error::Error>> {
+    use std::io::Read;
+
+    let mut text = match (message, message_file) {
+        (_, Some(path)) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read message file {}: {}", path, e))?,
+        (Some(m), None) if m == "-" => {
+            let mut buffer = String::new();
+            std::io::stdin().read_to_string(&mut buffer)?;
+            buffer
+        }
+        (Some(m), None) => m,
+        (None, None) => return Err("A message is required".into()),
+    };
 
-    if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(format!("Failed to create reminder: {}", error_text).into());
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
     }
 
-    let result: CreateReminderResponse = response.json().await?;
+    Ok(text)
+}
+
+async fn create_reminder(payload: CreateReminderRequest) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
 
-    let due_dt: DateTime<Utc> = result.due_time.parse()?;
-    let local_time = due_dt.with_timezone(&Local);
+    let result = client
+        .create(&payload)
+        .await
+        .map_err(|e| format!("Failed to create reminder: {}", e))?;
+    let reminder = &result.reminder;
 
-    println!("✅ Reminder created successfully!");
-    println!("   Message: {}", result.message);
-    println!("   Due: {}", local_time.format("%Y-%m-%d %H:%M:%S %Z"));
-    println!("   ID: {}", result.id);
+    outln!("✅ Reminder created successfully!");
+    outln!("   Message: {}", reminder.message);
+    outln!("   Due: {}", reminder.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+    outln!("   ID: {}", reminder.id);
     
-    if let Some(user) = username {
-        println!("   User: {}", user);
+    if let Some(ref user) = reminder.username {
+        outln!("   User: {}", user);
+    }
+    if let Some(ref recurrence) = reminder.recurrence {
+        let anchor = match reminder.recurrence_anchor {
+            RecurrenceAnchor::Schedule => "",
+            RecurrenceAnchor::Completion => ", counted from each firing",
+        };
+        outln!("   Recurrence: {}{}", recurrence, anchor);
+    }
+    if reminder.quiet {
+        outln!("   Quiet: yes");
+    }
+    if !reminder.tags.is_empty() {
+        outln!("   Tags: {}", reminder.tags.join(", "));
+    }
+    if let Some(ref link) = reminder.link {
+        outln!("   Link: {}", link);
+    }
+    for lead in &result.lead_reminders {
+        outln!("   ⏰ Lead reminder at {} ({})", lead.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"), short_id(&lead.id));
+    }
+
+    for warning in &result.warnings {
+        outln!("⚠️  {}", warning);
     }
 
     Ok(())
 }
 
-async fn view_reminders() -> Result<(), Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
+async fn preview_reminder(payload: CreateReminderRequest, count: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    let preview = client
+        .preview(&payload, count)
+        .await
+        .map_err(|e| format!("Failed to preview reminder: {}", e))?;
 
-    let response = client
-        .get(format!("{}/reminders", API_URL))
-        .send()
-        .await?;
+    outln!("🔍 Preview (nothing was created)");
+    outln!("   Message: {}", payload.message);
+    outln!("   Recurrence: {}", preview.recurrence.as_deref().unwrap_or("none"));
+    print_occurrences(&preview.occurrences);
 
-    if !response.status().is_success() {
-        let error_text = response.text().await?;
-        return Err(format!("Failed to fetch reminders: {}", error_text).into());
+    Ok(())
+}
+
+async fn show_reminder(id: String, occurrences: Option<usize>) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+    let id = resolve_reminder_id(&client, &id).await?;
+
+    let reminder = client
+        .get(&id)
+        .await
+        .map_err(|e| format!("Failed to fetch reminder: {}", e))?;
+
+    outln!("📌 Reminder {}", reminder.id);
+    outln!("   Message: {}", reminder.message);
+    outln!("   Due: {}", reminder.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+    if let Some(ref recurrence) = reminder.recurrence {
+        outln!("   Recurrence: {}", recurrence);
+    }
+    if let Some(ref user) = reminder.username {
+        outln!("   User: {}", user);
+    }
+    if reminder.quiet {
+        outln!("   Quiet: yes");
     }
+    if reminder.ignore_blackout {
+        outln!("   Ignores blackout: yes");
+    }
+    if let Some(ref group) = reminder.group_id {
+        outln!("   Group: {}", group);
+    }
+    if !reminder.tags.is_empty() {
+        outln!("   Tags: {}", reminder.tags.join(", "));
+    }
+    if let Some(ref link) = reminder.link {
+        outln!("   Link: {}", link);
+    }
+    outln!("   Created via: {}", reminder.source.as_deref().unwrap_or("API"));
+    let status = if reminder.completed {
+        "completed"
+    } else if reminder.sent {
+        "sent"
+    } else {
+        "pending"
+    };
+    outln!("   Status: {}", status);
+
+    if let Some(count) = occurrences {
+        let result = client
+            .occurrences(&id, count)
+            .await
+            .map_err(|e| format!("Failed to fetch occurrences: {}", e))?;
+        print_occurrences(&result.occurrences);
+    }
+
+    Ok(())
+}
 
-    let result: ReminderListResponse = response.json().await?;
+fn print_occurrences(occurrences: &[DateTime<Utc>]) {
+    outln!("\n📅 Occurrences ({})", occurrences.len());
+    for (i, time) in occurrences.iter().enumerate() {
+        outln!("   {}. {}", i + 1, time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+    }
+}
 
-    if result.reminders.is_empty() {
-        println!("📭 No upcoming reminders found.");
+async fn view_reminders(long: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    let reminders = client
+        .list()
+        .await
+        .map_err(|e| format!("Failed to fetch reminders: {}", e))?;
+
+    if reminders.is_empty() {
+        outln!("📭 No upcoming reminders found.");
         return Ok(());
     }
 
-    println!("\n📋 Upcoming Reminders ({})\n", result.reminders.len());
+    outln!("\n📋 Upcoming Reminders ({})\n", reminders.len());
+    print_reminder_table(reminders, long);
+
+    Ok(())
+}
+
+async fn search_reminders(query: String, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    let reminders = client
+        .search(&query)
+        .await
+        .map_err(|e| format!("Failed to search reminders: {}", e))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reminders)?);
+        return Ok(());
+    }
+
+    if reminders.is_empty() {
+        outln!("📭 No upcoming reminders match '{}'.", query);
+        return Ok(());
+    }
 
+    outln!("\n🔍 Reminders matching '{}' ({})\n", query, reminders.len());
+    print_reminder_table(reminders, false);
+
+    Ok(())
+}
+
+/// The first eight characters of a reminder id, or all of it when shorter
+/// (imported ids don't have to be UUIDs).
+fn short_id(id: &str) -> &str {
+    id.char_indices().nth(8).map_or(id, |(end, _)| &id[..end])
+}
+
+/// Prints reminders as a table; `long` adds a Link column.
+fn print_reminder_table(reminders: Vec<Reminder>, long: bool) {
     let mut table = Table::new();
-    table.add_row(Row::new(vec![
+    let mut headers = vec![
         Cell::new("ID"),
         Cell::new("Message"),
         Cell::new("Due Time"),
         Cell::new("User"),
-    ]));
+    ];
+    if long {
+        headers.push(Cell::new("Link"));
+    }
+    table.add_row(Row::new(headers));
 
-    for reminder in result.reminders {
-        let due_dt: DateTime<Utc> = reminder.due_time.parse()?;
-        let local_time = due_dt.with_timezone(&Local);
+    for reminder in reminders {
+        let local_time = reminder.due_time.with_timezone(&Local);
 
         let username = reminder
             .username
             .unwrap_or_else(|| "-".to_string());
 
-        table.add_row(Row::new(vec![
-            Cell::new(&reminder.id[..8]),
+        let mut cells = vec![
+            Cell::new(short_id(&reminder.id)),
             Cell::new(&reminder.message),
             Cell::new(&local_time.format("%Y-%m-%d %H:%M:%S").to_string()),
             Cell::new(&username),
+        ];
+        if long {
+            cells.push(Cell::new(reminder.link.as_deref().unwrap_or("-")));
+        }
+        table.add_row(Row::new(cells));
+    }
+
+    output::print_table(table);
+    outln!();
+}
+
+async fn complete_reminder(id: String, skip_occurrence: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+    let id = resolve_reminder_id(&client, &id).await?;
+
+    let reminder = client
+        .complete(&id, skip_occurrence)
+        .await
+        .map_err(|e| format!("Failed to complete reminder: {}", e))?;
+
+    if reminder.completed {
+        outln!("✅ Reminder completed");
+    } else {
+        if skip_occurrence {
+            outln!("⏭️  Occurrence skipped");
+        } else {
+            outln!("✅ Done for now; it repeats relative to this completion");
+        }
+        outln!("   Next due: {}", reminder.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+    }
+    outln!("   ID: {}", reminder.id);
+    outln!("   Message: {}", reminder.message);
+    if let Some(recurrence) = reminder.recurrence {
+        outln!("   Recurrence: {}", recurrence);
+    }
+
+    Ok(())
+}
+
+async fn skip_next(id: String) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+    let id = resolve_reminder_id(&client, &id).await?;
+
+    let reminder = client
+        .skip_next(&id)
+        .await
+        .map_err(|e| format!("Failed to skip occurrence: {}", e))?;
+
+    outln!("⏭️  Occurrence skipped");
+    outln!("   ID: {}", reminder.id);
+    outln!("   Message: {}", reminder.message);
+    outln!("   Next due: {}", reminder.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+
+    Ok(())
+}
+
+async fn snooze_reminder(id: String, minutes: Option<i64>) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+    let id = resolve_reminder_id(&client, &id).await?;
+
+    let reminder = client
+        .snooze(&id, minutes)
+        .await
+        .map_err(|e| format!("Failed to snooze reminder: {}", e))?;
+
+    outln!("💤 Reminder snoozed");
+    outln!("   ID: {}", reminder.id);
+    outln!("   Message: {}", reminder.message);
+    outln!("   Now due: {}", reminder.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+
+    Ok(())
+}
+
+async fn ack_reminder(id: String, by: String) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+    let id = resolve_reminder_id(&client, &id).await?;
+
+    let reminder = client
+        .ack(&id, &by)
+        .await
+        .map_err(|e| format!("Failed to acknowledge reminder: {}", e))?;
+
+    let (Some(acked_by), Some(acked_at)) = (&reminder.acked_by, reminder.acked_at) else {
+        return Err("Server did not record the acknowledgement".into());
+    };
+
+    outln!("✅ Reminder acknowledged");
+    outln!("   ID: {}", reminder.id);
+    outln!("   Message: {}", reminder.message);
+    outln!("   Acknowledged by: {}", acked_by);
+    outln!("   Acknowledged at: {}", acked_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+
+    Ok(())
+}
+
+async fn reassign_reminders(id: Option<String>, to: String, from: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    if let Some(from) = from {
+        let result = client
+            .reassign_all(&from, &to)
+            .await
+            .map_err(|e| format!("Failed to reassign reminders: {}", e))?;
+
+        if result.reassigned == 0 {
+            outln!("📭 No reminders for {} to reassign.", from);
+        } else {
+            outln!("✅ Reassigned {} reminder(s) from {} to {}", result.reassigned, from, to);
+        }
+        return Ok(());
+    }
+
+    let id = resolve_reminder_id(&client, &id.ok_or("Give a reminder ID or --from")?).await?;
+    let reminder = client
+        .reassign(&id, &to)
+        .await
+        .map_err(|e| format!("Failed to reassign reminder: {}", e))?;
+
+    outln!("✅ Reminder reassigned to {}", to);
+    outln!("   ID: {}", reminder.id);
+    outln!("   Message: {}", reminder.message);
+
+    Ok(())
+}
+
+async fn purge_reminders(older_than_days: u32, user: Option<String>, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let request = PurgeRequest {
+        sent: true,
+        older_than_days: Some(older_than_days),
+        username: user,
+        confirm_all: false,
+        dry_run,
+    };
+
+    let result = api_client()
+        .purge(&request)
+        .await
+        .map_err(|e| format!("Failed to purge reminders: {}", e))?;
+
+    if result.removed == 0 {
+        outln!("📭 No sent reminders older than {} days to purge.", older_than_days);
+        return Ok(());
+    }
+
+    if result.dry_run {
+        outln!("\n🧹 Would delete {} sent reminder(s) (dry run)\n", result.removed);
+        print_reminder_table(result.reminders, false);
+    } else {
+        outln!("🧹 Deleted {} sent reminder(s) older than {} days", result.removed, older_than_days);
+    }
+
+    Ok(())
+}
+
+async fn import_tasks(input: String, query: TaskImportQuery) -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(&input)?;
+
+    let result = api_client()
+        .import_tasks(data, &query)
+        .await
+        .map_err(|e| format!("Failed to import tasks: {}", e))?;
+
+    print_task_import(&result);
+
+    let count = |status| result.rows.iter().filter(|r| r.status == status).count();
+    let (created, updated) = (count(TaskImportStatus::Converted), count(TaskImportStatus::Updated));
+    let skipped = result.rows.len() - created - updated;
+    if result.dry_run {
+        outln!("🔍 Dry run for {}: {} would be created, {} updated, {} skipped", input, created, updated, skipped);
+    } else {
+        outln!("✅ Imported {}: {} created, {} updated, {} skipped", input, result.imported, result.updated, skipped);
+    }
+
+    Ok(())
+}
+
+fn print_task_import(result: &TaskImportResponse) {
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Line"),
+        Cell::new("Task"),
+        Cell::new("Due Time"),
+        Cell::new("Recurrence"),
+        Cell::new("Result"),
+    ]));
+
+    for row in &result.rows {
+        let reason = row.reason.as_deref().unwrap_or_default();
+        let note = row.reason.as_deref().map(|note| format!(" ({})", note)).unwrap_or_default();
+        let outcome = match row.status {
+            TaskImportStatus::Converted => format!("✅ converted{}", note),
+            TaskImportStatus::Updated => format!("🔁 updates an earlier import{}", note),
+            TaskImportStatus::Skipped => format!("⏭️  skipped: {}", reason),
+            TaskImportStatus::Failed => format!("❌ {}", reason),
+        };
+        let due = row
+            .due_time
+            .map(|due| due.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        table.add_row(Row::new(vec![
+            Cell::new(&row.line.to_string()),
+            Cell::new(&row.task),
+            Cell::new(&due),
+            Cell::new(row.recurrence.as_deref().unwrap_or("-")),
+            Cell::new(&outcome),
         ]));
     }
 
-    table.printstd();
-    println!();
+    output::print_table(table);
+    outln!();
+}
+
+/// Expands a unique id prefix to the full reminder id.
+async fn resolve_reminder_id(client: &ReminderClient, id: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let reminders = client
+        .list_all()
+        .await
+        .map_err(|e| format!("Failed to fetch reminders: {}", e))?;
+
+    if reminders.iter().any(|r| r.id == id) {
+        return Ok(id.to_string());
+    }
+
+    let matches: Vec<_> = reminders.iter().filter(|r| r.id.starts_with(id)).collect();
+    match matches.as_slice() {
+        [] => Err(format!("Reminder {} not found", id).into()),
+        [reminder] => Ok(reminder.id.clone()),
+        _ => Err(format!("ID prefix '{}' is ambiguous: it matches {} reminders", id, matches.len()).into()),
+    }
+}
+
+async fn show_capabilities() -> Result<(), Box<dyn std::error::Error>> {
+    let capabilities = api_client()
+        .capabilities()
+        .await
+        .map_err(|e| format!("Failed to fetch server capabilities: {}", e))?;
+
+    outln!("🖥️  Reminder service {}", capabilities.version);
+    outln!("   Authentication: {}", if capabilities.auth_required { "required" } else { "disabled" });
+    outln!("   Max request body: {} KB", capabilities.max_body_kb);
+
+    outln!("\n🔄 Recurrence patterns");
+    for format in &capabilities.recurrence_formats {
+        outln!("   {:<28} e.g. {:<20} {}", format.pattern, format.example, format.description);
+    }
+
+    outln!("\n⏰ Due time formats");
+    for example in &capabilities.due_time_examples {
+        outln!("   {}", example);
+    }
+
+    let imports: Vec<String> = capabilities
+        .task_import_formats
+        .iter()
+        .filter_map(|format| serde_json::to_value(format).ok())
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect();
+    outln!("\n📥 Task imports: {}", imports.join(", "));
+
+    Ok(())
+}
+
+async fn test_notify(message: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    let result = client
+        .test_notify(&TestNotifyRequest { message, username: None })
+        .await
+        .map_err(|e| format!("Failed to send test notification: {}", e))?;
+
+    if result.delivered {
+        outln!("✅ Test notification delivered via {}", result.channel);
+        Ok(())
+    } else {
+        Err(format!(
+            "Test notification failed via {}: {}",
+            result.channel,
+            result.error.unwrap_or_else(|| "unknown error".to_string())
+        )
+        .into())
+    }
+}
+
+async fn run_template(action: TemplateAction) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    match action {
+        TemplateAction::Save { name, message, recurrence, lead, tags, username } => {
+            let template = client
+                .create_template(&CreateTemplateRequest {
+                    name,
+                    username,
+                    message,
+                    recurrence,
+                    lead_offsets: lead,
+                    tags,
+                })
+                .await
+                .map_err(|e| format!("Failed to save template: {}", e))?;
+
+            outln!("✅ Template '{}' saved", template.name);
+            outln!("💡 Use it with: reminder template use {} --time <DUE TIME>", template.name);
+        }
+        TemplateAction::List { username } => {
+            let templates = client
+                .list_templates(username.as_deref())
+                .await
+                .map_err(|e| format!("Failed to fetch templates: {}", e))?;
+
+            if templates.is_empty() {
+                outln!("📭 No templates saved.");
+                return Ok(());
+            }
+
+            let mut table = Table::new();
+            table.add_row(Row::new(vec![
+                Cell::new("Name"),
+                Cell::new("Message"),
+                Cell::new("Recurrence"),
+                Cell::new("Lead"),
+                Cell::new("Tags"),
+            ]));
+
+            for template in templates {
+                table.add_row(Row::new(vec![
+                    Cell::new(&template.name),
+                    Cell::new(&template.message),
+                    Cell::new(template.recurrence.as_deref().unwrap_or("-")),
+                    Cell::new(&template.lead_offsets.join(", ")),
+                    Cell::new(&template.tags.join(", ")),
+                ]));
+            }
+
+            output::print_table(table);
+        }
+        TemplateAction::Use { name, time, username } => {
+            let result = client
+                .instantiate_template(&name, &InstantiateTemplateRequest { due_time: time, username })
+                .await
+                .map_err(|e| format!("Failed to use template: {}", e))?;
+
+            let reminder = result.reminder;
+            outln!("✅ Reminder created from '{}'", name);
+            outln!("   Message: {}", reminder.message);
+            outln!("   Due: {}", reminder.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"));
+            outln!("   ID: {}", reminder.id);
+            for lead in result.lead_reminders {
+                outln!("   ⏰ Lead reminder at {} ({})", lead.due_time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %Z"), short_id(&lead.id));
+            }
+        }
+        TemplateAction::Delete { name, username } => {
+            client
+                .delete_template(&name, username.as_deref())
+                .await
+                .map_err(|e| format!("Failed to delete template: {}", e))?;
+
+            outln!("✅ Template '{}' deleted", name);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_group(action: GroupAction) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    match action {
+        GroupAction::List { group } => {
+            let query = ReminderListQuery {
+                group: Some(group.clone()),
+                sent: Some(false),
+                ..Default::default()
+            };
+            let reminders = client
+                .list_matching(&query)
+                .await
+                .map_err(|e| format!("Failed to fetch reminders: {}", e))?;
+
+            if reminders.is_empty() {
+                outln!("📭 No pending reminders in group '{}'.", group);
+                return Ok(());
+            }
+
+            outln!("\n📂 Group '{}' ({})\n", group, reminders.len());
+            print_reminder_table(reminders, false);
+        }
+        GroupAction::Complete { group } => {
+            let result = client
+                .complete_group(&group)
+                .await
+                .map_err(|e| format!("Failed to complete group: {}", e))?;
+
+            if result.affected == 0 {
+                outln!("📭 Nothing left to complete in group '{}'.", group);
+            } else {
+                outln!("✅ Completed {} reminder(s) in group '{}'", result.affected, group);
+            }
+        }
+        GroupAction::Delete { group } => {
+            let result = client
+                .delete_group(&group)
+                .await
+                .map_err(|e| format!("Failed to delete group: {}", e))?;
+
+            if result.affected == 0 {
+                outln!("📭 No reminders in group '{}'.", group);
+            } else {
+                outln!("🗑️  Deleted {} reminder(s) in group '{}'", result.affected, group);
+            }
+        }
+    }
 
     Ok(())
 }
+
+async fn run_prefs(action: PrefsAction) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    match action {
+        PrefsAction::Show { username } => {
+            let preferences = match client.preferences(&username).await {
+                Ok(preferences) => preferences,
+                Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+                    outln!("📭 No preferences set for {}; the server defaults apply.", username);
+                    return Ok(());
+                }
+                Err(e) => return Err(format!("Failed to fetch preferences: {}", e).into()),
+            };
+
+            outln!("⚙️  Preferences for {}", username);
+            print_preferences(&preferences);
+        }
+        PrefsAction::Set { username, timezone, channels, lead, anchor, quiet_hours } => {
+            let current = match client.preferences(&username).await {
+                Ok(preferences) => Some(preferences),
+                Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => None,
+                Err(e) => return Err(format!("Failed to fetch preferences: {}", e).into()),
+            };
+            let mut request = current
+                .map(PreferencesRequest::from)
+                .unwrap_or_default();
+
+            if let Some(timezone) = timezone {
+                request.timezone = Some(timezone).filter(|t| !t.trim().is_empty());
+            }
+            if let Some(channels) = channels {
+                request.channels = channels;
+            }
+            if let Some(lead) = lead {
+                request.lead_offsets = lead;
+            }
+            if anchor.is_some() {
+                request.recurrence_anchor = anchor;
+            }
+            if let Some(quiet_hours) = quiet_hours {
+                request.quiet_hours = parse_quiet_hours(&quiet_hours)?;
+            }
+
+            let preferences = client
+                .set_preferences(&username, &request)
+                .await
+                .map_err(|e| format!("Failed to save preferences: {}", e))?;
+
+            outln!("✅ Preferences saved for {}", username);
+            print_preferences(&preferences);
+            outln!("💡 They apply to reminders created from now on.");
+        }
+    }
+
+    Ok(())
+}
+
+/// `22:00-07:00` as quiet hours; an empty value means none.
+fn parse_quiet_hours(value: &str) -> Result<Option<QuietHours>, String> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    QuietHours::parse_range(value).map(Some)
+}
+
+fn print_preferences(preferences: &ReminderPreferences) {
+    let list = |items: &[String]| if items.is_empty() { "(not set)".to_string() } else { items.join(", ") };
+
+    outln!("   🌍 Timezone: {}", preferences.timezone.as_deref().unwrap_or("(not set)"));
+    outln!("   📣 Channels: {}", list(&preferences.channels));
+    outln!("   ⏰ Lead reminders: {}", list(&preferences.lead_offsets));
+    let anchor = match preferences.recurrence_anchor {
+        Some(RecurrenceAnchor::Schedule) => "schedule",
+        Some(RecurrenceAnchor::Completion) => "completion",
+        None => "(not set)",
+    };
+    outln!("   🔄 Recurrence anchor: {}", anchor);
+    match preferences.quiet_hours {
+        Some(ref quiet) => outln!("   🌙 Quiet hours: {} to {}", quiet.start, quiet.end),
+        None => outln!("   🌙 Quiet hours: (not set)"),
+    }
+}
+
+async fn export_reminders(output: String) -> Result<(), Box<dyn std::error::Error>> {
+    let client = api_client();
+
+    let reminders = client
+        .list_all()
+        .await
+        .map_err(|e| format!("Failed to export reminders: {}", e))?;
+
+    let json = serde_json::to_string_pretty(&reminders)?;
+    std::fs::write(&output, json)?;
+
+    outln!("✅ Exported {} reminder(s) to {}", reminders.len(), output);
+
+    Ok(())
+}
+
+async fn import_reminders(input: String, preserve_ids: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(&input)?;
+    let reminders: Vec<serde_json::Value> = serde_json::from_str(&data)
+        .map_err(|e| format!("Invalid export file {}: {}", input, e))?;
+
+    let client = api_client();
+
+    let result = client
+        .import(&reminders, preserve_ids)
+        .await
+        .map_err(|e| format!("Failed to import reminders: {}", e))?;
+
+    outln!("✅ Imported {} of {} reminder(s) from {}", result.imported, reminders.len(), input);
+
+    if !result.skipped.is_empty() {
+        outln!("⚠️  Skipped {} entry(ies):", result.skipped.len());
+        for reason in &result.skipped {
+            outln!("   - {}", reason);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_id_never_splits_or_overruns_an_id() {
+        assert_eq!(short_id("0123456789abcdef"), "01234567");
+        assert_eq!(short_id("abc"), "abc");
+        assert_eq!(short_id(""), "");
+        assert_eq!(short_id("ééééééééé"), "éééééééé");
+    }
+
+    #[test]
+    fn create_splits_comma_separated_tags() {
+        let cli = Cli::try_parse_from(["reminder", "create", "-m", "Revise", "--now", "--tags", "exam,math", "--tags", "week-3"]).unwrap();
+
+        let Commands::Create { tags, .. } = cli.command else {
+            panic!("expected the create command");
+        };
+        assert_eq!(tags, ["exam", "math", "week-3"]);
+    }
+}
@@ -1,26 +1,49 @@
 use anyhow::{Context, Result, bail};
-use clap::{Parser, Subcommand};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use prettytable::{Cell, Row, Table};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
-
-mod user_models {
-    include!("../user_models.rs");
-}
-
-mod user_storage {
-    include!("../user_storage.rs");
-}
-
-use user_models::{User, UploadedFile};
-use user_storage::UserStorage;
-
+use std::path::{Path, PathBuf};
+
+use reminder_service::{auth, build_info, config, due_time, durations, errln, ids, models, outln, output, storage, summary};
+use reminder_service::models::{CreateReminderRequest, PreferencesRequest, Reminder, ReminderListQuery, UpdateReminderRequest};
+use reminder_service::reminder_client::{ClientError, ReminderClient};
+use reminder_service::user_models::{FileUpdate, User, UploadedFile, UserPreferences};
+use reminder_service::user_storage::{format_bytes, normalize_tag, validate_tags, SearchOptions, TagMatch, UserStorage};
+
+const DEFAULT_API_URL: &str = "http://localhost:3000";
+/// Sent as `X-Client-Source` so reminders record that they came from quiz.
+const CLIENT_SOURCE: &str = "quiz";
+const CONFIG_FILE: &str = ".quiz-config.json";
 const SESSION_FILE: &str = ".session";
+const NOTIFICATION_QUEUE_FILE: &str = ".notification-queue.json";
+const ACTIVITY_LOG_FILE: &str = "activity.log";
+const DEFAULT_ACTIVITY_LOG_MAX_KB: u64 = 1024;
+const EXPORT_FORMAT_VERSION: u32 = 1;
+const DEFAULT_IDLE_TIMEOUT_MIN: i64 = 30;
+const MIN_PASSWORD_LENGTH: usize = 6;
+/// Lowest `PasswordStrength::score` accepted when `QUIZ_ENFORCE_STRONG=1`.
+const STRONG_PASSWORD_SCORE: u8 = 3;
+const COMMON_PASSWORDS: &[&str] = &["password", "123456", "qwerty", "letmein", "welcome", "iloveyou", "admin", "abc123", "monkey", "dragon"];
+const DEFAULT_PAGE_SIZE: usize = 20;
+const DEFAULT_ALLOWED_EXTENSIONS: &str = "txt";
+const DEFAULT_PROMPT_TIMEOUT_SECS: u64 = 60;
 
 #[derive(Parser)]
 #[command(name = "quiz")]
+#[command(version = build_info::FULL_VERSION)]
 #[command(about = "A CLI tool for managing study quizzes", long_about = None)]
 struct Cli {
+    #[arg(long, global = true, help = "Reminder service URL (overrides REMINDER_API_URL and the config file)")]
+    reminder_url: Option<String>,
+
+    #[arg(long, global = true, help = "Accept self-signed or otherwise invalid TLS certificates from the reminder service (testing only)")]
+    insecure: bool,
+
+    #[arg(long, global = true, help = "ASCII-only output without emoji or borders, one line per record (also QUIZ_PLAIN=1 or NO_COLOR)")]
+    plain: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -48,18 +71,66 @@ enum Commands {
     #[command(about = "Log out of your account")]
     Logout,
 
+    #[command(about = "Change your username")]
+    RenameAccount {
+        #[arg(help = "New username")]
+        new_username: String,
+
+        #[arg(short, long, help = "Current password")]
+        password: String,
+    },
+
     #[command(about = "Upload a text file for quiz generation")]
     Upload {
-        #[arg(short, long, help = "Path to the text file")]
-        file: String,
+        #[arg(short, long, required_unless_present = "stdin", conflicts_with = "stdin", help = "Path to the text file")]
+        file: Option<String>,
+
+        #[arg(long, requires = "name", help = "Read the content from stdin instead of a file")]
+        stdin: bool,
+
+        #[arg(long, requires = "stdin", help = "Filename to store stdin content under (.txt is added when it has no extension)")]
+        name: Option<String>,
     },
 
     #[command(about = "List your uploaded files")]
-    List,
+    List {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text, help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, default_value_t = 1, help = "Page to show")]
+        page: usize,
+
+        #[arg(long, default_value_t = DEFAULT_PAGE_SIZE, help = "Files per page")]
+        page_size: usize,
+
+        #[arg(long, help = "Show each file's details on separate lines instead of a table")]
+        long: bool,
+    },
 
     #[command(about = "Show current user")]
     Whoami,
 
+    #[command(about = "Update a file's name, description, or flags")]
+    EditFile {
+        #[arg(short, long, help = "File ID")]
+        file_id: String,
+
+        #[arg(short, long, help = "New filename")]
+        name: Option<String>,
+
+        #[arg(short, long, help = "New description")]
+        description: Option<String>,
+
+        #[arg(long, help = "Star or unstar the file (true/false)")]
+        starred: Option<bool>,
+
+        #[arg(long, help = "Archive or unarchive the file (true/false)")]
+        archived: Option<bool>,
+
+        #[arg(short, long, help = "Path to a text file whose content replaces the current content")]
+        content: Option<String>,
+    },
+
     #[command(about = "Add tag(s) to a file")]
     Tag {
         #[arg(short, long, help = "File ID")]
@@ -80,8 +151,71 @@ enum Commands {
 
     #[command(about = "Filter files by tag")]
     FilterByTag {
-        #[arg(short, long, help = "Tag to filter by")]
+        #[arg(short, long, help = "Tag(s) to filter by (comma-separated)")]
         tag: String,
+
+        #[arg(short, long, help = "Tags to exclude (comma-separated)")]
+        exclude: Option<String>,
+
+        #[arg(long, help = "Require every tag instead of any of them")]
+        all: bool,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text, help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, help = "Show each file's details on separate lines instead of a table")]
+        long: bool,
+    },
+
+    #[command(about = "Search the content of your files")]
+    Search {
+        #[arg(short, long, help = "Text to search for")]
+        query: String,
+
+        #[arg(short, long, help = "Treat the query as a regular expression")]
+        regex: bool,
+
+        #[arg(short, long, help = "Match case exactly")]
+        case_sensitive: bool,
+
+        #[arg(long, help = "Show each result with its matching snippets instead of a table")]
+        long: bool,
+    },
+
+    #[command(about = "Show a line-based diff between two uploaded files")]
+    Diff {
+        #[arg(help = "ID of the original file")]
+        file_id_a: String,
+
+        #[arg(help = "ID of the updated file")]
+        file_id_b: String,
+    },
+
+    #[command(about = "Show a short summary of a file: title, size, and key terms")]
+    Summary {
+        #[arg(short, long, help = "File ID", conflicts_with = "tag", required_unless_present = "tag")]
+        file_id: Option<String>,
+
+        #[arg(short, long, help = "Summarize every file with this tag")]
+        tag: Option<String>,
+
+        #[arg(short = 'n', long, default_value_t = 5, help = "Number of key terms to show")]
+        terms: usize,
+    },
+
+    #[command(about = "Suggest tags for a file from its content and your existing tags")]
+    SuggestTags {
+        #[arg(short, long, help = "File ID", conflicts_with = "all_untagged", required_unless_present = "all_untagged")]
+        file_id: Option<String>,
+
+        #[arg(long, help = "Suggest tags for every file that has none")]
+        all_untagged: bool,
+
+        #[arg(short = 'n', long, default_value_t = 5, help = "Most suggestions per file")]
+        limit: usize,
+
+        #[arg(long, help = "Ask about each suggestion and add the accepted ones")]
+        apply: bool,
     },
 
     #[command(about = "Apply tags to multiple files")]
@@ -102,6 +236,21 @@ enum Commands {
         tag: String,
     },
 
+    #[command(about = "Delete multiple files, selected by ID or by tag")]
+    BulkDelete {
+        #[arg(short, long, help = "File IDs (comma-separated)", conflicts_with = "tag", required_unless_present = "tag")]
+        file_ids: Option<String>,
+
+        #[arg(short, long, help = "Delete every file with this tag")]
+        tag: Option<String>,
+
+        #[arg(short, long, visible_alias = "force", help = "Skip the confirmation prompt")]
+        yes: bool,
+
+        #[arg(long, help = "Show the files that would be deleted without deleting them")]
+        dry_run: bool,
+    },
+
     #[command(about = "Create a study notification reminder")]
     Notify {
         #[arg(short = 'n', long, help = "Title of the notification")]
@@ -110,21 +259,237 @@ enum Commands {
         #[arg(short, long, help = "Memo/description (optional)")]
         memo: Option<String>,
 
-        #[arg(short = 't', long, help = "Date and time (ISO 8601, e.g., 2025-11-05T10:00:00Z)")]
+        #[arg(short = 't', long, help = "Date and time (e.g., 2025-11-05T10:00:00Z, 2025-11-05 10:00, or 2025-11-05; without a zone your default timezone is used)")]
         time: String,
 
-        #[arg(short, long, help = "Recurrence pattern: daily, weekly, or custom interval in minutes")]
+        #[arg(short, long, help = "Recurrence pattern: daily, weekly, N days, or custom interval in minutes (see 'quiz recurrence-help')")]
         recurrence: Option<String>,
+
+        #[arg(long, conflicts_with = "recurrence", help = "Don't apply your default recurrence")]
+        once: bool,
+
+        #[arg(short, long, help = "Link uploaded file(s) to study (comma-separated IDs)")]
+        file: Option<String>,
+
+        #[arg(short = 'g', long, help = "Link files with these tag(s) (comma-separated)")]
+        tag: Option<String>,
+
+        #[arg(long, help = "Tags to organize the notification by subject (comma-separated)")]
+        tags: Option<String>,
+
+        #[arg(long, help = "http(s) URL to open when it fires, e.g. a file export or an external resource")]
+        link: Option<String>,
     },
 
     #[command(about = "List your study notifications")]
-    ListNotifications,
+    ListNotifications {
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text, help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, help = "Show raw UTC times instead of local and relative times")]
+        utc: bool,
+
+        #[arg(long, conflicts_with = "sent", help = "Only notifications that haven't fired yet, overdue ones included")]
+        pending: bool,
+
+        #[arg(long, help = "Only notifications that already fired")]
+        sent: bool,
+
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration, help = "Only notifications due between now and this far ahead (e.g., 2h or 3d)")]
+        due_within: Option<chrono::Duration>,
+
+        #[arg(long, value_name = "TEXT", help = "Only notifications whose title or memo contains this text")]
+        search: Option<String>,
+
+        #[arg(long, value_name = "N", help = "Show at most N notifications")]
+        limit: Option<usize>,
+    },
+
+    #[command(about = "Show the files linked to a study notification")]
+    Study {
+        #[arg(short, long, help = "Notification ID")]
+        id: String,
+    },
+
+    #[command(about = "Delete a study notification")]
+    #[command(group = clap::ArgGroup::new("target").required(true).args(["id", "all_past", "series"]))]
+    DeleteNotification {
+        #[arg(short, long, help = "Notification ID")]
+        id: Option<String>,
+
+        #[arg(long, help = "Delete all of your notifications that have already been sent")]
+        all_past: bool,
+
+        #[arg(short, long, help = "Delete every notification in a series")]
+        series: Option<String>,
+
+        #[arg(short, long, visible_alias = "force", help = "Skip the confirmation prompt")]
+        yes: bool,
+    },
+
+    #[command(about = "Create a series of reminders counting down to a date")]
+    NotifySeries {
+        #[arg(short = 'n', long, help = "Title of the notifications")]
+        title: String,
+
+        #[arg(short, long, help = "Memo/description (optional)")]
+        memo: Option<String>,
+
+        #[arg(short = 't', long, help = "Event date and time (e.g., 2025-12-10T09:00:00Z or 2025-12-10 09:00; without a zone your default timezone is used)")]
+        time: String,
+
+        #[arg(short, long, default_value = "1w,3d,1d,2h", help = "Offsets before the event (comma-separated, e.g., 1w,3d,1d,2h)")]
+        offsets: String,
+    },
+
+    #[command(about = "Edit a study notification")]
+    EditNotification {
+        #[arg(short, long, help = "Notification ID")]
+        id: String,
+
+        #[arg(short = 'n', long, help = "New title")]
+        title: Option<String>,
+
+        #[arg(short, long, help = "New memo/description")]
+        memo: Option<String>,
+
+        #[arg(short = 't', long, help = "New date and time (e.g., 2025-11-05T10:00:00Z or 2025-11-05 10:00; without a zone your default timezone is used)")]
+        time: Option<String>,
+
+        #[arg(short, long, help = "New recurrence pattern: daily, weekly, N days, or custom interval in minutes")]
+        recurrence: Option<String>,
+    },
+
+    #[command(about = "Snooze a study notification")]
+    SnoozeNotification {
+        #[arg(short, long, help = "Notification ID (or a unique prefix)")]
+        id: String,
+
+        #[arg(long, default_value_t = 30, help = "Minutes to snooze for")]
+        minutes: i64,
+    },
+
+    #[command(about = "Mark a study notification as done")]
+    DoneNotification {
+        #[arg(short, long, help = "Notification ID (or a unique prefix)")]
+        id: String,
+    },
+
+    #[command(about = "Show your study streak")]
+    Streak {
+        #[command(subcommand)]
+        action: Option<StreakAction>,
+    },
+
+    #[command(about = "Set your defaults for new study notifications")]
+    SetPrefs {
+        #[arg(long, help = "Default timezone (IANA name, e.g., Europe/Berlin)")]
+        timezone: Option<String>,
+
+        #[arg(long, help = "Default recurrence pattern, e.g., daily")]
+        recurrence: Option<String>,
+
+        #[arg(long, conflicts_with = "timezone", help = "Remove the default timezone")]
+        clear_timezone: bool,
+
+        #[arg(long, conflicts_with = "recurrence", help = "Remove the default recurrence")]
+        clear_recurrence: bool,
+    },
+
+    #[command(about = "Show your defaults for new study notifications")]
+    GetPrefs,
+
+    #[command(about = "View or change quiz settings")]
+    Config {
+        #[arg(long, help = "Save the reminder service URL")]
+        set_reminder_url: Option<String>,
+
+        #[arg(long, help = "Remove the saved reminder service URL")]
+        unset_reminder_url: bool,
+
+        #[arg(long, help = "Read and write reminders.json directly when the reminder service is unreachable (true/false)")]
+        embedded: Option<bool>,
+    },
+
+    #[command(about = "List the recurrence patterns notifications accept, with examples")]
+    RecurrenceHelp,
+
+    #[command(about = "Write a commented .env.example listing every recognized environment variable")]
+    InitConfig {
+        #[arg(short, long, default_value = config::ENV_EXAMPLE_FILE, help = "File to write")]
+        output: PathBuf,
+
+        #[arg(long, help = "Overwrite the file if it exists")]
+        force: bool,
+    },
+
+    #[command(about = "Send notifications queued while the reminder service was unreachable")]
+    SyncNotifications {
+        #[arg(long, help = "Also send queued notifications whose time has already passed")]
+        send_anyway: bool,
+    },
+
+    #[command(about = "Show your recent activity")]
+    History {
+        #[arg(short, long, default_value_t = 20, help = "Number of entries to show")]
+        limit: usize,
+    },
+
+    #[command(about = "Export your account data to a JSON file")]
+    ExportData {
+        #[arg(short, long, help = "Path of the JSON file to write")]
+        output: String,
+
+        #[arg(long, help = "Include the password hash in the export")]
+        include_credentials: bool,
+    },
+
+    #[command(about = "Import account data from an exported JSON file")]
+    ImportData {
+        #[arg(short, long, help = "Path of the exported JSON file")]
+        file: String,
+
+        #[arg(long, value_enum, default_value_t = ConflictPolicy::Skip, help = "What to do when a file with the same name already exists")]
+        on_conflict: ConflictPolicy,
+    },
+}
+
+#[derive(Subcommand)]
+enum StreakAction {
+    #[command(about = "Keep one daily reminder nudging you to study (or remove it with --off)")]
+    EnableNudge {
+        #[arg(short, long, required_unless_present = "off", conflicts_with = "off", help = "Time of day in your timezone (HH:MM)")]
+        time: Option<String>,
+
+        #[arg(long, help = "Remove the daily nudge")]
+        off: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable text
+    Text,
+    /// JSON for scripting
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ConflictPolicy {
+    /// Keep the existing file
+    Skip,
+    /// Replace the existing file's content and metadata
+    Overwrite,
+    /// Import under a new name with a numeric suffix
+    Duplicate,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Session {
     user_id: String,
     username: String,
+    #[serde(default = "Utc::now")]
+    last_used: DateTime<Utc>,
 }
 
 impl Session {
@@ -143,6 +508,10 @@ impl Session {
         }
     }
 
+    fn is_idle(&self) -> bool {
+        Utc::now() - self.last_used > idle_timeout(std::env::var("QUIZ_IDLE_TIMEOUT_MIN").ok().as_deref())
+    }
+
     fn clear() -> Result<()> {
         if Path::new(SESSION_FILE).exists() {
             fs::remove_file(SESSION_FILE)?;
@@ -151,12 +520,305 @@ impl Session {
     }
 }
 
+/// How long a session may go unused, from a `QUIZ_IDLE_TIMEOUT_MIN` value in
+/// minutes. Missing, negative, or out-of-range values give the default.
+fn idle_timeout(minutes: Option<&str>) -> chrono::Duration {
+    minutes
+        .and_then(|v| v.parse::<u64>().ok())
+        .and_then(|minutes| i64::try_from(minutes).ok())
+        .and_then(chrono::Duration::try_minutes)
+        .unwrap_or_else(|| chrono::Duration::minutes(DEFAULT_IDLE_TIMEOUT_MIN))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reminder_url: Option<String>,
+    /// Fall back to the shared storage file when the server is unreachable
+    #[serde(default)]
+    embedded: bool,
+}
+
+impl Config {
+    fn load() -> Result<Self> {
+        if Path::new(CONFIG_FILE).exists() {
+            let data = fs::read_to_string(CONFIG_FILE).context("Failed to read config file")?;
+            serde_json::from_str(&data).context("Failed to parse config file")
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(CONFIG_FILE, json)?;
+        Ok(())
+    }
+}
+
+static API_URL: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+static INSECURE_TLS: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Resolves the reminder service URL once per run: `--reminder-url`, then
+/// `REMINDER_API_URL`, then the config file, then the default.
+fn resolve_api_url(flag: Option<String>) -> Result<String> {
+    let url = match flag.or_else(|| std::env::var("REMINDER_API_URL").ok()) {
+        Some(url) => url,
+        None => Config::load()?.reminder_url.unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+    };
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+fn api_url() -> &'static str {
+    API_URL.get().map(String::as_str).unwrap_or(DEFAULT_API_URL)
+}
+
+fn connect_error() -> String {
+    format!("Failed to connect to reminder service at {}. Is the server running?", api_url())
+}
+
+/// File metadata for list output; content is left out to keep it compact.
+#[derive(Debug, Serialize)]
+struct FileSummary {
+    id: String,
+    filename: String,
+    size: usize,
+    uploaded_at: DateTime<Utc>,
+    tags: Vec<String>,
+    description: Option<String>,
+    starred: bool,
+    archived: bool,
+}
+
+impl From<&UploadedFile> for FileSummary {
+    fn from(file: &UploadedFile) -> Self {
+        Self {
+            id: file.id.clone(),
+            filename: file.filename.clone(),
+            size: file.content.len(),
+            uploaded_at: file.uploaded_at,
+            tags: file.tags.clone(),
+            description: file.description.clone(),
+            starred: file.starred,
+            archived: file.archived,
+        }
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ActivityEntry {
+    user_id: String,
+    action: String,
+    details: String,
+    timestamp: DateTime<Utc>,
+}
+
+/// Appends an entry to the activity log. Logging failures are reported but never
+/// fail the command that triggered them.
+fn log_activity(user_id: &str, action: &str, details: String) {
+    let entry = ActivityEntry {
+        user_id: user_id.to_string(),
+        action: action.to_string(),
+        details,
+        timestamp: Utc::now(),
+    };
+
+    if let Err(e) = append_activity(&entry) {
+        errln!("⚠️  Failed to record activity: {}", e);
+    }
+}
+
+fn append_activity(entry: &ActivityEntry) -> Result<()> {
+    use std::io::Write;
+
+    let max_kb = std::env::var("QUIZ_ACTIVITY_LOG_MAX_KB")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_ACTIVITY_LOG_MAX_KB);
+
+    // Rotate: keep one previous generation alongside the live log
+    if let Ok(meta) = fs::metadata(ACTIVITY_LOG_FILE) {
+        if meta.len() >= max_kb * 1024 {
+            fs::rename(ACTIVITY_LOG_FILE, format!("{}.1", ACTIVITY_LOG_FILE))?;
+        }
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(ACTIVITY_LOG_FILE)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UserDataExport {
+    version: u32,
+    exported_at: DateTime<Utc>,
+    profile: ExportedProfile,
+    files: Vec<UploadedFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedProfile {
+    user_id: String,
+    username: String,
+    created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_hash: Option<String>,
+}
+
+/// Account errors callers can match on instead of parsing message text.
+#[derive(Debug)]
+enum QuizError {
+    EmptyUsername,
+    UsernameTaken(String),
+    WeakPassword { min_length: usize },
+    /// Long enough, but below `STRONG_PASSWORD_SCORE` while strong passwords are enforced
+    GuessablePassword(PasswordStrength),
+    InvalidCredentials,
+    Internal(anyhow::Error),
+}
+
+impl QuizError {
+    fn hint(&self) -> Option<String> {
+        match self {
+            QuizError::UsernameTaken(_) => Some("Pick a different username or log in with: quiz login -u <username> -p <password>".to_string()),
+            QuizError::WeakPassword { min_length } => Some(format!("Choose a password with at least {} characters", min_length)),
+            QuizError::GuessablePassword(strength) => Some(strength.suggestions.join("; ")),
+            QuizError::InvalidCredentials => Some("Check your username and password. New here? Use: quiz signup -u <username> -p <password>".to_string()),
+            QuizError::EmptyUsername | QuizError::Internal(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for QuizError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuizError::EmptyUsername => write!(f, "Username cannot be empty"),
+            QuizError::UsernameTaken(username) => write!(f, "Username '{}' is already taken", username),
+            QuizError::WeakPassword { min_length } => write!(f, "Password must be at least {} characters long", min_length),
+            QuizError::GuessablePassword(strength) => write!(
+                f,
+                "Password is {} ({}/4); at least {}/4 is required",
+                strength.label(),
+                strength.score,
+                STRONG_PASSWORD_SCORE
+            ),
+            QuizError::InvalidCredentials => write!(f, "Invalid username or password"),
+            QuizError::Internal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for QuizError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            QuizError::Internal(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for QuizError {
+    fn from(e: anyhow::Error) -> Self {
+        QuizError::Internal(e)
+    }
+}
+
+impl From<storage::StorageError> for QuizError {
+    fn from(e: storage::StorageError) -> Self {
+        QuizError::Internal(e.into())
+    }
+}
+
+/// A rough guessability estimate from length, character classes, and
+/// obvious patterns, scored 0 (very weak) to 4 (strong).
+#[derive(Debug)]
+struct PasswordStrength {
+    score: u8,
+    /// What would raise the score, best first
+    suggestions: Vec<String>,
+}
+
+impl PasswordStrength {
+    fn estimate(password: &str) -> Self {
+        let length = password.chars().count();
+        let classes = [
+            password.chars().any(|c| c.is_lowercase()),
+            password.chars().any(|c| c.is_uppercase()),
+            password.chars().any(|c| c.is_ascii_digit()),
+            password.chars().any(|c| !c.is_alphanumeric()),
+        ];
+        let class_count = classes.iter().filter(|&&present| present).count();
+
+        let lower = password.to_lowercase();
+        let common = COMMON_PASSWORDS.iter().any(|p| lower.contains(p));
+        let distinct = password.chars().collect::<std::collections::HashSet<_>>().len();
+        let repetitive = distinct * 2 <= length;
+
+        let mut suggestions = Vec::new();
+        if common {
+            suggestions.push("Avoid common passwords and words like 'password' or 'qwerty'".to_string());
+        }
+        if repetitive {
+            suggestions.push("Avoid repeating the same few characters".to_string());
+        }
+        if length < 12 {
+            suggestions.push("Use at least 12 characters (a few unrelated words work well)".to_string());
+        }
+        for (present, suggestion) in classes.iter().zip(["Add lowercase letters", "Add uppercase letters", "Add digits", "Add a symbol such as ! or #"]) {
+            if !present && class_count < 3 {
+                suggestions.push(suggestion.to_string());
+            }
+        }
+
+        let score = if common || repetitive {
+            0
+        } else {
+            let length_points = [8, 12, 16].iter().filter(|&&n| length >= n).count();
+            let class_points = class_count.saturating_sub(1).min(2);
+            (length_points + class_points).min(4) as u8
+        };
+
+        Self { score, suggestions }
+    }
+
+    fn label(&self) -> &'static str {
+        match self.score {
+            0 => "very weak",
+            1 => "weak",
+            2 => "fair",
+            3 => "good",
+            _ => "strong",
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    output::set_plain(output::plain_requested(cli.plain));
+
+    let result = match resolve_api_url(cli.reminder_url) {
+        Ok(url) => {
+            API_URL.get_or_init(|| url);
+            INSECURE_TLS.store(cli.insecure, std::sync::atomic::Ordering::Relaxed);
+            run_command(cli.command).await
+        }
+        Err(e) => Err(e),
+    };
 
-    if let Err(e) = run_command(cli.command).await {
-        eprintln!("❌ Error: {}", e);
+    if let Err(e) = result {
+        errln!("❌ Error: {}", e);
+        if let Some(hint) = e.downcast_ref::<QuizError>().and_then(|qe| qe.hint()) {
+            errln!("💡 {}", hint);
+        }
         std::process::exit(1);
     }
 }
@@ -164,6 +826,24 @@ async fn main() {
 async fn run_command(command: Commands) -> Result<()> {
     let storage = UserStorage::new()?;
 
+    // Opportunistically deliver anything queued offline before talking to the server
+    if matches!(
+        command,
+        Commands::Notify { .. }
+            | Commands::NotifySeries { .. }
+            | Commands::ListNotifications { .. }
+            | Commands::DeleteNotification { .. }
+            | Commands::EditNotification { .. }
+            | Commands::Study { .. }
+            | Commands::SnoozeNotification { .. }
+            | Commands::DoneNotification { .. }
+            | Commands::Streak { action: Some(_) }
+    ) {
+        if let Some(session) = Session::load().filter(|s| !s.is_idle()) {
+            sync_notifications(&session, false, true).await?;
+        }
+    }
+
     match command {
         Commands::Signup { username, password } => {
             signup(&storage, username, password).await?;
@@ -174,16 +854,39 @@ async fn run_command(command: Commands) -> Result<()> {
         Commands::Logout => {
             logout()?;
         }
-        Commands::Upload { file } => {
+        Commands::RenameAccount { new_username, password } => {
+            let session = require_login()?;
+            rename_account(&storage, session, new_username, password).await?;
+        }
+        Commands::Upload { file, stdin: _, name } => {
             let session = require_login()?;
-            upload_file(&storage, &session, file).await?;
+            match (file, name) {
+                (Some(file), _) => upload_file(&storage, &session, file).await?,
+                (None, Some(name)) => upload_stdin(&storage, &session, name).await?,
+                (None, None) => unreachable!("clap requires --file or --stdin --name"),
+            }
         }
-        Commands::List => {
+        Commands::List { format, page, page_size, long } => {
             let session = require_login()?;
-            list_files(&storage, &session).await?;
+            list_files(&storage, &session, format, page, page_size, long).await?;
         }
         Commands::Whoami => {
-            whoami()?;
+            whoami(&storage).await?;
+        }
+        Commands::EditFile { file_id, name, description, starred, archived, content } => {
+            let session = require_login()?;
+            let content = content
+                .map(|path| fs::read_to_string(&path).with_context(|| format!("Failed to read file: {}", path)))
+                .transpose()?;
+            let changes = FileUpdate {
+                filename: name,
+                description,
+                archived,
+                starred,
+                tags: None,
+                content,
+            };
+            edit_file(&storage, &session, file_id, changes).await?;
         }
         Commands::Tag { file_id, tags } => {
             let session = require_login()?;
@@ -193,9 +896,26 @@ async fn run_command(command: Commands) -> Result<()> {
             let session = require_login()?;
             untag_file(&storage, &session, file_id, tag).await?;
         }
-        Commands::FilterByTag { tag } => {
+        Commands::FilterByTag { tag, exclude, all, format, long } => {
+            let session = require_login()?;
+            let match_mode = if all { TagMatch::All } else { TagMatch::Any };
+            filter_files_by_tag(&storage, &session, tag, exclude, match_mode, format, long).await?;
+        }
+        Commands::Search { query, regex, case_sensitive, long } => {
+            let session = require_login()?;
+            search_files(&storage, &session, query, SearchOptions { regex, case_sensitive }, long).await?;
+        }
+        Commands::Diff { file_id_a, file_id_b } => {
+            let session = require_login()?;
+            diff_files(&storage, &session, file_id_a, file_id_b).await?;
+        }
+        Commands::Summary { file_id, tag, terms } => {
+            let session = require_login()?;
+            summarize_files(&storage, &session, file_id, tag, terms).await?;
+        }
+        Commands::SuggestTags { file_id, all_untagged, limit, apply } => {
             let session = require_login()?;
-            filter_files_by_tag(&storage, &session, tag).await?;
+            suggest_file_tags(&storage, &session, file_id, all_untagged, limit, apply).await?;
         }
         Commands::BulkTag { file_ids, tags } => {
             let session = require_login()?;
@@ -205,72 +925,343 @@ async fn run_command(command: Commands) -> Result<()> {
             let session = require_login()?;
             bulk_untag_files(&storage, &session, file_ids, tag).await?;
         }
-        Commands::Notify { title, memo, time, recurrence } => {
+        Commands::BulkDelete { file_ids, tag, yes, dry_run } => {
             let session = require_login()?;
-            create_notification(&session, title, memo, time, recurrence).await?;
+            bulk_delete_files(&storage, &session, file_ids, tag, yes, dry_run).await?;
         }
-        Commands::ListNotifications => {
+        Commands::Notify { title, memo, time, recurrence, once, file, tag, tags, link } => {
             let session = require_login()?;
-            list_notifications(&session).await?;
+            let preferences = storage.get_user_by_username(&session.username).await?
+                .map(|u| u.preferences)
+                .unwrap_or_default();
+            let time = resolve_due_time(&time, user_timezone(&storage, &session).await?)?.to_rfc3339();
+            let recurrence = if once { None } else { recurrence.or(preferences.default_recurrence) };
+            let links = NotificationLinks {
+                file_ids: split_list(file.as_deref()),
+                tags: split_list(tag.as_deref()),
+                labels: split_list(tags.as_deref()),
+                url: link,
+            };
+            create_notification(&storage, &session, title, memo, time, recurrence, links).await?;
         }
-    }
-
-    Ok(())
-}
-
-async fn signup(storage: &UserStorage, username: String, password: String) -> Result<()> {
-    if username.is_empty() {
-        bail!("Username cannot be empty");
-    }
-
-    if password.len() < 6 {
-        bail!("Password must be at least 6 characters long");
-    }
+        Commands::ListNotifications { format, utc, pending, sent, due_within, search, limit } => {
+            let session = require_login()?;
+            let filter = NotificationFilter {
+                sent: if sent { Some(true) } else if pending { Some(false) } else { None },
+                due_within,
+                search,
+            };
+            list_notifications(&storage, &session, format, utc, &filter, limit).await?;
+        }
+        Commands::Study { id } => {
+            let session = require_login()?;
+            study_notification(&storage, &session, id).await?;
+        }
+        Commands::DeleteNotification { id, all_past, series, yes } => {
+            let session = require_login()?;
+            if all_past {
+                delete_matching_notifications(&session, "sent", |r| r.sent, yes).await?;
+            } else if let Some(series) = series {
+                delete_matching_notifications(&session, "series", |r| r.series_id.as_ref() == Some(&series), yes).await?;
+            } else if let Some(id) = id {
+                delete_notification(&session, id, yes).await?;
+            }
+        }
+        Commands::NotifySeries { title, memo, time, offsets } => {
+            let session = require_login()?;
+            let event_time = resolve_due_time(&time, user_timezone(&storage, &session).await?)?;
+            create_notification_series(&session, title, memo, event_time, offsets).await?;
+        }
+        Commands::EditNotification { id, title, memo, time, recurrence } => {
+            let session = require_login()?;
+            let time = match time {
+                Some(time) => Some(resolve_due_time(&time, user_timezone(&storage, &session).await?)?.to_rfc3339()),
+                None => None,
+            };
+            edit_notification(&session, id, title, memo, time, recurrence).await?;
+        }
+        Commands::SnoozeNotification { id, minutes } => {
+            let session = require_login()?;
+            snooze_notification(&session, id, minutes).await?;
+        }
+        Commands::DoneNotification { id } => {
+            let session = require_login()?;
+            complete_notification(&session, id).await?;
+        }
+        Commands::Streak { action } => {
+            let session = require_login()?;
+            match action {
+                None => show_streak(&storage, &session).await?,
+                Some(StreakAction::EnableNudge { time, off }) => {
+                    if off {
+                        disable_nudge(&storage, &session).await?;
+                    } else if let Some(time) = time {
+                        enable_nudge(&storage, &session, &time).await?;
+                    }
+                }
+            }
+        }
+        Commands::SetPrefs { timezone, recurrence, clear_timezone, clear_recurrence } => {
+            let session = require_login()?;
+            set_preferences(&storage, &session, timezone, recurrence, clear_timezone, clear_recurrence).await?;
+        }
+        Commands::GetPrefs => {
+            let session = require_login()?;
+            show_preferences(&storage, &session).await?;
+        }
+        Commands::Config { set_reminder_url, unset_reminder_url, embedded } => {
+            configure(set_reminder_url, unset_reminder_url, embedded)?;
+        }
+        Commands::RecurrenceHelp => {
+            recurrence_help().await;
+        }
+        Commands::InitConfig { output, force } => {
+            config::write_env_example(&output, force)?;
+            outln!("✅ Wrote {}", output.display());
+            outln!("💡 Edit it, then load it into your shell with: set -a; . {}; set +a", output.display());
+        }
+        Commands::SyncNotifications { send_anyway } => {
+            let session = require_login()?;
+            sync_notifications(&session, send_anyway, false).await?;
+        }
+        Commands::History { limit } => {
+            let session = require_login()?;
+            show_history(&session, limit)?;
+        }
+        Commands::ExportData { output, include_credentials } => {
+            let session = require_login()?;
+            export_data(&storage, &session, output, include_credentials).await?;
+        }
+        Commands::ImportData { file, on_conflict } => {
+            let session = require_login()?;
+            import_data(&storage, &session, file, on_conflict).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn signup(storage: &UserStorage, username: String, password: String) -> Result<(), QuizError> {
+    if username.is_empty() {
+        return Err(QuizError::EmptyUsername);
+    }
+
+    if password.len() < MIN_PASSWORD_LENGTH {
+        return Err(QuizError::WeakPassword { min_length: MIN_PASSWORD_LENGTH });
+    }
+
+    let strength = PasswordStrength::estimate(&password);
+    if strength.score < STRONG_PASSWORD_SCORE {
+        if std::env::var("QUIZ_ENFORCE_STRONG").is_ok_and(|v| v == "1") {
+            return Err(QuizError::GuessablePassword(strength));
+        }
+        errln!("⚠️  Weak password ({}, {}/4): {}", strength.label(), strength.score, strength.suggestions.join("; "));
+    }
+
+    if storage.get_user_by_username(&username).await?.is_some() {
+        return Err(QuizError::UsernameTaken(username));
+    }
 
     let password_hash = bcrypt::hash(&password, bcrypt::DEFAULT_COST)
         .context("Failed to hash password")?;
 
     let user = User::new(username.clone(), password_hash);
-    storage.create_user(user.clone()).await?;
+    match storage.create_user(user.clone()).await {
+        Ok(_) => {}
+        // Lost a race with another signup for the same name
+        Err(storage::StorageError::Conflict(_)) => return Err(QuizError::UsernameTaken(username)),
+        Err(e) => return Err(e.into()),
+    }
 
-    println!("✅ Account created successfully!");
-    println!("👤 Username: {}", username);
-    println!("🆔 User ID: {}", user.id);
-    println!("\n💡 You can now log in using: quiz login -u {} -p <password>", username);
+    log_activity(&user.id, "signup", format!("Created account '{}'", username));
+
+    outln!("✅ Account created successfully!");
+    outln!("👤 Username: {}", username);
+    outln!("🆔 User ID: {}", user.id);
+    outln!("🔐 Password strength: {} ({}/4)", strength.label(), strength.score);
+    outln!("\n💡 You can now log in using: quiz login -u {} -p <password>", username);
 
     Ok(())
 }
 
-async fn login(storage: &UserStorage, username: String, password: String) -> Result<()> {
+async fn login(storage: &UserStorage, username: String, password: String) -> Result<(), QuizError> {
     let user = storage.get_user_by_username(&username).await?
-        .ok_or_else(|| anyhow::anyhow!("Invalid username or password"))?;
+        .ok_or(QuizError::InvalidCredentials)?;
 
     let valid = bcrypt::verify(&password, &user.password_hash)
         .context("Failed to verify password")?;
 
     if !valid {
-        bail!("Invalid username or password");
+        return Err(QuizError::InvalidCredentials);
     }
 
     let session = Session {
         user_id: user.id.clone(),
         username: user.username.clone(),
+        last_used: Utc::now(),
     };
 
     session.save()?;
 
-    println!("✅ Login successful!");
-    println!("👤 Welcome back, {}!", user.username);
+    // Best effort: the reminder service may not be running, and quiz works without it
+    let _ = seed_server_timezone(&session, user.preferences.timezone.as_deref(), false).await;
+
+    log_activity(&user.id, "login", "Logged in".to_string());
+
+    outln!("✅ Login successful!");
+    outln!("👤 Welcome back, {}!", user.username);
+
+    Ok(())
+}
+
+async fn rename_account(storage: &UserStorage, mut session: Session, new_username: String, password: String) -> Result<(), QuizError> {
+    if new_username.is_empty() {
+        return Err(QuizError::EmptyUsername);
+    }
+
+    let user = storage.get_user_by_username(&session.username).await?
+        .ok_or_else(|| anyhow::anyhow!("Account '{}' no longer exists", session.username))?;
+
+    let valid = bcrypt::verify(&password, &user.password_hash)
+        .context("Failed to verify password")?;
+
+    if !valid {
+        return Err(QuizError::InvalidCredentials);
+    }
+
+    match storage.rename_user(&user.id, &new_username).await {
+        Ok(()) => {}
+        Err(storage::StorageError::Conflict(_)) => return Err(QuizError::UsernameTaken(new_username)),
+        Err(e) => return Err(e.into()),
+    }
+
+    let old_username = std::mem::replace(&mut session.username, new_username.clone());
+    session.last_used = Utc::now();
+    session.save()?;
+
+    log_activity(&user.id, "rename", format!("Renamed account '{}' to '{}'", old_username, new_username));
+
+    outln!("✅ Username changed!");
+    outln!("👤 {} → {}", old_username, new_username);
+    outln!("\n💡 Study notifications created before the rename still belong to '{}'", old_username);
 
     Ok(())
 }
 
 fn logout() -> Result<()> {
     Session::clear()?;
-    println!("✅ Logged out successfully!");
+    outln!("✅ Logged out successfully!");
     Ok(())
 }
 
+/// Lowercase extensions `upload` accepts, from `QUIZ_ALLOWED_EXTENSIONS`
+/// (comma-separated, leading dots optional) or `txt` when unset.
+fn allowed_extensions() -> Vec<String> {
+    let configured = std::env::var("QUIZ_ALLOWED_EXTENSIONS").unwrap_or_default();
+    let parse = |list: &str| -> Vec<String> {
+        list.split(',')
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect()
+    };
+
+    let extensions = parse(&configured);
+    if extensions.is_empty() {
+        parse(DEFAULT_ALLOWED_EXTENSIONS)
+    } else {
+        extensions
+    }
+}
+
+/// Prints `prompt` and reads a yes/no answer from stdin. Anything but `yes`
+/// or `y` (including end of input, or no answer within `prompt_timeout`)
+/// counts as no. For questions that don't destroy anything; destructive
+/// commands use `confirm`.
+async fn ask(prompt: &str) -> Result<bool> {
+    use std::io::Write;
+
+    outln!("{}", prompt);
+    std::io::stdout().flush()?;
+
+    // A plain thread rather than a blocking task: one still waiting on stdin
+    // after the deadline must not keep the runtime from shutting down
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let mut input = String::new();
+        let _ = tx.send(std::io::stdin().read_line(&mut input).map(|_| input));
+    });
+
+    let answer = match prompt_timeout() {
+        Some(timeout) => match tokio::time::timeout(timeout, rx).await {
+            Ok(answer) => answer,
+            Err(_) => {
+                outln!("\n⌛ No answer within {}s", timeout.as_secs());
+                return Ok(false);
+            }
+        },
+        None => rx.await,
+    };
+    let answer = answer.unwrap_or_else(|_| Ok(String::new()))?.trim().to_lowercase();
+    Ok(answer == "yes" || answer == "y")
+}
+
+/// How long `ask` waits, from `QUIZ_PROMPT_TIMEOUT_SECS`; `0` waits forever.
+fn prompt_timeout() -> Option<std::time::Duration> {
+    let secs = std::env::var("QUIZ_PROMPT_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_PROMPT_TIMEOUT_SECS);
+    (secs > 0).then(|| std::time::Duration::from_secs(secs))
+}
+
+/// What a destructive command needs typed before it goes ahead.
+enum Confirmation<'a> {
+    /// The word `yes`
+    Yes,
+    /// The exact name of the one thing being deleted
+    Name(&'a str),
+}
+
+/// The safety check in front of every destructive command. The caller has
+/// already printed what will be affected; `action` says it in a few words
+/// ("delete 3 file(s)"). `force` (`--yes`/`--force`) skips the question.
+/// Without it, a stdin that isn't a terminal is an error rather than a
+/// silent no, so scripts learn they need the flag.
+fn confirm(action: &str, expected: Confirmation, force: bool) -> Result<bool> {
+    use std::io::IsTerminal;
+
+    let stdin = std::io::stdin();
+    confirm_from(action, expected, force, stdin.is_terminal(), &mut stdin.lock())
+}
+
+/// `confirm` reading the answer from `input`; `interactive` says whether
+/// that is a terminal.
+fn confirm_from(action: &str, expected: Confirmation, force: bool, interactive: bool, input: &mut impl std::io::BufRead) -> Result<bool> {
+    if force {
+        return Ok(true);
+    }
+    if !interactive {
+        bail!("Refusing to {} without confirmation: stdin is not a terminal. Pass --yes (or --force) to go ahead", action);
+    }
+
+    let confirmed = match expected {
+        Confirmation::Yes => read_answer(&format!("❓ Type 'yes' to {}: ", action), input)?.eq_ignore_ascii_case("yes"),
+        Confirmation::Name(name) => read_answer(&format!("❓ Type '{}' to {}: ", name, action), input)? == name,
+    };
+    Ok(confirmed)
+}
+
+fn read_answer(prompt: &str, input: &mut impl std::io::BufRead) -> Result<String> {
+    use std::io::Write;
+
+    outln!("{}", prompt);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
 async fn upload_file(storage: &UserStorage, session: &Session, file_path: String) -> Result<()> {
     // Check if file exists
     if !Path::new(&file_path).exists() {
@@ -279,13 +1270,15 @@ async fn upload_file(storage: &UserStorage, session: &Session, file_path: String
 
     let path = Path::new(&file_path);
     
-    // Validate file type - only .txt files allowed
+    // Validate file type against QUIZ_ALLOWED_EXTENSIONS
     let extension = path.extension()
         .and_then(|e| e.to_str())
         .unwrap_or("");
-    
-    if extension != "txt" {
-        bail!("❌ Unsupported file type: .{}\n💡 Only .txt files are allowed. Please upload a text file.", extension);
+
+    let allowed = allowed_extensions();
+    if !allowed.contains(&extension.to_lowercase()) {
+        let listed: Vec<String> = allowed.iter().map(|e| format!(".{}", e)).collect();
+        bail!("❌ Unsupported file type: .{}\n💡 Allowed file types: {}", extension, listed.join(", "));
     }
 
     // Read file content
@@ -303,36 +1296,66 @@ async fn upload_file(storage: &UserStorage, session: &Session, file_path: String
         .unwrap_or("unknown")
         .to_string();
 
+    finish_upload(storage, session, filename, content, true).await
+}
+
+/// Uploads content piped to stdin under `name`. There is no file to check the
+/// extension of, so a name without one gets `.txt`; the confirmation prompt
+/// is skipped because stdin is already used up.
+async fn upload_stdin(storage: &UserStorage, session: &Session, name: String) -> Result<()> {
+    use std::io::Read;
+
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) {
+        bail!("--name must be a filename without directories, e.g. notes.txt");
+    }
+    let filename = if Path::new(name).extension().is_some() {
+        name.to_string()
+    } else {
+        format!("{}.txt", name)
+    };
+
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .context("Failed to read stdin. Only UTF-8 text can be uploaded.")?;
+
+    if content.trim().is_empty() {
+        bail!("❌ Empty input detected\n💡 Nothing was piped to stdin. Try: cat notes.txt | quiz upload --stdin --name notes.txt");
+    }
+
+    finish_upload(storage, session, filename, content, false).await
+}
+
+/// Shows the preview, asks for confirmation when `confirm` is set, and stores
+/// the file.
+const PREVIEW_CHARS: usize = 200;
+
+/// The first `PREVIEW_CHARS` characters of `content`, ending in `...` when
+/// there is more.
+fn content_preview(content: &str) -> String {
+    match content.char_indices().nth(PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}...", &content[..end]),
+        None => content.to_string(),
+    }
+}
+
+async fn finish_upload(storage: &UserStorage, session: &Session, filename: String, content: String, confirm: bool) -> Result<()> {
     let file_size_bytes = content.len();
     let file_size_kb = file_size_bytes as f64 / 1024.0;
     
     // Show file preview and metadata
-    println!("\n📄 File Preview");
-    println!("═══════════════════════════════════════");
-    println!("📝 Filename: {}", filename);
-    println!("📊 Size: {} characters ({:.2} KB)", file_size_bytes, file_size_kb);
-    println!("═══════════════════════════════════════");
-    println!("\n📖 Content Preview (first 200 characters):");
-    println!("{}", 
-        if content.len() > 200 {
-            format!("{}...", &content[..200])
-        } else {
-            content.clone()
-        }
-    );
-    println!("═══════════════════════════════════════\n");
-
-    // Ask for confirmation
-    println!("❓ Confirm upload of this file? (yes/no): ");
-    use std::io::{self, Write};
-    io::stdout().flush()?;
-    
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    
-    let confirmed = input.trim().to_lowercase();
-    if confirmed != "yes" && confirmed != "y" {
-        println!("❌ Upload cancelled");
+    outln!("\n📄 File Preview");
+    outln!("═══════════════════════════════════════");
+    outln!("📝 Filename: {}", filename);
+    outln!("📊 Size: {} characters ({:.2} KB)", file_size_bytes, file_size_kb);
+    outln!("═══════════════════════════════════════");
+    outln!("\n📖 Content Preview (first {} characters):", PREVIEW_CHARS);
+    outln!("{}", content_preview(&content));
+    outln!("═══════════════════════════════════════\n");
+
+    if confirm && !ask("❓ Confirm upload of this file? (yes/no): ").await? {
+        outln!("❌ Upload cancelled");
         return Ok(());
     }
 
@@ -344,53 +1367,168 @@ async fn upload_file(storage: &UserStorage, session: &Session, file_path: String
     );
 
     storage.add_file(uploaded_file.clone()).await?;
+    log_activity(&session.user_id, "upload", format!("Uploaded '{}' ({})", filename, uploaded_file.id));
 
-    println!("\n✅ File uploaded successfully!");
-    println!("📄 Filename: {}", filename);
-    println!("🆔 File ID: {}", uploaded_file.id);
-    println!("📊 Size: {} characters", content.len());
-    println!("\n💡 Use 'quiz list' to see all your uploaded files");
+    outln!("\n✅ File uploaded successfully!");
+    outln!("📄 Filename: {}", filename);
+    outln!("🆔 File ID: {}", uploaded_file.id);
+    outln!("📊 Size: {} characters", content.len());
+    outln!("\n💡 Use 'quiz list' to see all your uploaded files");
 
     Ok(())
 }
 
-async fn list_files(storage: &UserStorage, session: &Session) -> Result<()> {
+fn print_file_entry(number: usize, file: &UploadedFile) {
+    let mut details = vec![
+        format!("🆔 ID: {}", file.id),
+        format!("📊 Size: {} characters", file.content.len()),
+        format!("⏰ Uploaded: {}", file.uploaded_at.format("%Y-%m-%d %H:%M:%S UTC")),
+    ];
+    if !file.tags.is_empty() {
+        details.push(format!("🏷️  Tags: {}", file.tags.join(", ")));
+    }
+    output::record(&format!("{}. 📄 {}", number, file.filename), &details);
+}
+
+const FILE_NAME_WIDTH: usize = 32;
+const FILE_TAGS_WIDTH: usize = 24;
+const FILE_TABLE_HEADERS: &[&str] = &["#", "Name", "ID", "Size", "Uploaded", "Tags", "Flags"];
+
+/// The cells of one file table row, in `FILE_TABLE_HEADERS` order.
+fn file_row(number: usize, file: &UploadedFile) -> Vec<String> {
+    let flags: Vec<&str> = [(file.starred, "starred"), (file.archived, "archived")]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect();
+
+    vec![
+        number.to_string(),
+        truncate_cell(&file.filename, FILE_NAME_WIDTH),
+        file.id.clone(),
+        file.content.len().to_string(),
+        file.uploaded_at.format("%Y-%m-%d %H:%M").to_string(),
+        elide_tags(&file.tags, FILE_TAGS_WIDTH),
+        flags.join(", "),
+    ]
+}
+
+/// `text` cut to `width` characters, ending in `…` when anything was cut.
+fn truncate_cell(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(width.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+/// As many leading tags as fit in `width`, then `+N` for the rest.
+fn elide_tags(tags: &[String], width: usize) -> String {
+    let all = tags.join(", ");
+    if all.chars().count() <= width || tags.len() == 1 {
+        return truncate_cell(&all, width);
+    }
+
+    (1..tags.len())
+        .rev()
+        .map(|shown| format!("{} +{}", tags[..shown].join(", "), tags.len() - shown))
+        .find(|text| text.chars().count() <= width)
+        .unwrap_or_else(|| format!("+{}", tags.len()))
+}
+
+fn print_file_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut table = Table::new();
+    table.add_row(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    for row in rows {
+        table.add_row(Row::new(row.iter().map(|cell| Cell::new(cell)).collect()));
+    }
+    output::print_table(table);
+    outln!();
+}
+
+async fn list_files(storage: &UserStorage, session: &Session, format: OutputFormat, page: usize, page_size: usize, long: bool) -> Result<()> {
+    if page == 0 || page_size == 0 {
+        bail!("--page and --page-size must be at least 1");
+    }
+
     let files = storage.get_user_files(&session.user_id).await?;
 
+    let total_pages = files.len().div_ceil(page_size).max(1);
+    if page > total_pages {
+        bail!("Page {} is out of range; there {} {} page(s)", page, if total_pages == 1 { "is" } else { "are" }, total_pages);
+    }
+    let offset = (page - 1) * page_size;
+    let page_files = &files[offset..(offset + page_size).min(files.len())];
+
+    if format == OutputFormat::Json {
+        let summaries: Vec<FileSummary> = page_files.iter().map(FileSummary::from).collect();
+        return print_json(&summaries);
+    }
+
     if files.is_empty() {
-        println!("📭 No files uploaded yet.");
-        println!("💡 Use 'quiz upload -f <file>' to upload a text file");
+        outln!("📭 No files uploaded yet.");
+        outln!("💡 Use 'quiz upload -f <file>' to upload a text file");
         return Ok(());
     }
 
-    println!("📚 Your uploaded files:\n");
-    for (i, file) in files.iter().enumerate() {
-        println!("{}. 📄 {}", i + 1, file.filename);
-        println!("   🆔 ID: {}", file.id);
-        println!("   📊 Size: {} characters", file.content.len());
-        println!("   ⏰ Uploaded: {}", file.uploaded_at.format("%Y-%m-%d %H:%M:%S UTC"));
-        if !file.tags.is_empty() {
-            println!("   🏷️  Tags: {}", file.tags.join(", "));
+    outln!("📚 Your uploaded files:\n");
+    if long {
+        for (i, file) in page_files.iter().enumerate() {
+            print_file_entry(offset + i + 1, file);
         }
-        println!();
+    } else {
+        print_file_table(FILE_TABLE_HEADERS, page_files.iter().enumerate().map(|(i, file)| file_row(offset + i + 1, file)).collect());
+    }
+
+    outln!("📖 Page {} of {} ({} file(s))", page, total_pages, files.len());
+    if page < total_pages {
+        outln!("💡 Next page: quiz list --page {} --page-size {}", page + 1, page_size);
     }
 
+    let stats = storage.get_user_stats(&session.user_id).await?;
+    outln!("💾 Storage: {} of {} used", format_bytes(stats.bytes_used), format_bytes(stats.quota_bytes));
+
     Ok(())
 }
 
 fn require_login() -> Result<Session> {
-    Session::load()
-        .ok_or_else(|| anyhow::anyhow!("You must be logged in. Use: quiz login -u <username> -p <password>"))
+    let mut session = Session::load()
+        .ok_or_else(|| anyhow::anyhow!("You must be logged in. Use: quiz login -u <username> -p <password>"))?;
+
+    if session.is_idle() {
+        Session::clear()?;
+        bail!("Your session expired after inactivity. Use: quiz login -u <username> -p <password>");
+    }
+
+    session.last_used = Utc::now();
+    session.save()?;
+    Ok(session)
 }
 
-fn whoami() -> Result<()> {
-    if let Some(session) = Session::load() {
-        println!("👤 Logged in as: {}", session.username);
-        println!("🆔 User ID: {}", session.user_id);
+async fn whoami(storage: &UserStorage) -> Result<()> {
+    if let Some(session) = Session::load().filter(|s| !s.is_idle()) {
+        let stats = storage.get_user_stats(&session.user_id).await?;
+        outln!("👤 Logged in as: {}", session.username);
+        outln!("🆔 User ID: {}", session.user_id);
+        outln!("📚 Files: {}", stats.file_count);
+        outln!("💾 Storage: {} of {} used", format_bytes(stats.bytes_used), format_bytes(stats.quota_bytes));
     } else {
-        println!("❌ Not logged in");
-        println!("💡 Use 'quiz login -u <username> -p <password>' to log in");
+        outln!("❌ Not logged in");
+        outln!("💡 Use 'quiz login -u <username> -p <password>' to log in");
+    }
+    Ok(())
+}
+
+async fn edit_file(storage: &UserStorage, session: &Session, file_id: String, changes: FileUpdate) -> Result<()> {
+    let file = storage.update_file(&file_id, &session.user_id, changes).await?;
+
+    outln!("✅ File '{}' updated!", file.filename);
+    if let Some(description) = &file.description {
+        outln!("📝 Description: {}", description);
     }
+    outln!("⭐ Starred: {}", if file.starred { "yes" } else { "no" });
+    outln!("📦 Archived: {}", if file.archived { "yes" } else { "no" });
+    outln!("📊 Size: {} characters", file.content.len());
+
     Ok(())
 }
 
@@ -398,274 +1536,2270 @@ async fn tag_file(storage: &UserStorage, session: &Session, file_id: String, tag
     let file = storage.get_file_by_id(&file_id, &session.user_id).await?
         .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id))?;
 
-    let tag_list: Vec<String> = tags.split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    if tag_list.is_empty() {
-        bail!("No valid tags provided");
-    }
+    let tag_list = parse_tags(&tags)?;
 
     for tag in &tag_list {
         storage.add_tag_to_file(&file_id, &session.user_id, tag.clone()).await?;
     }
 
-    println!("✅ Tags added to file '{}'!", file.filename);
-    println!("🏷️  Tags: {}", tag_list.join(", "));
-    println!("\n💡 Use 'quiz list' to see all your files and tags");
+    log_activity(&session.user_id, "tag", format!("Tagged '{}' with {}", file.filename, tag_list.join(", ")));
+
+    outln!("✅ Tags added to file '{}'!", file.filename);
+    outln!("🏷️  Tags: {}", tag_list.join(", "));
+    outln!("\n💡 Use 'quiz list' to see all your files and tags");
 
     Ok(())
 }
 
+/// A comma-separated tag list, normalized and validated. Blank entries
+/// between commas are ignored.
+fn parse_tags(tags: &str) -> Result<Vec<String>> {
+    let raw: Vec<String> = tags.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+
+    if raw.is_empty() {
+        bail!("No valid tags provided");
+    }
+
+    Ok(validate_tags(&raw)?)
+}
+
 async fn untag_file(storage: &UserStorage, session: &Session, file_id: String, tag: String) -> Result<()> {
     let file = storage.get_file_by_id(&file_id, &session.user_id).await?
         .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id))?;
+    let tag = normalize_tag(&tag);
 
     storage.remove_tag_from_file(&file_id, &session.user_id, &tag).await?;
 
-    println!("✅ Tag '{}' removed from file '{}'!", tag, file.filename);
-
-    Ok(())
-}
+    log_activity(&session.user_id, "untag", format!("Removed tag '{}' from '{}'", tag, file.filename));
 
-#[derive(Debug, Serialize)]
-struct CreateReminderRequest {
-    message: String,
-    due_time: String,
-    username: Option<String>,
-    recurrence: Option<String>,
-}
+    outln!("✅ Tag '{}' removed from file '{}'!", tag, file.filename);
 
-#[derive(Debug, Deserialize)]
-struct CreateReminderResponse {
-    id: String,
-    message: String,
-    due_time: String,
+    Ok(())
 }
 
-#[derive(Debug, Deserialize)]
-struct Reminder {
-    id: String,
-    message: String,
-    due_time: String,
-    sent: bool,
-    username: Option<String>,
+/// Files and tags a study notification points at, plus the notification's own tags.
+#[derive(Debug, Default)]
+struct NotificationLinks {
+    file_ids: Vec<String>,
+    tags: Vec<String>,
+    labels: Vec<String>,
+    /// External link, as opposed to linked files and tags
+    url: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct RemindersResponse {
-    reminders: Vec<Reminder>,
+/// The reminder a study notification is stored as: the title and memo both
+/// as structured fields and folded into the message for older readers.
+fn notification_request(
+    session: &Session,
+    title: &str,
+    memo: Option<&str>,
+    time: &str,
+    recurrence: Option<String>,
+    links: &NotificationLinks,
+) -> CreateReminderRequest {
+    CreateReminderRequest {
+        message: build_notification_message(title, memo),
+        due_time: time.to_string(),
+        username: Some(session.username.clone()),
+        recurrence,
+        title: Some(title.to_string()),
+        description: memo.map(str::to_string),
+        series_id: None,
+        linked_file_ids: links.file_ids.clone(),
+        linked_tags: links.tags.clone(),
+        tags: links.labels.clone(),
+        link: links.url.clone(),
+        ..Default::default()
+    }
 }
 
 async fn create_notification(
+    storage: &UserStorage,
     session: &Session,
     title: String,
     memo: Option<String>,
     time: String,
     recurrence: Option<String>,
+    links: NotificationLinks,
 ) -> Result<()> {
-    let message = if let Some(m) = &memo {
-        format!("📚 {}\n📝 {}", title, m)
-    } else {
-        format!("📚 {}", title)
-    };
-    
-    let request = CreateReminderRequest {
-        message,
-        due_time: time.clone(),
-        username: Some(session.username.clone()),
-        recurrence: recurrence.clone(),
-    };
-
-    let client = reqwest::Client::new();
-    let response = client
-        .post("http://localhost:3000/reminders")
-        .json(&request)
-        .send()
-        .await
-        .context("Failed to connect to reminder service. Is the server running?")?;
+    let mut linked_names = Vec::new();
+    for file_id in &links.file_ids {
+        let file = storage.get_file_by_id(file_id, &session.user_id).await?
+            .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id))?;
+        linked_names.push(file.filename);
+    }
+    for tag in &links.tags {
+        if storage.get_files_by_tag(&session.user_id, tag).await?.is_empty() {
+            bail!("No files are tagged '{}'", tag);
+        }
+    }
 
-    if !response.status().is_success() {
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        bail!("Failed to create notification: {}", error_text);
+    if let Some(ref url) = links.url {
+        models::validate_link(url).map_err(|e| anyhow::anyhow!(e))?;
     }
 
-    let created: CreateReminderResponse = response.json().await
-        .context("Failed to parse response")?;
+    let request = notification_request(session, &title, memo.as_deref(), &time, recurrence.clone(), &links);
+
+    let (created_id, recurrence, warnings) = match reminder_client(session).create(&request).await {
+        Ok(created) => (created.id, created.reminder.recurrence, created.warnings),
+        Err(e) if e.is_connect() && embedded_enabled() => {
+            outln!("📂 Reminder service at {} is unreachable; writing to local storage (embedded mode)", api_url());
+            (create_reminder_embedded(Path::new(storage::STORAGE_FILE), &request).await?, recurrence, Vec::new())
+        }
+        Err(e) if e.is_connect() => {
+            enqueue_notification(request)?;
+            log_activity(&session.user_id, "notify", format!("Queued notification '{}' while offline", title));
+            outln!("📥 Reminder service at {} is unreachable; notification '{}' was queued", api_url(), title);
+            outln!("💡 Run 'quiz sync-notifications' once the server is back up");
+            return Ok(());
+        }
+        Err(e) => return Err(api_error(e)).context("Failed to create notification"),
+    };
+
+    log_activity(&session.user_id, "notify", format!("Created notification '{}' ({})", title, created_id));
 
-    println!("✅ Study notification created successfully!");
-    println!("📚 Title: {}", title);
+    outln!("✅ Study notification created successfully!");
+    outln!("📚 Title: {}", title);
     if let Some(m) = memo {
-        println!("📝 Memo: {}", m);
+        outln!("📝 Memo: {}", m);
     }
-    println!("⏰ Scheduled for: {}", time);
+    outln!("⏰ Scheduled for: {}", time);
     if let Some(rec) = recurrence {
-        println!("🔄 Recurrence: {}", rec);
+        outln!("🔄 Recurrence: {}", rec);
+    }
+    for warning in &warnings {
+        outln!("⚠️  {}", warning);
+    }
+    if !linked_names.is_empty() {
+        outln!("📄 Files: {}", linked_names.join(", "));
     }
-    println!("🆔 Notification ID: {}", created.id);
-    println!("\n💡 The reminder service will notify you at the scheduled time!");
+    if !links.tags.is_empty() {
+        outln!("🔗 Linked tags: {}", links.tags.join(", "));
+    }
+    if !links.labels.is_empty() {
+        outln!("🏷️  Tags: {}", links.labels.join(", "));
+    }
+    if let Some(ref url) = links.url {
+        outln!("🌐 Link: {}", url);
+    }
+    outln!("🆔 Notification ID: {}", created_id);
+    outln!("\n💡 The reminder service will notify you at the scheduled time!");
 
     Ok(())
 }
 
-async fn list_notifications(session: &Session) -> Result<()> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get("http://localhost:3000/reminders")
-        .send()
-        .await
-        .context("Failed to connect to reminder service. Is the server running?")?;
-
-    if !response.status().is_success() {
-        bail!("Failed to fetch notifications");
+async fn create_notification_series(
+    session: &Session,
+    title: String,
+    memo: Option<String>,
+    event_time: DateTime<Utc>,
+    offsets: String,
+) -> Result<()> {
+    let offsets = durations::parse_offsets(&offsets)?;
+    if offsets.is_empty() {
+        bail!("No valid offsets provided");
     }
 
-    let response_data: RemindersResponse = response.json().await
-        .context("Failed to parse response")?;
-
-    // Filter by current user
-    let user_reminders: Vec<&Reminder> = response_data.reminders
-        .iter()
-        .filter(|r| r.username.as_ref() == Some(&session.username))
-        .collect();
+    let series_id = ids::next_id();
+    let now = Utc::now();
+    let client = reminder_client(session);
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Offset"),
+        Cell::new("Scheduled"),
+        Cell::new("ID"),
+    ]));
+    let mut created = 0;
+
+    for (label, offset) in offsets {
+        let due_time = event_time - offset;
+        if due_time <= now {
+            outln!("⏭️  Skipping {} before: {} is already in the past", label, due_time.format("%Y-%m-%d %H:%M:%S UTC"));
+            continue;
+        }
 
-    if user_reminders.is_empty() {
-        println!("📭 No study notifications found");
-        println!("💡 Use 'quiz notify' to create a study reminder");
-        return Ok(());
+        let reminder_title = format!("{} (in {})", title, label);
+        let request = CreateReminderRequest {
+            message: build_notification_message(&reminder_title, memo.as_deref()),
+            due_time: due_time.to_rfc3339(),
+            username: Some(session.username.clone()),
+            recurrence: None,
+            title: Some(reminder_title),
+            description: memo.clone(),
+            series_id: Some(series_id.clone()),
+            ..Default::default()
+        };
+
+        let result = client.create(&request).await
+            .map_err(api_error)
+            .with_context(|| format!("Failed to create notification for {} before", label))?;
+
+        table.add_row(Row::new(vec![
+            Cell::new(&label),
+            Cell::new(&due_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            Cell::new(&result.id),
+        ]));
+        created += 1;
     }
 
-    println!("📚 Your Study Notifications:\n");
-    for (i, reminder) in user_reminders.iter().enumerate() {
-        println!("{}. 📌 Notification", i + 1);
-        println!("   🆔 ID: {}", reminder.id);
-        println!("   📝 Message: {}", reminder.message);
-        println!("   ⏰ Scheduled: {}", reminder.due_time);
-        println!("   📊 Status: {}", if reminder.sent { "✅ Sent" } else { "⏳ Pending" });
-        println!();
+    if created == 0 {
+        bail!("Every offset falls in the past; nothing was scheduled");
     }
 
+    log_activity(&session.user_id, "notify", format!("Created series '{}' with {} notification(s) ({})", title, created, series_id));
+
+    outln!("✅ Scheduled {} notification(s) before '{}'", created, title);
+    output::print_table(table);
+    outln!("🔗 Series ID: {}", series_id);
+    outln!("\n💡 Cancel the whole series with: quiz delete-notification --series {}", series_id);
+
     Ok(())
 }
 
-async fn filter_files_by_tag(storage: &UserStorage, session: &Session, tag: String) -> Result<()> {
-    let all_files = storage.get_user_files(&session.user_id).await?;
-    
-    let filtered_files: Vec<&UploadedFile> = all_files
-        .iter()
-        .filter(|f| f.tags.contains(&tag))
-        .collect();
+#[derive(Debug, Serialize, Deserialize)]
+struct QueuedNotification {
+    queued_at: DateTime<Utc>,
+    request: CreateReminderRequest,
+}
 
-    if filtered_files.is_empty() {
-        println!("📭 No files found with tag '{}'", tag);
-        println!("💡 Use 'quiz tag' to add tags to your files");
-        return Ok(());
+fn load_notification_queue() -> Result<Vec<QueuedNotification>> {
+    if Path::new(NOTIFICATION_QUEUE_FILE).exists() {
+        let data = fs::read_to_string(NOTIFICATION_QUEUE_FILE).context("Failed to read notification queue")?;
+        serde_json::from_str(&data).context("Failed to parse notification queue")
+    } else {
+        Ok(Vec::new())
     }
+}
 
-    println!("📚 Files with tag '{}':\n", tag);
-    for (i, file) in filtered_files.iter().enumerate() {
-        println!("{}. 📄 {}", i + 1, file.filename);
-        println!("   🆔 ID: {}", file.id);
-        println!("   📊 Size: {} characters", file.content.len());
-        println!("   ⏰ Uploaded: {}", file.uploaded_at.format("%Y-%m-%d %H:%M:%S UTC"));
-        if !file.tags.is_empty() {
-            println!("   🏷️  Tags: {}", file.tags.join(", "));
+fn save_notification_queue(queue: &[QueuedNotification]) -> Result<()> {
+    if queue.is_empty() {
+        if Path::new(NOTIFICATION_QUEUE_FILE).exists() {
+            fs::remove_file(NOTIFICATION_QUEUE_FILE)?;
         }
-        println!();
+        return Ok(());
     }
-
+    let json = serde_json::to_string_pretty(queue)?;
+    fs::write(NOTIFICATION_QUEUE_FILE, json)?;
     Ok(())
 }
 
-async fn bulk_tag_files(storage: &UserStorage, session: &Session, file_ids: String, tags: String) -> Result<()> {
-    let file_id_list: Vec<String> = file_ids.split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+fn enqueue_notification(request: CreateReminderRequest) -> Result<()> {
+    let mut queue = load_notification_queue()?;
+    queue.push(QueuedNotification {
+        queued_at: Utc::now(),
+        request,
+    });
+    save_notification_queue(&queue)
+}
 
-    let tag_list: Vec<String> = tags.split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
-        .collect();
+fn embedded_enabled() -> bool {
+    Config::load().map(|c| c.embedded).unwrap_or(false)
+}
 
-    if file_id_list.is_empty() {
-        bail!("No valid file IDs provided");
+/// Inserts a reminder straight into the storage file at `storage_path`,
+/// refusing while a server (or another embedded client) holds its lock.
+async fn create_reminder_embedded(storage_path: &Path, request: &CreateReminderRequest) -> Result<String> {
+    let _lock = storage::StorageLock::acquire_for(storage_path)?;
+
+    let due_time = due_time::parse_due_time(&request.due_time, &due_time::DueTimeRules::default())
+        .with_context(|| format!("Invalid due time '{}'", request.due_time))?;
+    if let Some(ref recurrence) = request.recurrence {
+        models::parse_recurrence_rule(recurrence).map_err(|e| anyhow::anyhow!(e))?;
     }
 
-    if tag_list.is_empty() {
-        bail!("No valid tags provided");
+    let mut reminder = models::Reminder::new(
+        request.message.clone(),
+        due_time,
+        request.username.clone(),
+        request.recurrence.clone(),
+    );
+    reminder.recurrence_anchor = request.recurrence_anchor.unwrap_or_default();
+    reminder.missed_policy = request.missed_policy.unwrap_or_default();
+    reminder.color = request.color.clone();
+    reminder.title = request.title.clone();
+    reminder.description = request.description.clone();
+    reminder.series_id = request.series_id.clone();
+    reminder.linked_file_ids = request.linked_file_ids.clone();
+    reminder.linked_tags = request.linked_tags.clone();
+    reminder.tags = request.tags.clone();
+    reminder.link = request.link.clone();
+    reminder.ignore_blackout = request.ignore_blackout;
+    reminder.group_id = request.group_id.clone();
+    reminder.source = Some(CLIENT_SOURCE.to_string());
+
+    let reminders = storage::ReminderStorage::open(storage_path)?;
+    Ok(reminders.add_reminder(reminder).await?.id)
+}
+
+/// Every reminder read straight from the storage file at `storage_path`.
+async fn list_reminders_embedded(storage_path: &Path) -> Result<Vec<Reminder>> {
+    let _lock = storage::StorageLock::acquire_for(storage_path)?;
+
+    Ok(storage::ReminderStorage::open(storage_path)?.get_all_reminders().await?)
+}
+
+/// Sends the session user's queued notifications, keeping anything that fails.
+/// `automatic` runs quietly and gives up on the first connection failure.
+async fn sync_notifications(session: &Session, send_anyway: bool, automatic: bool) -> Result<()> {
+    let queue = load_notification_queue()?;
+    let (mine, mut remaining): (Vec<_>, Vec<_>) = queue
+        .into_iter()
+        .partition(|q| q.request.username.as_ref() == Some(&session.username));
+
+    if mine.is_empty() {
+        if !automatic {
+            outln!("📭 No queued notifications");
+        }
+        return Ok(());
     }
 
-    let mut success_count = 0;
-    let mut failed_files = Vec::new();
+    let client = reminder_client(session);
+    let now = Utc::now();
+    let mut delivered = 0;
+    let mut pending = mine.into_iter();
 
-    for file_id in &file_id_list {
-        // Check if file exists
-        if let Some(_file) = storage.get_file_by_id(file_id, &session.user_id).await? {
-            for tag in &tag_list {
-                storage.add_tag_to_file(file_id, &session.user_id, tag.clone()).await?;
+    while let Some(item) = pending.next() {
+        let title = item.request.title.clone().unwrap_or_else(|| item.request.message.clone());
+
+        let overdue = due_time::parse_due_time(&item.request.due_time, &due_time::DueTimeRules::default()).is_ok_and(|t| t <= now);
+        if overdue && !send_anyway {
+            if !automatic {
+                outln!("⏰ '{}' was due at {} and is still queued (use --send-anyway to send it)", title, item.request.due_time);
+            }
+            remaining.push(item);
+            continue;
+        }
+
+        match client.create(&item.request).await {
+            Ok(created) => {
+                outln!("✅ Sent queued notification '{}' ({})", title, created.id);
+                delivered += 1;
+            }
+            Err(e) if !e.is_connect() => {
+                outln!("❌ Failed to send '{}': {}", title, e);
+                remaining.push(item);
+            }
+            Err(e) => {
+                if !automatic {
+                    outln!("❌ Failed to send '{}': {}", title, e);
+                }
+                remaining.push(item);
+                // Server still down: keep the rest without trying each one
+                remaining.extend(pending.by_ref());
             }
-            success_count += 1;
-        } else {
-            failed_files.push(file_id.clone());
         }
     }
 
-    println!("✅ Bulk tag operation completed!");
-    println!("📊 Successfully tagged {} file(s)", success_count);
-    println!("🏷️  Tags added: {}", tag_list.join(", "));
-    
-    if !failed_files.is_empty() {
-        println!("⚠️  Failed to find {} file(s): {}", failed_files.len(), failed_files.join(", "));
+    save_notification_queue(&remaining)?;
+
+    if delivered > 0 {
+        log_activity(&session.user_id, "notify", format!("Sent {} queued notification(s)", delivered));
+    }
+    if !automatic || delivered > 0 {
+        let still_queued = remaining.iter().filter(|q| q.request.username.as_ref() == Some(&session.username)).count();
+        outln!("📊 Sent {} queued notification(s), {} still queued\n", delivered, still_queued);
     }
 
     Ok(())
 }
 
-async fn bulk_untag_files(storage: &UserStorage, session: &Session, file_ids: String, tag: String) -> Result<()> {
-    let file_id_list: Vec<String> = file_ids.split(',')
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
+fn parse_duration(input: &str) -> Result<chrono::Duration, String> {
+    durations::parse_offset(input).map_err(|e| e.to_string())
+}
+
+/// Which of the user's notifications `list-notifications` shows.
+struct NotificationFilter {
+    /// `Some(true)` for sent, `Some(false)` for pending; `None` shows upcoming ones
+    sent: Option<bool>,
+    due_within: Option<chrono::Duration>,
+    search: Option<String>,
+}
+
+impl NotificationFilter {
+    /// The part of the filter the reminder service applies itself.
+    fn query(&self, username: &str, now: DateTime<Utc>) -> ReminderListQuery {
+        ReminderListQuery {
+            username: Some(username.to_string()),
+            sent: self.sent,
+            due_after: self.due_within.map(|_| now),
+            due_before: self.due_within.map(|within| now + within),
+            ..Default::default()
+        }
+    }
+
+    /// The whole filter, applied locally as well so embedded mode and older
+    /// servers that ignore parts of the query give the same answer.
+    fn matches(&self, reminder: &Reminder, username: &str, now: DateTime<Utc>) -> bool {
+        let state_matches = match self.sent {
+            Some(sent) => reminder.sent == sent,
+            None => !reminder.sent && reminder.due_time > now,
+        };
+
+        reminder.username.as_deref() == Some(username)
+            && state_matches
+            && self.due_within.is_none_or(|within| reminder.due_time >= now && reminder.due_time <= now + within)
+            && self.search.as_deref().is_none_or(|text| reminder.matches_text(text))
+    }
+}
+
+async fn list_notifications(
+    storage: &UserStorage,
+    session: &Session,
+    format: OutputFormat,
+    utc: bool,
+    filter: &NotificationFilter,
+    limit: Option<usize>,
+) -> Result<()> {
+    let now = Utc::now();
+    let reminders = match reminder_client(session).list_matching(&filter.query(&session.username, now)).await {
+        Ok(reminders) => reminders,
+        Err(e) if e.is_connect() && embedded_enabled() => {
+            errln!("📂 Reminder service at {} is unreachable; reading local storage (embedded mode)", api_url());
+            list_reminders_embedded(Path::new(storage::STORAGE_FILE)).await?
+        }
+        Err(e) => return Err(api_error(e)).context("Failed to fetch notifications"),
+    };
+
+    let mut user_reminders: Vec<&Reminder> = reminders
+        .iter()
+        .filter(|r| filter.matches(r, &session.username, now))
         .collect();
+    user_reminders.sort_by_key(|r| r.due_time);
+    let total = user_reminders.len();
+    user_reminders.truncate(limit.unwrap_or(usize::MAX));
 
-    if file_id_list.is_empty() {
-        bail!("No valid file IDs provided");
+    if format == OutputFormat::Json {
+        return print_json(&user_reminders);
     }
 
-    let mut success_count = 0;
-    let mut failed_files = Vec::new();
-    let mut tag_not_found = Vec::new();
+    if user_reminders.is_empty() {
+        outln!("📭 No study notifications found");
+        outln!("💡 Use 'quiz notify' to create a study reminder");
+        return Ok(());
+    }
 
-    for file_id in &file_id_list {
-        if let Some(file) = storage.get_file_by_id(file_id, &session.user_id).await? {
-            if file.tags.contains(&tag) {
-                storage.remove_tag_from_file(file_id, &session.user_id, &tag).await?;
-                success_count += 1;
-            } else {
-                tag_not_found.push(file.filename.clone());
+    outln!("📚 Your Study Notifications:\n");
+    for (i, reminder) in user_reminders.iter().enumerate() {
+        let (title, memo) = title_and_memo(reminder);
+        let mut details: Vec<String> = memo.iter().flat_map(|memo| memo.lines()).map(|line| format!("   {}", line)).collect();
+        details.push(format!("🆔 ID: {}", reminder.id));
+        let due = reminder.due_time;
+        if utc {
+            details.push(format!("⏰ Scheduled: {}", due.to_rfc3339()));
+        } else {
+            let local = due.with_timezone(&chrono::Local);
+            details.push(format!("⏰ Scheduled: {} ({})", local.format("%Y-%m-%d %H:%M %Z"), format_relative(due, now)));
+        }
+        if let Some(ref recurrence) = reminder.recurrence {
+            details.push(format!("🔄 Recurrence: {}", recurrence));
+        }
+        if !reminder.linked_file_ids.is_empty() {
+            let mut names = Vec::new();
+            for file_id in &reminder.linked_file_ids {
+                names.push(linked_file_name(storage, session, file_id).await?);
             }
+            details.push(format!("📄 Files: {}", names.join(", ")));
+        }
+        if !reminder.linked_tags.is_empty() {
+            details.push(format!("🔗 Linked tags: {}", reminder.linked_tags.join(", ")));
+        }
+        if !reminder.tags.is_empty() {
+            details.push(format!("🏷️  Tags: {}", reminder.tags.join(", ")));
+        }
+        if let Some(ref link) = reminder.link {
+            details.push(format!("🌐 Link: {}", link));
+        }
+        details.push(format!("📊 Status: {}", notification_status(reminder, utc, now)));
+        output::record(&format!("{}. 📌 \x1b[1m{}\x1b[0m", i + 1, title), &details);
+    }
+
+    outln!("📊 Showing {} of {} notification(s)", user_reminders.len(), total);
+
+    Ok(())
+}
+
+fn notification_status(reminder: &Reminder, utc: bool, now: DateTime<Utc>) -> String {
+    if reminder.completed {
+        return "✔️  Completed".to_string();
+    }
+    if let Some(until) = reminder.snoozed_until {
+        let until = if utc {
+            until.to_rfc3339()
         } else {
-            failed_files.push(file_id.clone());
+            until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M %Z").to_string()
+        };
+        return format!("💤 Snoozed until {}", until);
+    }
+    if reminder.sent {
+        "✅ Sent".to_string()
+    } else if reminder.due_time <= now {
+        "⚠️  Overdue".to_string()
+    } else {
+        "⏳ Pending".to_string()
+    }
+}
+
+async fn linked_file_name(storage: &UserStorage, session: &Session, file_id: &str) -> Result<String> {
+    Ok(storage.get_file_by_id(file_id, &session.user_id).await?
+        .map(|f| f.filename)
+        .unwrap_or_else(|| "(file removed)".to_string()))
+}
+
+async fn study_notification(storage: &UserStorage, session: &Session, id: String) -> Result<()> {
+    let client = reminder_client(session);
+    let reminder = fetch_owned_notification(&client, session, &id).await?;
+    let (title, memo) = title_and_memo(&reminder);
+
+    let today = Utc::now().with_timezone(&user_timezone(storage, session).await?).date_naive();
+    storage.record_study_day(&session.user_id, today).await?;
+
+    let mut files: Vec<UploadedFile> = Vec::new();
+    let mut removed = 0;
+    for file_id in &reminder.linked_file_ids {
+        match storage.get_file_by_id(file_id, &session.user_id).await? {
+            Some(file) => files.push(file),
+            None => removed += 1,
+        }
+    }
+    for tag in &reminder.linked_tags {
+        for file in storage.get_files_by_tag(&session.user_id, tag).await? {
+            if !files.iter().any(|f| f.id == file.id) {
+                files.push(file);
+            }
         }
     }
 
-    if success_count > 0 {
-        println!("✅ Bulk untag operation completed!");
-        println!("📊 Successfully removed tag '{}' from {} file(s)", tag, success_count);
+    outln!("📚 {}", title);
+    if let Some(memo) = memo {
+        outln!("📝 {}", memo);
     }
-    
-    if !tag_not_found.is_empty() {
-        println!("ℹ️  Tag '{}' not found on {} file(s): {}", tag, tag_not_found.len(), tag_not_found.join(", "));
+    outln!();
+
+    if files.is_empty() && removed == 0 {
+        outln!("📭 This notification has no linked files");
+        outln!("💡 Use 'quiz notify --file <id>' or '--tag <tag>' to link study material");
+        return Ok(());
     }
 
-    if !failed_files.is_empty() {
-        println!("⚠️  Failed to find {} file(s): {}", failed_files.len(), failed_files.join(", "));
+    for file in &files {
+        outln!("═══════════════════════════════════════");
+        outln!("📄 {} ({})", file.filename, file.id);
+        outln!("═══════════════════════════════════════");
+        println!("{}\n", file.content);
+    }
+    if removed > 0 {
+        outln!("⚠️  {} linked file(s) were removed", removed);
     }
 
-    if success_count == 0 && !tag_not_found.is_empty() {
-        bail!("Cannot remove tag '{}' because it was not found on any of the selected files", tag);
+    Ok(())
+}
+
+async fn delete_notification(session: &Session, id: String, yes: bool) -> Result<()> {
+    let client = reminder_client(session);
+    let reminder = fetch_owned_notification(&client, session, &id).await?;
+
+    outln!("🗑️  Notification to delete:");
+    outln!("   {} ({}), due {}", reminder.id, title_and_memo(&reminder).0, reminder.due_time.format("%Y-%m-%d %H:%M:%S UTC"));
+    if !confirm("delete this notification", Confirmation::Yes, yes)? {
+        outln!("❌ Deletion cancelled");
+        return Ok(());
+    }
+
+    delete_reminder(&client, &reminder.id).await?;
+    log_activity(&session.user_id, "delete-notification", format!("Deleted notification {}", reminder.id));
+
+    outln!("✅ Study notification deleted!");
+    outln!("🆔 ID: {}", reminder.id);
+    outln!("📝 Message: {}", reminder.message);
+    outln!("⏰ Was scheduled: {}", reminder.due_time);
+
+    Ok(())
+}
+
+async fn snooze_notification(session: &Session, id: String, minutes: i64) -> Result<()> {
+    if minutes <= 0 {
+        bail!("Snooze minutes must be positive");
+    }
+
+    let client = reminder_client(session);
+    let reminder = fetch_owned_notification(&client, session, &id).await?;
+    if reminder.completed {
+        bail!("Notification {} is already completed", reminder.id);
+    }
+
+    let updated = client.snooze(&reminder.id, Some(minutes)).await
+        .map_err(|e| notification_action_error(e, &reminder.id, "snooze"))?;
+
+    log_activity(&session.user_id, "snooze-notification", format!("Snoozed notification {} for {} minutes", updated.id, minutes));
+
+    outln!("💤 Study notification snoozed for {} minutes", minutes);
+    outln!("🆔 ID: {}", updated.id);
+    outln!("⏰ Next reminder: {}", updated.due_time);
+
+    Ok(())
+}
+
+async fn complete_notification(session: &Session, id: String) -> Result<()> {
+    let client = reminder_client(session);
+    let reminder = fetch_owned_notification(&client, session, &id).await?;
+    if reminder.completed {
+        bail!("Notification {} is already completed", reminder.id);
+    }
+
+    let updated = client.complete(&reminder.id, false).await
+        .map_err(|e| notification_action_error(e, &reminder.id, "complete"))?;
+
+    log_activity(&session.user_id, "done-notification", format!("Completed notification {}", updated.id));
+
+    outln!("✔️  Study notification marked as done!");
+    outln!("🆔 ID: {}", updated.id);
+    outln!("📌 {}", title_and_memo(&updated).0);
+    if !updated.completed {
+        outln!("🔁 Repeats after completion; next due {}", updated.due_time.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+
+    Ok(())
+}
+
+/// Maps a failed snooze/complete call onto distinct errors.
+fn notification_action_error(e: ClientError, id: &str, action: &str) -> anyhow::Error {
+    match e.status() {
+        Some(reqwest::StatusCode::NOT_FOUND) => anyhow::anyhow!("Notification not found with ID: {}", id),
+        Some(reqwest::StatusCode::CONFLICT) => anyhow::anyhow!("Notification {} is already completed", id),
+        Some(reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) => {
+            anyhow::anyhow!("Notification {} does not belong to you", id)
+        }
+        _ => api_error(e).context(format!("Failed to {} notification {}", action, id)),
+    }
+}
+
+async fn edit_notification(
+    session: &Session,
+    id: String,
+    title: Option<String>,
+    memo: Option<String>,
+    time: Option<String>,
+    recurrence: Option<String>,
+) -> Result<()> {
+    if title.is_none() && memo.is_none() && time.is_none() && recurrence.is_none() {
+        bail!("Nothing to change. Pass at least one of --title, --memo, --time, or --recurrence");
+    }
+
+    let client = reminder_client(session);
+    let before = fetch_owned_notification(&client, session, &id).await?;
+
+    let (new_title, new_memo) = if title.is_some() || memo.is_some() {
+        let (old_title, old_memo) = title_and_memo(&before);
+        (Some(title.unwrap_or(old_title)), memo.or(old_memo))
+    } else {
+        (None, None)
+    };
+
+    let request = UpdateReminderRequest {
+        message: new_title.as_deref().map(|t| build_notification_message(t, new_memo.as_deref())),
+        due_time: time.clone(),
+        recurrence,
+        title: new_title,
+        description: new_memo,
+        ..Default::default()
+    };
+
+    let after = client.update(&before.id, &request).await
+        .map_err(api_error)
+        .context("Failed to update notification")?;
+
+    log_activity(&session.user_id, "edit-notification", format!("Edited notification {}", after.id));
+
+    outln!("✅ Study notification updated!");
+    outln!("🆔 ID: {}", after.id);
+    let (before_title, before_memo) = title_and_memo(&before);
+    let (after_title, after_memo) = title_and_memo(&after);
+    outln!("   Before:");
+    outln!("     📚 Title: {}", before_title);
+    outln!("     📝 Memo: {}", before_memo.as_deref().unwrap_or("-"));
+    outln!("     ⏰ Scheduled: {}", before.due_time);
+    outln!("     🔄 Recurrence: {}", before.recurrence.as_deref().unwrap_or("none"));
+    outln!("   After:");
+    outln!("     📚 Title: {}", after_title);
+    outln!("     📝 Memo: {}", after_memo.as_deref().unwrap_or("-"));
+    outln!("     ⏰ Scheduled: {}", after.due_time);
+    outln!("     🔄 Recurrence: {}", after.recurrence.as_deref().unwrap_or("none"));
+
+    if before.sent && time.is_some() && !after.sent {
+        outln!("\n⚠️  This notification already fired. It will fire again at the new time.");
+    }
+
+    Ok(())
+}
+
+/// Deletes all of the user's notifications matching `predicate`; `label`
+/// describes the selection in output.
+async fn delete_matching_notifications(session: &Session, label: &str, predicate: impl Fn(&Reminder) -> bool, yes: bool) -> Result<()> {
+    let client = reminder_client(session);
+    let reminders = client.list_all().await
+        .map_err(api_error)
+        .context("Failed to fetch notifications")?;
+
+    let matching: Vec<&Reminder> = reminders
+        .iter()
+        .filter(|r| r.username.as_ref() == Some(&session.username) && predicate(r))
+        .collect();
+
+    if matching.is_empty() {
+        outln!("📭 No {} notifications to delete", label);
+        return Ok(());
+    }
+
+    outln!("🗑️  {} {} notification(s) selected for deletion:", matching.len(), label);
+    for reminder in &matching {
+        outln!("   {} ({})", reminder.id, title_and_memo(reminder).0);
+    }
+    if !confirm(&format!("delete {} notification(s)", matching.len()), Confirmation::Yes, yes)? {
+        outln!("❌ Deletion cancelled");
+        return Ok(());
+    }
+
+    for reminder in &matching {
+        delete_reminder(&client, &reminder.id).await?;
+        outln!("🗑️  Deleted {} ({})", reminder.id, title_and_memo(reminder).0);
+    }
+    log_activity(&session.user_id, "delete-notification", format!("Deleted {} {} notification(s)", matching.len(), label));
+
+    outln!("\n✅ Deleted {} {} notification(s)", matching.len(), label);
+
+    Ok(())
+}
+
+/// Fetches a notification and checks it belongs to the logged-in user. An id
+/// that matches nothing exactly is tried as a prefix of the user's own ids.
+async fn fetch_owned_notification(client: &ReminderClient, session: &Session, id: &str) -> Result<Reminder> {
+    let reminder = match client.get(id).await {
+        Ok(reminder) => reminder,
+        Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+            return resolve_notification_prefix(client, session, id).await;
+        }
+        Err(e) => return Err(api_error(e)).with_context(|| format!("Failed to fetch notification {}", id)),
+    };
+
+    if reminder.username.as_ref() != Some(&session.username) {
+        bail!("Notification {} does not belong to you", id);
+    }
+
+    Ok(reminder)
+}
+
+async fn resolve_notification_prefix(client: &ReminderClient, session: &Session, prefix: &str) -> Result<Reminder> {
+    let reminders = client.list_all().await
+        .map_err(api_error)
+        .context("Failed to fetch notifications")?;
+
+    let mut matches: Vec<Reminder> = reminders
+        .into_iter()
+        .filter(|r| r.username.as_ref() == Some(&session.username) && r.id.starts_with(prefix))
+        .collect();
+
+    match matches.len() {
+        0 => bail!("Notification not found with ID: {}", prefix),
+        1 => Ok(matches.remove(0)),
+        n => bail!("ID prefix '{}' is ambiguous: it matches {} of your notifications", prefix, n),
+    }
+}
+
+/// Prints the recurrence formats the server reports, or this build's own
+/// list when the server can't be asked.
+async fn recurrence_help() {
+    let client = ReminderClient::new(api_url()).insecure(INSECURE_TLS.load(std::sync::atomic::Ordering::Relaxed));
+    let formats = match client.capabilities().await {
+        Ok(capabilities) => capabilities.recurrence_formats,
+        Err(e) => {
+            outln!("⚠️  Couldn't ask the reminder service at {} ({}); showing the formats this version of quiz knows", api_url(), e);
+            models::recurrence_formats()
+        }
+    };
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Pattern"),
+        Cell::new("Example"),
+        Cell::new("Meaning"),
+    ]));
+    for format in &formats {
+        table.add_row(Row::new(vec![
+            Cell::new(&format.pattern),
+            Cell::new(&format.example),
+            Cell::new(&format.description),
+        ]));
+    }
+
+    outln!("\n🔄 Recurrence patterns\n");
+    output::print_table(table);
+    outln!("\n💡 Example: quiz notify -n \"Review\" -t \"2025-11-05 18:00\" -r \"3 days\"");
+}
+
+/// A reminder service client for the session's user. Requests carry a signed
+/// bearer token when `REMINDER_SHARED_SECRET` is set.
+fn reminder_client(session: &Session) -> ReminderClient {
+    let secret = std::env::var("REMINDER_SHARED_SECRET").ok();
+    signed_client(api_url(), session, secret.as_deref())
+        .insecure(INSECURE_TLS.load(std::sync::atomic::Ordering::Relaxed))
+}
+
+/// A client for `url` that signs its requests as the session user when
+/// `secret` is set, and sends none when the service runs unauthenticated.
+fn signed_client(url: impl Into<String>, session: &Session, secret: Option<&str>) -> ReminderClient {
+    let token = secret
+        .filter(|secret| !secret.is_empty())
+        .map(|secret| auth::sign_token(&session.username, secret, Utc::now()));
+    ReminderClient::new(url).with_token(token).with_source(CLIENT_SOURCE)
+}
+
+/// Adds the "is the server running?" hint to connection failures.
+fn api_error(e: ClientError) -> anyhow::Error {
+    if e.is_connect() {
+        anyhow::Error::new(e).context(connect_error())
+    } else {
+        e.into()
+    }
+}
+
+/// Title and memo, from the structured fields when present or split out of
+/// the message for notifications created before they existed.
+fn title_and_memo(reminder: &Reminder) -> (String, Option<String>) {
+    match &reminder.title {
+        Some(title) => (title.clone(), reminder.description.clone()),
+        None => split_notification_message(&reminder.message),
+    }
+}
+
+fn build_notification_message(title: &str, memo: Option<&str>) -> String {
+    match memo {
+        Some(m) => format!("📚 {}\n📝 {}", title, m),
+        None => format!("📚 {}", title),
+    }
+}
+
+/// Inverse of `build_notification_message`: the first line is the title, the
+/// rest (if any) the memo.
+fn split_notification_message(message: &str) -> (String, Option<String>) {
+    let (title, memo) = match message.split_once('\n') {
+        Some((title, memo)) => (title, Some(memo)),
+        None => (message, None),
+    };
+
+    let title = title.trim_start_matches("📚").trim().to_string();
+    let memo = memo.map(|m| m.trim_start_matches("📝").trim().to_string());
+    (title, memo)
+}
+
+async fn delete_reminder(client: &ReminderClient, id: &str) -> Result<()> {
+    match client.delete(id).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+            bail!("Notification not found with ID: {}", id)
+        }
+        Err(e) => Err(api_error(e)).with_context(|| format!("Failed to delete notification {}", id)),
+    }
+}
+
+async fn filter_files_by_tag(
+    storage: &UserStorage,
+    session: &Session,
+    tag: String,
+    exclude: Option<String>,
+    match_mode: TagMatch,
+    format: OutputFormat,
+    long: bool,
+) -> Result<()> {
+    let include: Vec<String> = tag.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let exclude: Vec<String> = exclude.unwrap_or_default().split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let filtered_files = if include.len() == 1 && exclude.is_empty() {
+        storage.get_files_by_tag(&session.user_id, &include[0]).await?
+    } else {
+        storage.get_files_by_tags(&session.user_id, &include, &exclude, match_mode).await?
+    };
+
+    if format == OutputFormat::Json {
+        let summaries: Vec<FileSummary> = filtered_files.iter().map(FileSummary::from).collect();
+        return print_json(&summaries);
+    }
+
+    if filtered_files.is_empty() {
+        outln!("📭 No files found with tag '{}'", tag);
+        outln!("💡 Use 'quiz tag' to add tags to your files");
+        return Ok(());
+    }
+
+    outln!("📚 Files with tag '{}':\n", tag);
+    if long {
+        for (i, file) in filtered_files.iter().enumerate() {
+            print_file_entry(i + 1, file);
+        }
+    } else {
+        print_file_table(FILE_TABLE_HEADERS, filtered_files.iter().enumerate().map(|(i, file)| file_row(i + 1, file)).collect());
+    }
+
+    Ok(())
+}
+
+async fn summarize_files(
+    storage: &UserStorage,
+    session: &Session,
+    file_id: Option<String>,
+    tag: Option<String>,
+    terms: usize,
+) -> Result<()> {
+    let files = match (file_id, tag) {
+        (Some(file_id), _) => {
+            let file = storage.get_file_by_id(&file_id, &session.user_id).await?
+                .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id))?;
+            vec![file]
+        }
+        (None, Some(tag)) => {
+            let files = storage.get_files_by_tag(&session.user_id, &tag).await?;
+            if files.is_empty() {
+                outln!("📭 No files found with tag '{}'", normalize_tag(&tag));
+                return Ok(());
+            }
+            files
+        }
+        (None, None) => bail!("Provide --file-id or --tag"),
+    };
+
+    for file in &files {
+        let heading = format!("📄 {} ({})", file.filename, file.id);
+        let Some(summary) = summary::summarize(&file.content, terms) else {
+            output::record(&heading, &["⚠️  Not enough text to summarize".to_string()]);
+            continue;
+        };
+
+        let key_terms: Vec<String> = summary.key_terms
+            .iter()
+            .map(|(term, count)| format!("{} ({})", term, count))
+            .collect();
+        let mut details = vec![
+            format!("📌 {}", truncate_cell(&summary.title, 60)),
+            format!("📏 {} words in {} section(s), {}", summary.words, summary.sections, format_bytes(file.content.len() as u64)),
+        ];
+        if !key_terms.is_empty() {
+            details.push(format!("🔑 Key terms: {}", key_terms.join(", ")));
+        }
+        output::record(&heading, &details);
     }
 
     Ok(())
 }
+
+async fn suggest_file_tags(
+    storage: &UserStorage,
+    session: &Session,
+    file_id: Option<String>,
+    all_untagged: bool,
+    limit: usize,
+    apply: bool,
+) -> Result<()> {
+    let user_files = storage.get_user_files(&session.user_id).await?;
+    let vocabulary: Vec<String> = user_files.iter().flat_map(|f| f.tags.iter().cloned()).collect();
+
+    let files: Vec<UploadedFile> = if all_untagged {
+        user_files.iter().filter(|f| f.tags.is_empty()).cloned().collect()
+    } else {
+        let file_id = file_id.context("Provide --file-id or --all-untagged")?;
+        let file = user_files.iter().find(|f| f.id == file_id)
+            .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id))?;
+        vec![file.clone()]
+    };
+
+    if files.is_empty() {
+        outln!("📭 Every file already has tags");
+        return Ok(());
+    }
+
+    let proposals: Vec<(&UploadedFile, Vec<summary::TagSuggestion>)> = files
+        .iter()
+        .map(|file| (file, summary::suggest_tags(&file.content, &vocabulary, &file.tags, limit)))
+        .collect();
+
+    if all_untagged {
+        let rows = proposals
+            .iter()
+            .enumerate()
+            .map(|(i, (file, suggestions))| {
+                let tags: Vec<String> = suggestions.iter().map(|s| s.tag.clone()).collect();
+                vec![
+                    (i + 1).to_string(),
+                    truncate_cell(&file.filename, FILE_NAME_WIDTH),
+                    file.id.clone(),
+                    if tags.is_empty() { "-".to_string() } else { tags.join(", ") },
+                ]
+            })
+            .collect();
+        outln!("🏷️  Proposed tags for {} untagged file(s):\n", proposals.len());
+        print_file_table(&["#", "Name", "ID", "Proposed tags"], rows);
+    } else {
+        let (file, suggestions) = &proposals[0];
+        if suggestions.is_empty() {
+            outln!("📭 No tag suggestions for '{}'", file.filename);
+            return Ok(());
+        }
+        outln!("🏷️  Suggested tags for '{}':\n", file.filename);
+        for (i, suggestion) in suggestions.iter().enumerate() {
+            let source = if suggestion.existing { ", already one of your tags" } else { "" };
+            outln!("   {}. {} ({} mention(s){})", i + 1, suggestion.tag, suggestion.mentions, source);
+        }
+    }
+
+    if !apply {
+        outln!("\n💡 Use --apply to choose which suggestions to add");
+        return Ok(());
+    }
+
+    let mut applied = 0;
+    for (file, suggestions) in &proposals {
+        for suggestion in suggestions {
+            if !ask(&format!("❓ Tag '{}' with '{}'? (yes/no): ", file.filename, suggestion.tag)).await? {
+                continue;
+            }
+            storage.add_tag_to_file(&file.id, &session.user_id, suggestion.tag.clone()).await?;
+            log_activity(&session.user_id, "tag", format!("Tagged '{}' with {} (suggested)", file.filename, suggestion.tag));
+            applied += 1;
+        }
+    }
+
+    outln!("✅ Added {} suggested tag(s)", applied);
+    Ok(())
+}
+
+async fn diff_files(storage: &UserStorage, session: &Session, file_id_a: String, file_id_b: String) -> Result<()> {
+    let a = storage.get_file_by_id(&file_id_a, &session.user_id).await?
+        .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id_a))?;
+    let b = storage.get_file_by_id(&file_id_b, &session.user_id).await?
+        .ok_or_else(|| anyhow::anyhow!("File not found with ID: {}", file_id_b))?;
+
+    if a.content == b.content {
+        outln!("✅ {} and {} have identical content", a.filename, b.filename);
+        return Ok(());
+    }
+
+    let diff = similar::TextDiff::from_lines(&a.content, &b.content);
+
+    let header_a = format!("{} ({})", a.filename, a.id);
+    let header_b = format!("{} ({})", b.filename, b.id);
+    for line in diff.unified_diff().header(&header_a, &header_b).to_string().lines() {
+        if output::is_plain() {
+            println!("{}", line);
+        } else if line.starts_with("+++") || line.starts_with("---") {
+            outln!("\x1b[1m{}\x1b[0m", line);
+        } else if line.starts_with('+') {
+            outln!("\x1b[32m{}\x1b[0m", line);
+        } else if line.starts_with('-') {
+            outln!("\x1b[31m{}\x1b[0m", line);
+        } else if line.starts_with("@@") {
+            outln!("\x1b[36m{}\x1b[0m", line);
+        } else {
+            outln!("{}", line);
+        }
+    }
+
+    Ok(())
+}
+
+async fn search_files(storage: &UserStorage, session: &Session, query: String, options: SearchOptions, long: bool) -> Result<()> {
+    let results = storage.search_files(&session.user_id, &query, options).await?;
+
+    if results.is_empty() {
+        outln!("📭 No files contain '{}'", query);
+        return Ok(());
+    }
+
+    outln!("🔍 Results for '{}':\n", query);
+    if !long {
+        let mut rows = Vec::new();
+        for (i, result) in results.iter().enumerate() {
+            if let Some(file) = storage.get_file_by_id(&result.file_id, &session.user_id).await? {
+                let mut row = file_row(i + 1, &file);
+                row.insert(2, result.matches.len().to_string());
+                rows.push(row);
+            }
+        }
+        print_file_table(&["#", "Name", "Matches", "ID", "Size", "Uploaded", "Tags", "Flags"], rows);
+        return Ok(());
+    }
+
+    for (i, result) in results.iter().enumerate() {
+        let file = storage.get_file_by_id(&result.file_id, &session.user_id).await?;
+        let mut details = vec![format!("🆔 ID: {}", result.file_id)];
+        if let Some(file) = file {
+            for &(start, end) in result.matches.iter().take(3) {
+                details.push(format!("… {} …", snippet(&file.content, start, end)));
+            }
+            if result.matches.len() > 3 {
+                details.push(format!("(+{} more)", result.matches.len() - 3));
+            }
+        }
+        output::record(&format!("{}. 📄 {} ({} match(es))", i + 1, result.filename, result.matches.len()), &details);
+    }
+
+    Ok(())
+}
+
+/// A single-line excerpt around a match, padded to char boundaries.
+fn snippet(content: &str, start: usize, end: usize) -> String {
+    const CONTEXT: usize = 30;
+
+    let mut from = start.saturating_sub(CONTEXT);
+    while !content.is_char_boundary(from) {
+        from -= 1;
+    }
+    let mut to = (end + CONTEXT).min(content.len());
+    while !content.is_char_boundary(to) {
+        to += 1;
+    }
+
+    format!("{}[{}]{}", &content[from..start], &content[start..end], &content[end..to])
+        .replace('\n', " ")
+}
+
+async fn bulk_tag_files(storage: &UserStorage, session: &Session, file_ids: String, tags: String) -> Result<()> {
+    let file_id_list: Vec<String> = file_ids.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if file_id_list.is_empty() {
+        bail!("No valid file IDs provided");
+    }
+
+    let tag_list = parse_tags(&tags)?;
+
+    let mut success_count = 0;
+    let mut failed_files = Vec::new();
+
+    for file_id in &file_id_list {
+        // Check if file exists
+        if let Some(_file) = storage.get_file_by_id(file_id, &session.user_id).await? {
+            for tag in &tag_list {
+                storage.add_tag_to_file(file_id, &session.user_id, tag.clone()).await?;
+            }
+            success_count += 1;
+        } else {
+            failed_files.push(file_id.clone());
+        }
+    }
+
+    log_activity(&session.user_id, "tag", format!("Bulk tagged {} file(s) with {}", success_count, tag_list.join(", ")));
+
+    outln!("✅ Bulk tag operation completed!");
+    outln!("📊 Successfully tagged {} file(s)", success_count);
+    outln!("🏷️  Tags added: {}", tag_list.join(", "));
+    
+    if !failed_files.is_empty() {
+        outln!("⚠️  Failed to find {} file(s): {}", failed_files.len(), failed_files.join(", "));
+    }
+
+    Ok(())
+}
+
+async fn bulk_untag_files(storage: &UserStorage, session: &Session, file_ids: String, tag: String) -> Result<()> {
+    let file_id_list: Vec<String> = file_ids.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if file_id_list.is_empty() {
+        bail!("No valid file IDs provided");
+    }
+
+    let tag = normalize_tag(&tag);
+    let mut success_count = 0;
+    let mut failed_files = Vec::new();
+    let mut tag_not_found = Vec::new();
+
+    for file_id in &file_id_list {
+        if let Some(file) = storage.get_file_by_id(file_id, &session.user_id).await? {
+            if file.tags.iter().any(|t| normalize_tag(t) == tag) {
+                storage.remove_tag_from_file(file_id, &session.user_id, &tag).await?;
+                success_count += 1;
+            } else {
+                tag_not_found.push(file.filename.clone());
+            }
+        } else {
+            failed_files.push(file_id.clone());
+        }
+    }
+
+    if success_count > 0 {
+        log_activity(&session.user_id, "untag", format!("Bulk removed tag '{}' from {} file(s)", tag, success_count));
+        outln!("✅ Bulk untag operation completed!");
+        outln!("📊 Successfully removed tag '{}' from {} file(s)", tag, success_count);
+    }
+    
+    if !tag_not_found.is_empty() {
+        outln!("ℹ️  Tag '{}' not found on {} file(s): {}", tag, tag_not_found.len(), tag_not_found.join(", "));
+    }
+
+    if !failed_files.is_empty() {
+        outln!("⚠️  Failed to find {} file(s): {}", failed_files.len(), failed_files.join(", "));
+    }
+
+    if success_count == 0 && !tag_not_found.is_empty() {
+        bail!("Cannot remove tag '{}' because it was not found on any of the selected files", tag);
+    }
+
+    Ok(())
+}
+
+async fn bulk_delete_files(
+    storage: &UserStorage,
+    session: &Session,
+    file_ids: Option<String>,
+    tag: Option<String>,
+    yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let mut failed_files = Vec::new();
+    let selected = match (file_ids, tag) {
+        (Some(file_ids), _) => {
+            let file_id_list: Vec<String> = file_ids.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+
+            if file_id_list.is_empty() {
+                bail!("No valid file IDs provided");
+            }
+
+            let mut selected: Vec<UploadedFile> = Vec::new();
+            for file_id in file_id_list {
+                match storage.get_file_by_id(&file_id, &session.user_id).await? {
+                    Some(file) if !selected.iter().any(|f| f.id == file.id) => selected.push(file),
+                    Some(_) => {}
+                    None => failed_files.push(file_id),
+                }
+            }
+            selected
+        }
+        (None, Some(tag)) => {
+            let files = storage.get_files_by_tag(&session.user_id, &tag).await?;
+            if files.is_empty() {
+                outln!("📭 No files found with tag '{}'", normalize_tag(&tag));
+                return Ok(());
+            }
+            files
+        }
+        (None, None) => bail!("Provide --file-ids or --tag"),
+    };
+
+    if !failed_files.is_empty() {
+        outln!("⚠️  Failed to find {} file(s): {}", failed_files.len(), failed_files.join(", "));
+    }
+    if selected.is_empty() {
+        bail!("None of the selected files were found");
+    }
+
+    let total_bytes: u64 = selected.iter().map(|f| f.content.len() as u64).sum();
+    outln!("🗑️  {} file(s) selected for deletion ({}):\n", selected.len(), format_bytes(total_bytes));
+    print_file_table(FILE_TABLE_HEADERS, selected.iter().enumerate().map(|(i, file)| file_row(i + 1, file)).collect());
+
+    if dry_run {
+        outln!("🔍 Dry run: no files were deleted");
+        return Ok(());
+    }
+
+    outln!("⚠️  This cannot be undone.");
+    let expected = match selected.as_slice() {
+        [file] => Confirmation::Name(&file.filename),
+        _ => Confirmation::Yes,
+    };
+    if !confirm(&format!("delete {} file(s)", selected.len()), expected, yes)? {
+        outln!("❌ Deletion cancelled");
+        return Ok(());
+    }
+
+    let ids: Vec<String> = selected.iter().map(|f| f.id.clone()).collect();
+    let deleted = storage.delete_files(&session.user_id, &ids).await?;
+    let freed: u64 = deleted.iter().map(|f| f.content.len() as u64).sum();
+
+    log_activity(&session.user_id, "delete", format!("Bulk deleted {} file(s)", deleted.len()));
+
+    outln!("✅ Bulk delete operation completed!");
+    outln!("📊 Deleted {} file(s), freeing {}", deleted.len(), format_bytes(freed));
+
+    Ok(())
+}
+
+fn show_history(session: &Session, limit: usize) -> Result<()> {
+    let data = if Path::new(ACTIVITY_LOG_FILE).exists() {
+        fs::read_to_string(ACTIVITY_LOG_FILE).context("Failed to read activity log")?
+    } else {
+        String::new()
+    };
+
+    // Skip lines that fail to parse rather than losing the whole history
+    let entries: Vec<ActivityEntry> = data
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ActivityEntry>(line).ok())
+        .filter(|e| e.user_id == session.user_id)
+        .collect();
+
+    if entries.is_empty() {
+        outln!("📭 No activity recorded yet.");
+        return Ok(());
+    }
+
+    let start = entries.len().saturating_sub(limit);
+    outln!("📜 Recent activity for {}:\n", session.username);
+    for entry in entries[start..].iter().rev() {
+        outln!("⏰ {}  [{}] {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC"), entry.action, entry.details);
+    }
+
+    Ok(())
+}
+
+async fn export_data(storage: &UserStorage, session: &Session, output: String, include_credentials: bool) -> Result<()> {
+    let export = collect_export(storage, session, include_credentials).await?;
+
+    let json = serde_json::to_string_pretty(&export).context("Failed to serialize export")?;
+    fs::write(&output, json).with_context(|| format!("Failed to write {}", output))?;
+
+    outln!("✅ Exported account data to {}", output);
+    outln!("👤 User: {}", export.profile.username);
+    outln!("📚 Files: {}", export.files.len());
+    if include_credentials {
+        outln!("⚠️  The export contains your password hash. Keep it private.");
+    }
+
+    Ok(())
+}
+
+/// The session's profile and files as `export` writes them.
+async fn collect_export(storage: &UserStorage, session: &Session, include_credentials: bool) -> Result<UserDataExport> {
+    let user = storage.get_user_by_username(&session.username).await?
+        .ok_or_else(|| anyhow::anyhow!("Account '{}' no longer exists", session.username))?;
+
+    let files = storage.get_user_files(&session.user_id).await?;
+
+    Ok(UserDataExport {
+        version: EXPORT_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        profile: ExportedProfile {
+            user_id: user.id.clone(),
+            username: user.username.clone(),
+            created_at: user.created_at,
+            password_hash: include_credentials.then(|| user.password_hash.clone()),
+        },
+        files,
+    })
+}
+
+/// Names of the files an import created, overwrote, and skipped.
+#[derive(Debug, Default)]
+struct ImportSummary {
+    created: Vec<String>,
+    overwritten: Vec<String>,
+    skipped: Vec<String>,
+}
+
+async fn import_data(storage: &UserStorage, session: &Session, path: String, on_conflict: ConflictPolicy) -> Result<()> {
+    let data = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?;
+    let export = parse_export(&data)?;
+    let ImportSummary { created, overwritten, skipped } = restore_export(storage, session, export, on_conflict).await?;
+
+    log_activity(&session.user_id, "import", format!("Imported data from {} ({} created, {} overwritten, {} skipped)", path, created.len(), overwritten.len(), skipped.len()));
+
+    outln!("✅ Import from {} completed!", path);
+    outln!("📊 Created {} file(s)", created.len());
+    for name in &created {
+        outln!("   + {}", name);
+    }
+    if !overwritten.is_empty() {
+        outln!("📊 Overwrote {} file(s)", overwritten.len());
+        for name in &overwritten {
+            outln!("   ~ {}", name);
+        }
+    }
+    if !skipped.is_empty() {
+        outln!("ℹ️  Skipped {} existing file(s)", skipped.len());
+        for name in &skipped {
+            outln!("   - {}", name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads an export file's JSON, checking its schema version first.
+fn parse_export(data: &str) -> Result<UserDataExport> {
+    let version = serde_json::from_str::<serde_json::Value>(data)
+        .context("Export file is not valid JSON")?
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| anyhow::anyhow!("Export file has no schema version"))?;
+
+    if version != EXPORT_FORMAT_VERSION as u64 {
+        bail!("Unsupported export version {} (expected {})", version, EXPORT_FORMAT_VERSION);
+    }
+
+    serde_json::from_str(data).context("Export file does not match the expected schema")
+}
+
+/// Adds an export's files to the session's account, resolving name clashes
+/// with existing files under `on_conflict`.
+async fn restore_export(
+    storage: &UserStorage,
+    session: &Session,
+    export: UserDataExport,
+    on_conflict: ConflictPolicy,
+) -> Result<ImportSummary> {
+    let existing = storage.get_user_files(&session.user_id).await?;
+    let mut taken: Vec<String> = existing.iter().map(|f| f.filename.clone()).collect();
+
+    let mut created = Vec::new();
+    let mut overwritten = Vec::new();
+    let mut skipped = Vec::new();
+
+    for file in export.files {
+        let conflict = existing.iter().find(|f| f.filename == file.filename);
+
+        match (conflict, on_conflict) {
+            (Some(_), ConflictPolicy::Skip) => {
+                skipped.push(file.filename);
+            }
+            (Some(current), ConflictPolicy::Overwrite) => {
+                let changes = FileUpdate {
+                    filename: None,
+                    description: file.description,
+                    archived: Some(file.archived),
+                    starred: Some(file.starred),
+                    tags: Some(file.tags),
+                    content: Some(file.content),
+                };
+                storage.update_file(&current.id, &session.user_id, changes).await?;
+                overwritten.push(file.filename);
+            }
+            (conflict, _) => {
+                let filename = if conflict.is_some() {
+                    unique_filename(&file.filename, &taken)
+                } else {
+                    file.filename.clone()
+                };
+
+                let mut imported = UploadedFile::new(session.user_id.clone(), filename.clone(), file.content);
+                imported.uploaded_at = file.uploaded_at;
+                imported.tags = file.tags;
+                imported.description = file.description;
+                imported.archived = file.archived;
+                imported.starred = file.starred;
+
+                storage.add_file(imported).await?;
+                taken.push(filename.clone());
+                created.push(filename);
+            }
+        }
+    }
+
+    Ok(ImportSummary { created, overwritten, skipped })
+}
+
+/// Describes `time` relative to `now`, e.g. "in 3 h 20 m" or "2 days ago".
+fn format_relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta = time - now;
+    let future = delta >= chrono::Duration::zero();
+    let minutes = delta.num_minutes().abs();
+
+    let span = if minutes < 1 {
+        return "now".to_string();
+    } else if minutes < 60 {
+        format!("{} m", minutes)
+    } else if minutes < 24 * 60 {
+        let (h, m) = (minutes / 60, minutes % 60);
+        if m == 0 { format!("{} h", h) } else { format!("{} h {} m", h, m) }
+    } else {
+        let days = minutes / (24 * 60);
+        format!("{} day{}", days, if days == 1 { "" } else { "s" })
+    };
+
+    if future { format!("in {}", span) } else { format!("{} ago", span) }
+}
+
+/// Splits a comma-separated CLI value, dropping empty entries.
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// `notes.txt` -> `notes (2).txt`, picking the first suffix not already taken.
+fn unique_filename(filename: &str, taken: &[String]) -> String {
+    let path = Path::new(filename);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(filename);
+    let extension = path.extension().and_then(|e| e.to_str());
+
+    (2..)
+        .map(|n| match extension {
+            Some(ext) => format!("{} ({}).{}", stem, n, ext),
+            None => format!("{} ({})", stem, n),
+        })
+        .find(|candidate| !taken.contains(candidate))
+        .expect("unbounded suffix range")
+}
+
+/// The user's preferred timezone, falling back to UTC when none is set.
+async fn user_timezone(storage: &UserStorage, session: &Session) -> Result<chrono_tz::Tz> {
+    let timezone = storage.get_user_by_username(&session.username).await?
+        .and_then(|u| u.preferences.timezone);
+
+    match timezone {
+        Some(tz) => tz.parse().map_err(|_| anyhow::anyhow!("Unknown timezone '{}' in your preferences", tz)),
+        None => Ok(chrono_tz::UTC),
+    }
+}
+
+async fn show_streak(storage: &UserStorage, session: &Session) -> Result<()> {
+    let activity = storage.get_study_activity(&session.user_id).await?;
+    let today = Utc::now().with_timezone(&user_timezone(storage, session).await?).date_naive();
+    let studied = |day: chrono::NaiveDate| activity.days.binary_search(&day).is_ok();
+
+    // Today still counts as "in progress", so an unstudied today doesn't break the streak
+    let mut day = if studied(today) { today } else { today - chrono::Duration::days(1) };
+    let mut current = 0;
+    while studied(day) {
+        current += 1;
+        day -= chrono::Duration::days(1);
+    }
+
+    let mut longest = 0;
+    let mut run = 0;
+    for (i, day) in activity.days.iter().enumerate() {
+        run = match i.checked_sub(1).map(|p| activity.days[p]) {
+            Some(prev) if *day - prev == chrono::Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+    }
+
+    let sparkline: String = (0..30)
+        .rev()
+        .map(|offset| if studied(today - chrono::Duration::days(offset)) { '█' } else { '▁' })
+        .collect();
+
+    outln!("🔥 Current streak: {} day(s)", current);
+    outln!("🏆 Longest streak: {} day(s)", longest);
+    outln!("📅 Last 30 days:  {}", sparkline);
+    if !studied(today) {
+        outln!("\n💡 You haven't studied today. Run 'quiz study --id <id>' to keep your streak going");
+    }
+    match activity.nudge_reminder_id {
+        Some(id) => outln!("🔔 Daily nudge: on ({})", id),
+        None => outln!("🔕 Daily nudge: off (enable with 'quiz streak enable-nudge --time 20:00')"),
+    }
+
+    Ok(())
+}
+
+/// Creates the daily nudge reminder, or moves the existing one so the user
+/// only ever has one.
+async fn enable_nudge(storage: &UserStorage, session: &Session, time: &str) -> Result<()> {
+    let at = chrono::NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| anyhow::anyhow!("Invalid time '{}'. Use HH:MM, e.g., 20:00", time))?;
+    let tz = user_timezone(storage, session).await?;
+
+    let now = Utc::now();
+    let mut day = now.with_timezone(&tz).date_naive();
+    let due = loop {
+        if let Some(due) = day.and_time(at).and_local_timezone(tz).earliest() {
+            if due > now {
+                break due.with_timezone(&Utc);
+            }
+        }
+        day += chrono::Duration::days(1);
+    };
+
+    let client = reminder_client(session);
+    let activity = storage.get_study_activity(&session.user_id).await?;
+
+    if let Some(existing) = activity.nudge_reminder_id {
+        if let Some(reminder) = fetch_nudge(&client, &existing).await? {
+            let request = UpdateReminderRequest {
+                due_time: Some(due.to_rfc3339()),
+                recurrence: Some("daily".to_string()),
+                ..Default::default()
+            };
+            client.update(&reminder.id, &request).await
+                .map_err(api_error)
+                .context("Failed to update daily nudge")?;
+
+            log_activity(&session.user_id, "streak-nudge", format!("Moved daily nudge {} to {}", reminder.id, time));
+            outln!("🔔 Daily nudge moved to {} ({})", time, tz);
+            outln!("🆔 ID: {}", reminder.id);
+            return Ok(());
+        }
+    }
+
+    let title = "Daily study nudge";
+    let memo = "Keep your streak going! Study something today";
+    let request = CreateReminderRequest {
+        message: build_notification_message(title, Some(memo)),
+        due_time: due.to_rfc3339(),
+        username: Some(session.username.clone()),
+        recurrence: Some("daily".to_string()),
+        title: Some(title.to_string()),
+        description: Some(memo.to_string()),
+        tags: vec!["nudge".to_string()],
+        ..Default::default()
+    };
+
+    let created = client.create(&request).await
+        .map_err(api_error)
+        .context("Failed to create daily nudge")?;
+    storage.set_nudge_reminder(&session.user_id, Some(created.id.clone())).await?;
+
+    log_activity(&session.user_id, "streak-nudge", format!("Enabled daily nudge {} at {}", created.id, time));
+    outln!("🔔 Daily nudge enabled at {} ({})", time, tz);
+    outln!("🆔 ID: {}", created.id);
+
+    Ok(())
+}
+
+async fn disable_nudge(storage: &UserStorage, session: &Session) -> Result<()> {
+    let activity = storage.get_study_activity(&session.user_id).await?;
+    let Some(id) = activity.nudge_reminder_id else {
+        outln!("🔕 Daily nudge is already off");
+        return Ok(());
+    };
+
+    let client = reminder_client(session);
+    if fetch_nudge(&client, &id).await?.is_some() {
+        delete_reminder(&client, &id).await?;
+    }
+    storage.set_nudge_reminder(&session.user_id, None).await?;
+
+    log_activity(&session.user_id, "streak-nudge", format!("Disabled daily nudge {}", id));
+    outln!("🔕 Daily nudge disabled");
+
+    Ok(())
+}
+
+/// The stored nudge reminder, or `None` if it was deleted or completed meanwhile.
+async fn fetch_nudge(client: &ReminderClient, id: &str) -> Result<Option<Reminder>> {
+    match client.get(id).await {
+        Ok(reminder) => Ok(Some(reminder).filter(|r| !r.completed)),
+        Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => Ok(None),
+        Err(e) => Err(api_error(e)).with_context(|| format!("Failed to fetch daily nudge {}", id)),
+    }
+}
+
+async fn set_preferences(
+    storage: &UserStorage,
+    session: &Session,
+    timezone: Option<String>,
+    recurrence: Option<String>,
+    clear_timezone: bool,
+    clear_recurrence: bool,
+) -> Result<()> {
+    let mut preferences = storage.get_user_by_username(&session.username).await?
+        .map(|u| u.preferences)
+        .unwrap_or_default();
+
+    if let Some(timezone) = timezone {
+        timezone.parse::<chrono_tz::Tz>()
+            .map_err(|_| anyhow::anyhow!("Unknown timezone '{}'. Use an IANA name such as Europe/Berlin", timezone))?;
+        preferences.timezone = Some(timezone);
+    } else if clear_timezone {
+        preferences.timezone = None;
+    }
+
+    if let Some(recurrence) = recurrence {
+        let recurrence = recurrence.trim().to_string();
+        if recurrence.is_empty() {
+            bail!("Recurrence cannot be empty");
+        }
+        preferences.default_recurrence = Some(recurrence);
+    } else if clear_recurrence {
+        preferences.default_recurrence = None;
+    }
+
+    let preferences = storage.set_preferences(&session.user_id, preferences).await?;
+    log_activity(&session.user_id, "set-prefs", "Updated notification preferences".to_string());
+
+    outln!("✅ Preferences saved");
+    print_preferences(&preferences);
+
+    if let Err(e) = seed_server_timezone(session, preferences.timezone.as_deref(), true).await {
+        outln!("⚠️  Couldn't update the timezone on the reminder service: {}", api_error(e));
+    }
+    Ok(())
+}
+
+/// Copies the profile timezone into the user's reminder service preferences,
+/// so reminders created with other clients default to it too. Without
+/// `overwrite`, a timezone already set there is left alone.
+async fn seed_server_timezone(session: &Session, timezone: Option<&str>, overwrite: bool) -> std::result::Result<(), ClientError> {
+    let client = reminder_client(session);
+    let current = match client.preferences(&session.username).await {
+        Ok(preferences) => Some(preferences),
+        Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => None,
+        Err(e) => return Err(e),
+    };
+
+    let current_timezone = current.as_ref().and_then(|p| p.timezone.as_deref());
+    if current_timezone == timezone || (current_timezone.is_some() && !overwrite) {
+        return Ok(());
+    }
+
+    let mut request = current
+        .map(PreferencesRequest::from)
+        .unwrap_or_default();
+    request.timezone = timezone.map(str::to_string);
+    client.set_preferences(&session.username, &request).await?;
+    Ok(())
+}
+
+async fn show_preferences(storage: &UserStorage, session: &Session) -> Result<()> {
+    let preferences = storage.get_user_by_username(&session.username).await?
+        .map(|u| u.preferences)
+        .unwrap_or_default();
+
+    outln!("⚙️  Preferences for {}", session.username);
+    print_preferences(&preferences);
+    Ok(())
+}
+
+fn print_preferences(preferences: &UserPreferences) {
+    outln!("   🌍 Timezone: {}", preferences.timezone.as_deref().unwrap_or("(not set)"));
+    outln!("   🔄 Default recurrence: {}", preferences.default_recurrence.as_deref().unwrap_or("(not set)"));
+}
+
+/// Parses a due time with the shared rules. Times without a zone are read in
+/// the user's timezone.
+fn resolve_due_time(time: &str, timezone: chrono_tz::Tz) -> Result<DateTime<Utc>> {
+    let rules = due_time::DueTimeRules { default_timezone: Some(timezone), ..Default::default() };
+
+    due_time::parse_due_time(time, &rules).map_err(|e| {
+        anyhow::anyhow!("Invalid time '{}': {}. Accepted formats: {}", time, e, due_time::accepted_examples())
+    })
+}
+
+fn configure(set_reminder_url: Option<String>, unset_reminder_url: bool, embedded: Option<bool>) -> Result<()> {
+    let mut config = Config::load()?;
+
+    if let Some(url) = set_reminder_url {
+        reqwest::Url::parse(&url).with_context(|| format!("Invalid URL: {}", url))?;
+        config.reminder_url = Some(url.trim_end_matches('/').to_string());
+        config.save()?;
+        outln!("✅ Reminder service URL saved");
+    } else if unset_reminder_url {
+        config.reminder_url = None;
+        config.save()?;
+        outln!("✅ Saved reminder service URL removed");
+    }
+
+    if let Some(embedded) = embedded {
+        config.embedded = embedded;
+        config.save()?;
+        outln!("✅ Embedded mode {}", if embedded { "enabled" } else { "disabled" });
+    }
+
+    outln!("⚙️  Configuration ({})", CONFIG_FILE);
+    outln!("   Saved reminder URL: {}", config.reminder_url.as_deref().unwrap_or("-"));
+    outln!("   Effective reminder URL: {}", api_url());
+    outln!("   Embedded mode: {}", if config.embedded { "on" } else { "off" });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reminder_service::preference_storage::PreferenceStorage;
+    use reminder_service::server::{build_router, AppState};
+    use reminder_service::storage::ReminderStorage;
+    use reminder_service::template_storage::TemplateStorage;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// A reminder server on a free local port, storing into a temp dir;
+    /// `secret` turns on authentication. Returns the dir and the base URL.
+    async fn serve(secret: Option<&str>) -> (TempDir, String) {
+        let dir = TempDir::new().unwrap();
+        let storage = Arc::new(ReminderStorage::open(dir.path().join("reminders.json")).unwrap());
+        let templates = Arc::new(TemplateStorage::open_beside(storage.path()).unwrap());
+        let preferences = Arc::new(PreferenceStorage::open_beside(storage.path()).unwrap());
+        let mut config = config::Config::default();
+        config.server.shared_secret = secret.map(str::to_string);
+        let state = Arc::new(AppState::from_config(storage, templates, preferences, &config));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, build_router(state)).await.unwrap() });
+        (dir, url)
+    }
+
+    fn session(username: &str) -> Session {
+        Session { user_id: format!("{}-id", username), username: username.to_string(), last_used: Utc::now() }
+    }
+
+    fn storage() -> (TempDir, UserStorage) {
+        let dir = TempDir::new().unwrap();
+        let storage = UserStorage::open_in(dir.path(), 1024 * 1024, None).unwrap();
+        (dir, storage)
+    }
+
+    async fn signed_up(storage: &UserStorage, username: &str) -> Session {
+        let user = storage.create_user(User::new(username.to_string(), "hash".to_string())).await.unwrap();
+        Session { user_id: user.id, username: user.username, last_used: Utc::now() }
+    }
+
+    /// The parts of a file an export is meant to carry over; ids are new on import.
+    fn restorable(files: Vec<UploadedFile>) -> Vec<serde_json::Value> {
+        files
+            .into_iter()
+            .map(|f| {
+                let mut value = serde_json::to_value(f).unwrap();
+                let fields = value.as_object_mut().unwrap();
+                fields.remove("id");
+                fields.remove("user_id");
+                value
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn export_then_import_into_a_wiped_store_restores_the_files() {
+        let (_dir, original) = storage();
+        let session = signed_up(&original, "ann").await;
+        let mut bio = UploadedFile::new(session.user_id.clone(), "bio.txt".to_string(), "cells".to_string());
+        bio.tags = vec!["biology".to_string(), "exam".to_string()];
+        bio.description = Some("chapter 1".to_string());
+        bio.starred = true;
+        original.add_file(bio).await.unwrap();
+        let mut old = UploadedFile::new(session.user_id.clone(), "old.txt".to_string(), "archived notes".to_string());
+        old.archived = true;
+        original.add_file(old).await.unwrap();
+
+        let export = collect_export(&original, &session, false).await.unwrap();
+        let json = serde_json::to_string_pretty(&export).unwrap();
+
+        let (_wiped_dir, wiped) = storage();
+        let restored_session = signed_up(&wiped, "ann").await;
+        let summary = restore_export(&wiped, &restored_session, parse_export(&json).unwrap(), ConflictPolicy::Skip)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.created, ["bio.txt", "old.txt"]);
+        assert!(summary.overwritten.is_empty() && summary.skipped.is_empty());
+        assert_eq!(
+            restorable(wiped.get_user_files(&restored_session.user_id).await.unwrap()),
+            restorable(original.get_user_files(&session.user_id).await.unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn export_leaves_out_the_password_hash_unless_asked() {
+        let (_dir, storage) = storage();
+        let session = signed_up(&storage, "ann").await;
+
+        assert!(collect_export(&storage, &session, false).await.unwrap().profile.password_hash.is_none());
+        assert_eq!(collect_export(&storage, &session, true).await.unwrap().profile.password_hash.as_deref(), Some("hash"));
+    }
+
+    #[tokio::test]
+    async fn import_resolves_name_clashes_by_policy() {
+        let (_dir, storage) = storage();
+        let session = signed_up(&storage, "ann").await;
+        storage
+            .add_file(UploadedFile::new(session.user_id.clone(), "a.txt".to_string(), "current".to_string()))
+            .await
+            .unwrap();
+        let export = collect_export(&storage, &session, false).await.unwrap();
+        let json = serde_json::to_string(&export).unwrap();
+
+        let skipped = restore_export(&storage, &session, parse_export(&json).unwrap(), ConflictPolicy::Skip).await.unwrap();
+        assert_eq!(skipped.skipped, ["a.txt"]);
+
+        let duplicated = restore_export(&storage, &session, parse_export(&json).unwrap(), ConflictPolicy::Duplicate).await.unwrap();
+        assert_eq!(duplicated.created.len(), 1);
+        assert_ne!(duplicated.created[0], "a.txt");
+
+        let overwritten = restore_export(&storage, &session, parse_export(&json).unwrap(), ConflictPolicy::Overwrite).await.unwrap();
+        assert_eq!(overwritten.overwritten, ["a.txt"]);
+        assert_eq!(storage.get_user_files(&session.user_id).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn notification_title_and_memo_survive_notify_then_list() {
+        let (_dir, url) = serve(None).await;
+        let session = session("ann");
+        let client = ReminderClient::new(url).with_source(CLIENT_SOURCE);
+
+        let request = notification_request(
+            &session,
+            "Cell biology",
+            Some("Chapter 3\nfocus on mitosis"),
+            "2099-01-01T09:00:00Z",
+            Some("daily".to_string()),
+            &NotificationLinks::default(),
+        );
+        client.create(&request).await.unwrap();
+        let untitled = notification_request(&session, "Flashcards", None, "2099-01-02T09:00:00Z", None, &NotificationLinks::default());
+        client.create(&untitled).await.unwrap();
+
+        let query = ReminderListQuery { username: Some("ann".to_string()), ..Default::default() };
+        let listed: Vec<(String, Option<String>)> = client.list_matching(&query).await.unwrap().iter().map(title_and_memo).collect();
+
+        assert_eq!(
+            listed,
+            [
+                ("Cell biology".to_string(), Some("Chapter 3\nfocus on mitosis".to_string())),
+                ("Flashcards".to_string(), None),
+            ]
+        );
+    }
+
+    /// Creates a notification for `session` and lists that user's notifications back.
+    async fn create_and_list(client: &ReminderClient, session: &Session, title: &str) -> Result<Vec<Reminder>, ClientError> {
+        let request = notification_request(session, title, None, "2099-01-01T09:00:00Z", None, &NotificationLinks::default());
+        client.create(&request).await?;
+        let query = ReminderListQuery { username: Some(session.username.clone()), ..Default::default() };
+        client.list_matching(&query).await
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_service_needs_no_token() {
+        let (_dir, url) = serve(None).await;
+        let ann = session("ann");
+
+        let listed = create_and_list(&signed_client(url, &ann, None), &ann, "Open mode").await.unwrap();
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].username.as_deref(), Some("ann"));
+    }
+
+    #[tokio::test]
+    async fn authenticated_service_accepts_the_signed_session_user() {
+        let (_dir, url) = serve(Some("s3cret")).await;
+        let ann = session("ann");
+
+        let listed = create_and_list(&signed_client(url, &ann, Some("s3cret")), &ann, "Auth mode").await.unwrap();
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].owner.as_deref(), Some("ann"));
+    }
+
+    #[tokio::test]
+    async fn authenticated_service_refuses_missing_or_wrong_tokens() {
+        let (_dir, url) = serve(Some("s3cret")).await;
+        let ann = session("ann");
+
+        let err = create_and_list(&signed_client(url.clone(), &ann, None), &ann, "No token").await.unwrap_err();
+        assert_eq!(err.status(), Some(reqwest::StatusCode::UNAUTHORIZED));
+
+        let err = create_and_list(&signed_client(url, &ann, Some("wrong")), &ann, "Bad token").await.unwrap_err();
+        assert_eq!(err.status(), Some(reqwest::StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn authenticated_users_only_list_their_own_notifications() {
+        let (_dir, url) = serve(Some("s3cret")).await;
+        let (ann, bob) = (session("ann"), session("bob"));
+        create_and_list(&signed_client(url.clone(), &ann, Some("s3cret")), &ann, "Ann's").await.unwrap();
+
+        let bob_client = signed_client(url, &bob, Some("s3cret"));
+        let listed = bob_client.list().await.unwrap();
+        assert!(listed.is_empty());
+
+        let query = ReminderListQuery { username: Some("ann".to_string()), ..Default::default() };
+        assert!(bob_client.list_matching(&query).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unreachable_service_is_reported_as_a_connection_failure() {
+        // A port nothing listens on any more
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let ann = session("ann");
+
+        let err = create_and_list(&signed_client(url, &ann, None), &ann, "Offline").await.unwrap_err();
+
+        assert!(err.is_connect(), "{err}");
+    }
+
+    #[tokio::test]
+    async fn embedded_mode_writes_and_reads_the_storage_file_directly() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("reminders.json");
+        let ann = session("ann");
+        let request = notification_request(&ann, "Offline study", Some("memo"), "2099-01-01T09:00:00Z", Some("daily".to_string()), &NotificationLinks::default());
+
+        let id = create_reminder_embedded(&path, &request).await.unwrap();
+
+        let stored = list_reminders_embedded(&path).await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].id, id);
+        assert_eq!(title_and_memo(&stored[0]), ("Offline study".to_string(), Some("memo".to_string())));
+        assert_eq!(stored[0].recurrence.as_deref(), Some("daily"));
+        assert_eq!(stored[0].source.as_deref(), Some(CLIENT_SOURCE));
+        assert!(!path.with_extension("lock").exists());
+    }
+
+    #[tokio::test]
+    async fn embedded_mode_refuses_while_the_storage_is_locked() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("reminders.json");
+        let request = notification_request(&session("ann"), "Blocked", None, "2099-01-01T09:00:00Z", None, &NotificationLinks::default());
+        let _server_lock = storage::StorageLock::acquire_for(&path).unwrap();
+
+        let err = create_reminder_embedded(&path, &request).await.unwrap_err();
+        assert!(err.to_string().contains("locked by process"), "{err}");
+
+        let err = list_reminders_embedded(&path).await.unwrap_err();
+        assert!(err.to_string().contains("locked by process"), "{err}");
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn embedded_mode_rejects_what_the_server_would() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("reminders.json");
+        let mut request = notification_request(&session("ann"), "Bad", None, "2099-01-01T09:00:00Z", None, &NotificationLinks::default());
+        request.recurrence = Some("fortnightly".to_string());
+
+        assert!(create_reminder_embedded(&path, &request).await.is_err());
+        assert!(list_reminders_embedded(&path).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn notifications_from_before_structured_fields_are_split_from_the_message() {
+        let legacy = Reminder::new(build_notification_message("Cell biology", Some("Chapter 3")), Utc::now(), None, None);
+        assert_eq!(title_and_memo(&legacy), ("Cell biology".to_string(), Some("Chapter 3".to_string())));
+
+        let legacy = Reminder::new(build_notification_message("Flashcards", None), Utc::now(), None, None);
+        assert_eq!(title_and_memo(&legacy), ("Flashcards".to_string(), None));
+    }
+
+    #[test]
+    fn relative_times_around_minute_hour_and_day_boundaries() {
+        let now = DateTime::parse_from_rfc3339("2025-03-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |seconds: i64| now + chrono::Duration::seconds(seconds);
+
+        assert_eq!(format_relative(now, now), "now");
+        assert_eq!(format_relative(at(59), now), "now");
+        assert_eq!(format_relative(at(60), now), "in 1 m");
+        assert_eq!(format_relative(at(59 * 60 + 59), now), "in 59 m");
+        assert_eq!(format_relative(at(60 * 60), now), "in 1 h");
+        assert_eq!(format_relative(at(3 * 3600 + 20 * 60), now), "in 3 h 20 m");
+        assert_eq!(format_relative(at(24 * 3600 - 60), now), "in 23 h 59 m");
+        assert_eq!(format_relative(at(24 * 3600), now), "in 1 day");
+        assert_eq!(format_relative(at(48 * 3600 - 1), now), "in 1 day");
+        assert_eq!(format_relative(at(48 * 3600), now), "in 2 days");
+    }
+
+    #[test]
+    fn relative_times_in_the_past_read_as_ago() {
+        let now = DateTime::parse_from_rfc3339("2025-03-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |seconds: i64| now - chrono::Duration::seconds(seconds);
+
+        assert_eq!(format_relative(at(59), now), "now");
+        assert_eq!(format_relative(at(60), now), "1 m ago");
+        assert_eq!(format_relative(at(90 * 60), now), "1 h 30 m ago");
+        assert_eq!(format_relative(at(2 * 24 * 3600), now), "2 days ago");
+    }
+
+    #[test]
+    fn exports_of_another_version_are_refused() {
+        let err = parse_export(r#"{"version": 99}"#).unwrap_err();
+        assert!(err.to_string().contains("Unsupported export version 99"), "{err}");
+
+        let err = parse_export(r#"{"files": []}"#).unwrap_err();
+        assert!(err.to_string().contains("no schema version"), "{err}");
+    }
+
+    fn uploaded(filename: &str, tags: &[&str]) -> UploadedFile {
+        UploadedFile {
+            id: "f-1".to_string(),
+            user_id: "ann-id".to_string(),
+            filename: filename.to_string(),
+            content: "a".repeat(1200),
+            uploaded_at: DateTime::parse_from_rfc3339("2025-03-10T08:05:59Z").unwrap().with_timezone(&Utc),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            description: None,
+            archived: false,
+            starred: false,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn file_rows_follow_the_table_headers() {
+        let mut file = uploaded("notes.txt", &["biology", "exam"]);
+        file.starred = true;
+        file.archived = true;
+
+        let row = file_row(7, &file);
+
+        assert_eq!(row.len(), FILE_TABLE_HEADERS.len());
+        assert_eq!(row, ["7", "notes.txt", "f-1", "1200", "2025-03-10 08:05", "biology, exam", "starred, archived"]);
+        assert_eq!(file_row(1, &uploaded("plain.txt", &[]))[5..], ["", ""]);
+    }
+
+    #[test]
+    fn long_names_are_cut_to_the_column_width() {
+        let name = "a-very-long-file-name-for-the-table.txt";
+        let cell = &file_row(1, &uploaded(name, &[]))[1];
+
+        assert_eq!(cell.chars().count(), FILE_NAME_WIDTH);
+        assert_eq!(cell, "a-very-long-file-name-for-the-t…");
+        assert_eq!(truncate_cell("exactly", 7), "exactly");
+        assert_eq!(truncate_cell("ünïcödé names", 6), "ünïcö…");
+    }
+
+    #[test]
+    fn tags_that_do_not_fit_are_counted_instead() {
+        let tags = |names: &[&str]| names.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(elide_tags(&tags(&["bio", "exam"]), 24), "bio, exam");
+        assert_eq!(elide_tags(&tags(&["biology", "chemistry", "physics", "maths"]), 24), "biology, chemistry +2");
+        assert_eq!(elide_tags(&tags(&["biology", "chemistry", "physics"]), 12), "biology +2");
+        assert_eq!(elide_tags(&tags(&["a-very-long-tag", "another-long-tag"]), 10), "+2");
+        assert_eq!(elide_tags(&tags(&["one-single-very-long-tag-name"]), 10), "one-singl…");
+    }
+
+    #[test]
+    fn due_within_includes_its_end_and_excludes_anything_later() {
+        let now = DateTime::parse_from_rfc3339("2025-03-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let within = chrono::Duration::hours(2);
+        let filter = NotificationFilter { sent: None, due_within: Some(within), search: None };
+        let due = |offset: chrono::Duration| Reminder::new("Review".to_string(), now + offset, Some("ann".to_string()), None);
+
+        assert!(filter.matches(&due(chrono::Duration::seconds(1)), "ann", now));
+        assert!(filter.matches(&due(within), "ann", now));
+        assert!(!filter.matches(&due(within + chrono::Duration::seconds(1)), "ann", now));
+        assert!(!filter.matches(&due(chrono::Duration::zero()), "ann", now), "due now is no longer upcoming");
+        assert!(!filter.matches(&due(within), "bob", now));
+
+        let query = filter.query("ann", now);
+        assert_eq!((query.due_after, query.due_before), (Some(now), Some(now + within)));
+        assert_eq!(query.username.as_deref(), Some("ann"));
+    }
+
+    #[test]
+    fn sent_and_search_filters_combine() {
+        let now = DateTime::parse_from_rfc3339("2025-03-10T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut sent = Reminder::new("Cell biology".to_string(), now - chrono::Duration::days(1), Some("ann".to_string()), None);
+        sent.sent = true;
+        let mut pending = sent.clone();
+        pending.sent = false;
+
+        let only_sent = NotificationFilter { sent: Some(true), due_within: None, search: Some("BIOLOGY".to_string()) };
+        assert!(only_sent.matches(&sent, "ann", now));
+        assert!(!only_sent.matches(&pending, "ann", now));
+
+        let only_pending = NotificationFilter { sent: Some(false), due_within: None, search: Some("chemistry".to_string()) };
+        assert!(!only_pending.matches(&pending, "ann", now), "search must match too");
+        let only_pending = NotificationFilter { search: None, ..only_pending };
+        assert!(only_pending.matches(&pending, "ann", now), "overdue pending reminders are listed");
+        assert!(!NotificationFilter { sent: None, due_within: None, search: None }.matches(&pending, "ann", now));
+    }
+
+    #[tokio::test]
+    async fn server_and_local_filters_agree_on_the_due_within_boundary() {
+        let (_dir, url) = serve(None).await;
+        let client = ReminderClient::new(url).with_source(CLIENT_SOURCE);
+        let now = chrono::DurationRound::duration_trunc(Utc::now(), chrono::Duration::seconds(1)).unwrap();
+        let within = chrono::Duration::hours(2);
+        for (who, title, offset) in [
+            ("ann", "Inside", chrono::Duration::hours(1)),
+            ("ann", "At the end", within),
+            ("ann", "Just after", within + chrono::Duration::seconds(1)),
+            ("bob", "Someone else's", chrono::Duration::hours(1)),
+        ] {
+            let time = (now + offset).to_rfc3339();
+            client.create(&notification_request(&session(who), title, None, &time, None, &NotificationLinks::default())).await.unwrap();
+        }
+        let filter = NotificationFilter { sent: None, due_within: Some(within), search: None };
+
+        let from_server = client.list_matching(&filter.query("ann", now)).await.unwrap();
+        let mut titles: Vec<String> = from_server.iter().map(|r| title_and_memo(r).0).collect();
+        titles.sort();
+
+        assert_eq!(titles, ["At the end", "Inside"]);
+        assert!(from_server.iter().all(|r| filter.matches(r, "ann", now)));
+        let everything = client.list_all().await.unwrap();
+        assert_eq!(everything.iter().filter(|r| filter.matches(r, "ann", now)).count(), from_server.len());
+    }
+
+    #[test]
+    fn confirmation_reads_the_typed_answer() {
+        let confirm_yes = |typed: &str| confirm_from("delete 3 file(s)", Confirmation::Yes, false, true, &mut typed.as_bytes()).unwrap();
+        assert!(confirm_yes("yes\n"));
+        assert!(confirm_yes("  YES  \n"));
+        assert!(!confirm_yes("y\n"));
+        assert!(!confirm_yes("no\n"));
+        assert!(!confirm_yes(""), "end of input refuses");
+
+        let confirm_name = |typed: &str| confirm_from("delete it", Confirmation::Name("notes.txt"), false, true, &mut typed.as_bytes()).unwrap();
+        assert!(confirm_name("notes.txt\n"));
+        assert!(!confirm_name("Notes.txt\n"));
+        assert!(!confirm_name("yes\n"));
+    }
+
+    #[test]
+    fn piped_input_needs_force() {
+        let error = confirm_from("delete 3 file(s)", Confirmation::Yes, false, false, &mut "yes\n".as_bytes()).unwrap_err();
+        assert!(error.to_string().starts_with("Refusing to delete 3 file(s) without confirmation"), "{error}");
+
+        let mut untouched = "no\n".as_bytes();
+        assert!(confirm_from("delete 3 file(s)", Confirmation::Yes, true, false, &mut untouched).unwrap());
+        assert_eq!(untouched, b"no\n", "--yes must not read stdin");
+    }
+
+    #[test]
+    fn idle_timeout_falls_back_to_the_default_for_unusable_values() {
+        let default = chrono::Duration::minutes(DEFAULT_IDLE_TIMEOUT_MIN);
+
+        assert_eq!(idle_timeout(Some("90")), chrono::Duration::minutes(90));
+        assert_eq!(idle_timeout(Some("0")), chrono::Duration::zero());
+        for value in [None, Some("-5"), Some("soon"), Some("9223372036854775807"), Some("18446744073709551615")] {
+            assert_eq!(idle_timeout(value), default, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn content_preview_cuts_on_character_boundaries() {
+        let short = "é".repeat(150);
+        assert_eq!(content_preview(&short), short);
+
+        let long = "é".repeat(250);
+        assert_eq!(content_preview(&long), format!("{}...", "é".repeat(200)));
+        assert_eq!(content_preview(&format!("{}🧬", "a".repeat(199))), format!("{}🧬", "a".repeat(199)));
+    }
+
+    #[tokio::test]
+    async fn piped_non_ascii_upload_is_stored_whole() {
+        let (_dir, storage) = storage();
+        let session = signed_up(&storage, "ann").await;
+        // Byte 200 falls inside an "é"
+        let content = format!("a{}", "é".repeat(150));
+
+        finish_upload(&storage, &session, "accents.txt".to_string(), content.clone(), false).await.unwrap();
+
+        let files = storage.get_user_files(&session.user_id).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].content, content);
+    }
+}
@@ -0,0 +1,10 @@
+/// Crate version from Cargo.toml.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short commit the binaries were built from; `None` for builds outside a
+/// git checkout.
+pub const GIT_COMMIT: Option<&str> = option_env!("REMINDER_GIT_COMMIT");
+
+/// `VERSION` followed by the commit in parentheses when known, e.g.
+/// `0.1.0 (1f75183)`.
+pub const FULL_VERSION: &str = env!("REMINDER_FULL_VERSION");
@@ -0,0 +1,467 @@
+use crate::due_time::{DueTimeRules, DEFAULT_HOUR};
+use crate::models::QuietHours;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE_NAME: &str = "reminder.toml";
+
+/// Reminder server settings. Built-in defaults are overridden by the TOML
+/// file, which is in turn overridden by `REMINDER_*` environment variables.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub storage: StorageConfig,
+    pub notifications: NotificationsConfig,
+    pub scheduling: SchedulingConfig,
+    pub limits: LimitsConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind: String,
+    /// Shared secret for signed bearer tokens; unset runs unauthenticated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_secret: Option<String>,
+    /// Principals that may see and change every reminder and use /admin
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub admins: Vec<String>,
+    pub log_format: LogFormat,
+    /// PEM certificate chain; with `tls_key`, serves HTTPS on `bind`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+    /// Also serve plain HTTP here while TLS is on, e.g. while clients migrate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_bind: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "0.0.0.0:3000".to_string(),
+            shared_secret: None,
+            admins: Vec::new(),
+            log_format: LogFormat::Pretty,
+            tls_cert: None,
+            tls_key: None,
+            http_bind: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    /// Keeps the startup summary to one line
+    Compact,
+    /// One JSON object per log event, for log collectors
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub path: PathBuf,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("reminders.json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationsConfig {
+    /// How often to check for due reminders
+    pub interval_secs: u64,
+    /// JSON Lines file to append fire events to; unset disables the log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_log: Option<PathBuf>,
+    /// Size at which the event log is rotated to `<event_log>.1`
+    pub event_log_max_kb: u64,
+    /// `START-END` in server-local time (e.g. `22:00-07:00`) during which
+    /// reminders wait instead of firing; unset fires around the clock
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blackout: Option<String>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 10,
+            event_log: None,
+            event_log_max_kb: 10 * 1024,
+            blackout: None,
+        }
+    }
+}
+
+impl NotificationsConfig {
+    /// The blackout window. Assumes the config has been validated.
+    pub fn blackout_window(&self) -> Option<QuietHours> {
+        self.blackout.as_deref().and_then(|range| QuietHours::parse_range(range).ok())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulingConfig {
+    /// IANA zone for due times given without one; unset reads them as UTC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_timezone: Option<String>,
+    /// Hour of day for date-only due times
+    pub default_hour: u32,
+}
+
+impl Default for SchedulingConfig {
+    fn default() -> Self {
+        Self {
+            default_timezone: None,
+            default_hour: DEFAULT_HOUR,
+        }
+    }
+}
+
+impl SchedulingConfig {
+    /// The due time parsing rules. Assumes the config has been validated.
+    pub fn due_time_rules(&self) -> DueTimeRules {
+        DueTimeRules {
+            default_timezone: self.default_timezone.as_deref().and_then(|tz| tz.parse().ok()),
+            default_hour: self.default_hour,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    pub max_body_kb: usize,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self { max_body_kb: 64 }
+    }
+}
+
+impl Config {
+    /// Loads `explicit` if given, otherwise the first config file found in the
+    /// default locations (if any), then applies environment overrides and
+    /// validates the result. Returns the file that was read alongside.
+    pub fn load(explicit: Option<&Path>) -> Result<(Self, Option<PathBuf>)> {
+        let path = match explicit {
+            Some(path) => Some(path.to_path_buf()),
+            None => default_locations().into_iter().find(|p| p.exists()),
+        };
+
+        let mut config = match &path {
+            Some(path) => {
+                let data = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read config file {}", path.display()))?;
+                toml::from_str(&data)
+                    .with_context(|| format!("Invalid config file {}", path.display()))?
+            }
+            None => Self::default(),
+        };
+
+        config.apply_env()?;
+        config.validate()?;
+        Ok((config, path))
+    }
+
+    fn apply_env(&mut self) -> Result<()> {
+        if let Some(bind) = env("REMINDER_BIND") {
+            self.server.bind = bind;
+        }
+        if let Some(secret) = env("REMINDER_SHARED_SECRET") {
+            self.server.shared_secret = Some(secret);
+        }
+        if let Some(admins) = env("REMINDER_ADMINS") {
+            self.server.admins = admins
+                .split(',')
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect();
+        }
+        if let Some(format) = env("REMINDER_LOG_FORMAT") {
+            self.server.log_format = match format.as_str() {
+                "pretty" => LogFormat::Pretty,
+                "compact" => LogFormat::Compact,
+                "json" => LogFormat::Json,
+                other => bail!("REMINDER_LOG_FORMAT: expected 'pretty', 'compact', or 'json', got '{}'", other),
+            };
+        }
+        if let Some(path) = env("REMINDER_TLS_CERT") {
+            self.server.tls_cert = Some(PathBuf::from(path));
+        }
+        if let Some(path) = env("REMINDER_TLS_KEY") {
+            self.server.tls_key = Some(PathBuf::from(path));
+        }
+        if let Some(bind) = env("REMINDER_HTTP_BIND") {
+            self.server.http_bind = Some(bind);
+        }
+        if let Some(path) = env("REMINDER_STORAGE_PATH") {
+            self.storage.path = PathBuf::from(path);
+        }
+        if let Some(secs) = env("REMINDER_TICK_SECS") {
+            self.notifications.interval_secs = secs
+                .parse()
+                .map_err(|_| anyhow::anyhow!("REMINDER_TICK_SECS: expected a number of seconds, got '{}'", secs))?;
+        }
+        if let Some(path) = env("REMINDER_EVENT_LOG") {
+            self.notifications.event_log = Some(PathBuf::from(path));
+        }
+        if let Some(blackout) = env("REMINDER_BLACKOUT") {
+            self.notifications.blackout = Some(blackout);
+        }
+        if let Some(timezone) = env("REMINDER_DEFAULT_TIMEZONE") {
+            self.scheduling.default_timezone = Some(timezone);
+        }
+        if let Some(hour) = env("REMINDER_DEFAULT_HOUR") {
+            self.scheduling.default_hour = hour
+                .parse()
+                .map_err(|_| anyhow::anyhow!("REMINDER_DEFAULT_HOUR: expected an hour (0-23), got '{}'", hour))?;
+        }
+        if let Some(kb) = env("REMINDER_MAX_BODY_KB") {
+            self.limits.max_body_kb = kb
+                .parse()
+                .map_err(|_| anyhow::anyhow!("REMINDER_MAX_BODY_KB: expected a number of KB, got '{}'", kb))?;
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.server.bind.parse::<SocketAddr>().is_err() {
+            bail!("server.bind: '{}' is not a valid address (e.g., 0.0.0.0:3000)", self.server.bind);
+        }
+        if self.server.shared_secret.as_deref() == Some("") {
+            bail!("server.shared_secret: must not be empty (remove it to disable authentication)");
+        }
+        match (&self.server.tls_cert, &self.server.tls_key) {
+            (Some(_), None) => bail!("server.tls_cert: requires server.tls_key"),
+            (None, Some(_)) => bail!("server.tls_key: requires server.tls_cert"),
+            _ => {}
+        }
+        if let Some(http_bind) = &self.server.http_bind {
+            if http_bind.parse::<SocketAddr>().is_err() {
+                bail!("server.http_bind: '{}' is not a valid address (e.g., 0.0.0.0:8080)", http_bind);
+            }
+            if *http_bind == self.server.bind {
+                bail!("server.http_bind: must differ from server.bind");
+            }
+        }
+        if self.storage.path.as_os_str().is_empty() {
+            bail!("storage.path: must not be empty");
+        }
+        if self.notifications.interval_secs == 0 {
+            bail!("notifications.interval_secs: must be at least 1");
+        }
+        if self.notifications.event_log.as_ref().is_some_and(|p| p.as_os_str().is_empty()) {
+            bail!("notifications.event_log: must not be empty (remove it to disable the event log)");
+        }
+        if self.notifications.event_log_max_kb == 0 {
+            bail!("notifications.event_log_max_kb: must be at least 1");
+        }
+        if let Some(blackout) = &self.notifications.blackout {
+            if let Err(e) = QuietHours::parse_range(blackout) {
+                bail!("notifications.blackout: {}", e);
+            }
+        }
+        if let Some(timezone) = &self.scheduling.default_timezone {
+            if timezone.parse::<chrono_tz::Tz>().is_err() {
+                bail!("scheduling.default_timezone: '{}' is not a known timezone (e.g., Europe/Berlin)", timezone);
+            }
+        }
+        if self.scheduling.default_hour > 23 {
+            bail!("scheduling.default_hour: must be between 0 and 23");
+        }
+        if self.limits.max_body_kb == 0 {
+            bail!("limits.max_body_kb: must be at least 1");
+        }
+        if self.limits.max_body_kb.checked_mul(1024).is_none() {
+            bail!("limits.max_body_kb: {} KB is too large", self.limits.max_body_kb);
+        }
+        Ok(())
+    }
+
+    /// The config as TOML with secrets masked, for display.
+    pub fn to_redacted_toml(&self) -> Result<String> {
+        let mut shown = self.clone();
+        if shown.server.shared_secret.is_some() {
+            shown.server.shared_secret = Some("********".to_string());
+        }
+        toml::to_string_pretty(&shown).context("Failed to render config")
+    }
+}
+
+/// Default output of `init-config`.
+pub const ENV_EXAMPLE_FILE: &str = ".env.example";
+
+/// A commented `.env` file listing every environment variable the server and
+/// the CLIs read. Every entry starts commented out, since exporting a default
+/// would still override what reminder.toml sets.
+pub fn env_example() -> String {
+    let defaults = Config::default();
+    let set = |name: &str, value: &dyn std::fmt::Display| format!("# {}={}", name, value);
+    let unset = |name: &str, example: &str| format!("# {}={}", name, example);
+
+    let sections: Vec<(&str, Vec<(&str, String)>)> = vec![
+        ("Reminder server (overrides reminder.toml)", vec![
+            ("Address to listen on", set("REMINDER_BIND", &defaults.server.bind)),
+            ("Shared secret for signed bearer tokens, also read by quiz to sign requests (unset: no auth)", unset("REMINDER_SHARED_SECRET", "change-me")),
+            ("Comma-separated users who can see and change every reminder (unset by default)", unset("REMINDER_ADMINS", "alice,bob")),
+            ("Log format: pretty, compact, or json", set("REMINDER_LOG_FORMAT", &"pretty")),
+            ("Log level filter", set("RUST_LOG", &"info")),
+            ("PEM certificate and key; serve HTTPS when both are set (unset by default)", unset("REMINDER_TLS_CERT", "cert.pem")),
+            ("", unset("REMINDER_TLS_KEY", "key.pem")),
+            ("Extra plain-HTTP address while TLS is on (unset by default)", unset("REMINDER_HTTP_BIND", "0.0.0.0:8080")),
+            ("Reminder storage file", set("REMINDER_STORAGE_PATH", &defaults.storage.path.display())),
+            ("Seconds between checks for due reminders", set("REMINDER_TICK_SECS", &defaults.notifications.interval_secs)),
+            ("JSON Lines file recording every fired reminder (unset by default)", unset("REMINDER_EVENT_LOG", "notifications.log")),
+            ("Hold reminders until this window ends, in server-local time (unset by default)", unset("REMINDER_BLACKOUT", "22:00-07:00")),
+            ("Zone for due times given without one (unset: UTC)", unset("REMINDER_DEFAULT_TIMEZONE", "Europe/Berlin")),
+            ("Hour of day for date-only due times", set("REMINDER_DEFAULT_HOUR", &defaults.scheduling.default_hour)),
+            ("Maximum request body size in KB", set("REMINDER_MAX_BODY_KB", &defaults.limits.max_body_kb)),
+            ("Clear stored recurrences that no longer parse instead of warning (unset by default)", unset("REMINDER_STRICT_RECURRENCE", "1")),
+            ("Write storage files compactly with 0", set("REMINDER_PRETTY_JSON", &1)),
+        ]),
+        ("Clients (cli and quiz)", vec![
+            ("Reminder service URL", set("REMINDER_API_URL", &"http://localhost:3000")),
+            ("ASCII-only output without emoji or borders, like --plain (NO_COLOR also works; unset by default)", unset("QUIZ_PLAIN", "1")),
+        ]),
+        ("Quiz", vec![
+            ("Upload quota per user in MB", set("QUIZ_USER_QUOTA_MB", &20)),
+            ("Comma-separated file extensions quiz upload accepts", set("QUIZ_ALLOWED_EXTENSIONS", &"txt")),
+            ("Minutes of inactivity before you are logged out", set("QUIZ_IDLE_TIMEOUT_MIN", &30)),
+            ("Seconds a yes/no prompt such as the upload confirmation waits before cancelling (0 waits forever)", set("QUIZ_PROMPT_TIMEOUT_SECS", &60)),
+            ("Size at which the activity log is rotated, in KB", set("QUIZ_ACTIVITY_LOG_MAX_KB", &1024)),
+            ("Reject signup passwords that score below good instead of warning (unset by default)", unset("QUIZ_ENFORCE_STRONG", "1")),
+            ("Base64 32-byte key encrypting uploaded file content. Losing it loses the data (unset by default)", unset("QUIZ_ENCRYPTION_KEY", "")),
+        ]),
+    ];
+
+    let mut out = String::from(
+        "# Environment variables read by the reminder server, cli, and quiz.\n\
+         # Generated by `init-config`. Values shown are the defaults, or examples\n\
+         # for variables that are unset by default. Uncomment what you change.\n",
+    );
+    for (title, vars) in sections {
+        out.push_str(&format!("\n# --- {} ---\n", title));
+        for (help, line) in vars {
+            if !help.is_empty() {
+                out.push_str(&format!("# {}\n", help));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Writes `env_example()` to `path`, refusing to replace an existing file
+/// unless `force` is set.
+pub fn write_env_example(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        bail!("{} already exists (use --force to overwrite)", path.display());
+    }
+    std::fs::write(path, env_example()).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// `./reminder.toml`, then `$XDG_CONFIG_HOME/reminder/reminder.toml`
+/// (or `~/.config/reminder/reminder.toml`).
+fn default_locations() -> Vec<PathBuf> {
+    let mut locations = vec![PathBuf::from(CONFIG_FILE_NAME)];
+
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    if let Some(dir) = config_home {
+        locations.push(dir.join("reminder").join(CONFIG_FILE_NAME));
+    }
+
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_config(contents: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    // The only test that calls `load`; the others parse and validate directly
+    // so the REMINDER_* variables set here can't leak into them
+    #[test]
+    fn environment_overrides_file_which_overrides_defaults() {
+        let (_dir, path) = write_config(
+            "[server]\nbind = \"127.0.0.1:4000\"\n\n[notifications]\ninterval_secs = 30\n\n[limits]\nmax_body_kb = 128\n",
+        );
+        std::env::set_var("REMINDER_TICK_SECS", "5");
+        std::env::set_var("REMINDER_ADMINS", "alice, ,bob");
+
+        let loaded = Config::load(Some(&path));
+        std::env::remove_var("REMINDER_TICK_SECS");
+        std::env::remove_var("REMINDER_ADMINS");
+        let (config, read_from) = loaded.unwrap();
+
+        assert_eq!(read_from, Some(path));
+        assert_eq!(config.server.bind, "127.0.0.1:4000");
+        assert_eq!(config.limits.max_body_kb, 128);
+        assert_eq!(config.notifications.interval_secs, 5);
+        assert_eq!(config.server.admins, ["alice", "bob"]);
+        assert_eq!(config.scheduling.default_hour, DEFAULT_HOUR);
+        assert_eq!(config.storage.path, PathBuf::from("reminders.json"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = toml::from_str::<Config>("[server]\nbnid = \"127.0.0.1:4000\"\n").unwrap_err();
+
+        assert!(error.to_string().contains("bnid"), "{}", error);
+    }
+
+    #[test]
+    fn invalid_values_fail_validation() {
+        for (contents, expected) in [
+            ("[server]\nbind = \"localhost\"\n", "server.bind"),
+            ("[server]\ntls_cert = \"cert.pem\"\n", "server.tls_cert"),
+            ("[notifications]\ninterval_secs = 0\n", "notifications.interval_secs"),
+            ("[scheduling]\ndefault_timezone = \"Mars/Olympus\"\n", "scheduling.default_timezone"),
+        ] {
+            let config: Config = toml::from_str(contents).unwrap();
+
+            let error = config.validate().unwrap_err();
+
+            assert!(error.to_string().starts_with(expected), "{}: {}", contents, error);
+        }
+    }
+
+    #[test]
+    fn body_limit_that_overflows_in_bytes_fails_validation() {
+        let mut config = Config::default();
+        config.limits.max_body_kb = usize::MAX / 1024 + 1;
+
+        let error = config.validate().unwrap_err();
+
+        assert!(error.to_string().starts_with("limits.max_body_kb"), "{}", error);
+    }
+}
@@ -0,0 +1,487 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, SubsecRound, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Hour of day used for date-only due times unless configured otherwise.
+pub const DEFAULT_HOUR: u32 = 9;
+
+/// Inputs `parse_due_time` accepts, for error messages and help text.
+pub const ACCEPTED_EXAMPLES: &[&str] = &[
+    "2025-11-04T15:30:00Z",
+    "2025-11-04T15:30:00+02:00",
+    "2025-11-04 15:30",
+    "2025-11-04T15:30:00",
+    "2025-11-04 15:30 Europe/Berlin",
+    "2025-11-04",
+];
+
+/// How to fill in the parts of a due time the user left out.
+#[derive(Debug, Clone, Copy)]
+pub struct DueTimeRules {
+    /// Zone for times without one; `None` reads them as UTC
+    pub default_timezone: Option<Tz>,
+    /// Hour of day for date-only input
+    pub default_hour: u32,
+}
+
+impl Default for DueTimeRules {
+    fn default() -> Self {
+        Self {
+            default_timezone: None,
+            default_hour: DEFAULT_HOUR,
+        }
+    }
+}
+
+/// The part of a due time that could not be read.
+#[derive(Debug, thiserror::Error)]
+pub enum DueTimeError {
+    #[error("due time is empty")]
+    Empty,
+    #[error("date '{0}' is not a valid YYYY-MM-DD date")]
+    Date(String),
+    #[error("time '{0}' is not a valid HH:MM or HH:MM:SS time")]
+    Time(String),
+    #[error("timezone '{0}' is not Z, an offset like +02:00, or a name like Europe/Berlin")]
+    Timezone(String),
+    #[error("{time} does not exist in {zone} (skipped by a daylight saving change)")]
+    Nonexistent { time: NaiveDateTime, zone: Tz },
+    #[error("{0} does not exist in the local timezone (skipped by a daylight saving change)")]
+    NonexistentLocal(NaiveDateTime),
+}
+
+/// A due time as written, before any missing zone is filled in.
+enum Parsed {
+    Exact(DateTime<Utc>),
+    Naive(NaiveDateTime),
+    Date(NaiveDate),
+}
+
+/// `ACCEPTED_EXAMPLES` as a comma-separated list.
+pub fn accepted_examples() -> String {
+    ACCEPTED_EXAMPLES.join(", ")
+}
+
+/// Parses a due time leniently: RFC 3339, a space instead of `T`, missing
+/// seconds, a missing zone (read in `rules.default_timezone`, else UTC), a
+/// zone name after the time, or a bare date at `rules.default_hour`.
+pub fn parse_due_time(input: &str, rules: &DueTimeRules) -> Result<DateTime<Utc>, DueTimeError> {
+    match parse(input)? {
+        Parsed::Exact(time) => Ok(time),
+        Parsed::Naive(naive) => in_default_zone(naive, rules),
+        Parsed::Date(date) => in_default_zone(at_hour(date, rules.default_hour)?, rules),
+    }
+}
+
+/// Like `parse_due_time`, but reads times without a zone in this machine's
+/// local timezone. For the command-line tools, where "15:30" means the
+/// user's 15:30 rather than the server's.
+pub fn parse_local_due_time(input: &str, default_hour: u32) -> Result<DateTime<Utc>, DueTimeError> {
+    let naive = match parse(input)? {
+        Parsed::Exact(time) => return Ok(time),
+        Parsed::Naive(naive) => naive,
+        Parsed::Date(date) => at_hour(date, default_hour)?,
+    };
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or(DueTimeError::NonexistentLocal(naive))
+}
+
+fn parse(input: &str) -> Result<Parsed, DueTimeError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(DueTimeError::Empty);
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(Parsed::Exact(time.with_timezone(&Utc)));
+    }
+
+    let (date, rest) = match input.split_once(['T', ' ']) {
+        Some((date, rest)) => (date, rest.trim()),
+        None => (input, ""),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| DueTimeError::Date(date.to_string()))?;
+
+    if rest.is_empty() {
+        return Ok(Parsed::Date(date));
+    }
+
+    let (time, zone) = split_zone(rest);
+    let time = ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveTime::parse_from_str(time, fmt).ok())
+        .ok_or_else(|| DueTimeError::Time(time.to_string()))?;
+    let naive = date.and_time(time);
+
+    let Some(zone) = zone else {
+        return Ok(Parsed::Naive(naive));
+    };
+    if let Some(offset) = parse_fixed_offset(zone) {
+        return offset
+            .from_local_datetime(&naive)
+            .single()
+            .map(|time| Parsed::Exact(time.with_timezone(&Utc)))
+            .ok_or_else(|| DueTimeError::Timezone(zone.to_string()));
+    }
+    let tz: Tz = zone.parse().map_err(|_| DueTimeError::Timezone(zone.to_string()))?;
+    in_zone(naive, tz).map(Parsed::Exact)
+}
+
+fn at_hour(date: NaiveDate, hour: u32) -> Result<NaiveDateTime, DueTimeError> {
+    date.and_hms_opt(hour, 0, 0)
+        .ok_or_else(|| DueTimeError::Time(format!("{}:00", hour)))
+}
+
+/// Splits `15:30Z`, `15:30+02:00`, or `15:30 Europe/Berlin` into the time and
+/// the zone, if there is one.
+fn split_zone(rest: &str) -> (&str, Option<&str>) {
+    if let Some(time) = rest.strip_suffix(['Z', 'z']) {
+        return (time.trim_end(), Some("Z"));
+    }
+    if let Some((time, zone)) = rest.split_once(char::is_whitespace) {
+        return (time, Some(zone.trim()));
+    }
+    match rest.find(['+', '-']) {
+        Some(i) => (&rest[..i], Some(&rest[i..])),
+        None => (rest, None),
+    }
+}
+
+/// `Z`, `UTC`, `+02:00`, `+0200`, or `+02`.
+fn parse_fixed_offset(zone: &str) -> Option<FixedOffset> {
+    if zone.eq_ignore_ascii_case("z") || zone.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, digits) = match zone.as_bytes().first()? {
+        b'+' => (1, &zone[1..]),
+        b'-' => (-1, &zone[1..]),
+        _ => return None,
+    };
+    let digits = digits.replace(':', "");
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (digits[..2].parse::<i32>().ok()?, digits[2..].parse::<i32>().ok()?),
+        _ => return None,
+    };
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Reads a time without a zone the way `parse_due_time` does.
+pub fn in_default_zone(naive: NaiveDateTime, rules: &DueTimeRules) -> Result<DateTime<Utc>, DueTimeError> {
+    match rules.default_timezone {
+        Some(tz) => in_zone(naive, tz),
+        None => Ok(naive.and_utc()),
+    }
+}
+
+/// Ambiguous times (clocks going back) resolve to the earlier instant.
+fn in_zone(naive: NaiveDateTime, zone: Tz) -> Result<DateTime<Utc>, DueTimeError> {
+    naive
+        .and_local_timezone(zone)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or(DueTimeError::Nonexistent { time: naive, zone })
+}
+
+/// Hour "tonight" stands for when no time is given.
+const TONIGHT_HOUR: u32 = 20;
+
+/// A due time found in free text, with the words that gave it taken out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedDueTime {
+    pub due_time: DateTime<Utc>,
+    /// The text without the time phrase, e.g. "call mom"
+    pub rest: String,
+}
+
+/// Finds a due time in a sentence such as "remind me to call mom tomorrow at
+/// 5pm". Understands `in 20 minutes` / `in 2h`, `today`, `tonight`,
+/// `tomorrow`, weekdays (`friday`, `next friday`, `on friday`; always the
+/// next one after today), `YYYY-MM-DD` dates, and clock times (`at 5pm`,
+/// `5:30 pm`, `17:30`, `noon`, `midnight`). Dates and times are read in
+/// `rules.default_timezone` (else UTC); a date without a time gets
+/// `rules.default_hour`, and a time without a date that has already passed
+/// today means tomorrow. `None` when there is no time phrase at all.
+pub fn extract_due_time(text: &str, now: DateTime<Utc>, rules: &DueTimeRules) -> Option<ExtractedDueTime> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let words: Vec<String> = tokens
+        .iter()
+        .map(|t| t.trim_end_matches([',', '.', '!', '?', ';']).to_lowercase())
+        .collect();
+    let mut used = vec![false; tokens.len()];
+
+    let today = match rules.default_timezone {
+        Some(tz) => now.with_timezone(&tz).date_naive(),
+        None => now.date_naive(),
+    };
+
+    let mut offset = None;
+    let mut date = None;
+    let mut clock = None;
+    let mut tonight = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i].as_str();
+        let next = words.get(i + 1).map(String::as_str);
+
+        if offset.is_none() && word == "in" {
+            if let Some((duration, len)) = relative_offset(&words[i + 1..]) {
+                offset = Some(duration);
+                used[i..=i + len].fill(true);
+                i += len + 1;
+                continue;
+            }
+        }
+        if date.is_none() {
+            let (lead, candidate) = match (word, next) {
+                ("next" | "on" | "this", Some(next)) => (1, next),
+                _ => (0, word),
+            };
+            let found = match candidate {
+                "today" => Some(today),
+                "tonight" => {
+                    tonight = true;
+                    Some(today)
+                }
+                "tomorrow" => today.succ_opt(),
+                other => weekday(other)
+                    .map(|day| next_weekday(today, day))
+                    .or_else(|| NaiveDate::parse_from_str(other, "%Y-%m-%d").ok()),
+            };
+            if let Some(found) = found {
+                date = Some(found);
+                used[i..=i + lead].fill(true);
+                i += lead + 1;
+                continue;
+            }
+        }
+        if clock.is_none() {
+            let lead = usize::from(word == "at");
+            if let Some((time, len)) = clock_time(&words[i + lead..], lead == 1) {
+                clock = Some(time);
+                used[i..i + lead + len].fill(true);
+                i += lead + len;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    let due_time = if let Some(offset) = offset {
+        now.trunc_subsecs(0).checked_add_signed(offset)?
+    } else {
+        if date.is_none() && clock.is_none() {
+            return None;
+        }
+        let hour = if tonight { TONIGHT_HOUR } else { rules.default_hour };
+        let time = clock.or_else(|| NaiveTime::from_hms_opt(hour, 0, 0))?;
+        let mut due = in_default_zone(date.unwrap_or(today).and_time(time), rules).ok()?;
+        if date.is_none() && due <= now {
+            due = in_default_zone(today.succ_opt()?.and_time(time), rules).ok()?;
+        }
+        due
+    };
+
+    let rest: Vec<&str> = tokens
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| !**used)
+        .map(|(token, _)| *token)
+        .collect();
+    Some(ExtractedDueTime { due_time, rest: rest.join(" ") })
+}
+
+/// `20 minutes`, `an hour`, `3 days`, or a compact `2h`, and how many words
+/// it took.
+fn relative_offset(words: &[String]) -> Option<(chrono::Duration, usize)> {
+    let first = words.first()?;
+    if let Ok(duration) = crate::durations::parse_offset(first) {
+        return Some((duration, 1));
+    }
+
+    let amount: i64 = match first.as_str() {
+        "a" | "an" => 1,
+        n => n.parse().ok().filter(|n| *n > 0)?,
+    };
+    let duration = match words.get(1)?.trim_end_matches('s') {
+        "minute" | "min" => chrono::Duration::try_minutes(amount),
+        "hour" | "hr" => chrono::Duration::try_hours(amount),
+        "day" => chrono::Duration::try_days(amount),
+        "week" => chrono::Duration::try_weeks(amount),
+        _ => return None,
+    };
+    // Offsets too large to represent aren't a time the text can mean
+    let duration = duration.filter(|d| *d <= chrono::Duration::days(crate::durations::MAX_OFFSET_DAYS))?;
+    Some((duration, 2))
+}
+
+/// A clock time at the start of `words`, and how many words it took. Bare
+/// hours (`5`) only count after "at", where they can't be anything else.
+fn clock_time(words: &[String], after_at: bool) -> Option<(NaiveTime, usize)> {
+    let first = words.first()?.as_str();
+    match first {
+        "noon" | "midday" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" => return Some((NaiveTime::from_hms_opt(0, 0, 0)?, 1)),
+        _ => {}
+    }
+
+    // "5pm" / "5:30pm", or "5 pm" split over two words
+    let (digits, meridiem, len) = match first.strip_suffix("am").map(|d| (d, "am")).or_else(|| first.strip_suffix("pm").map(|d| (d, "pm"))) {
+        Some((digits, meridiem)) if !digits.is_empty() => (digits, Some(meridiem), 1),
+        _ => match words.get(1).map(String::as_str) {
+            Some(m @ ("am" | "pm")) => (first, Some(m), 2),
+            _ => (first, None, 1),
+        },
+    };
+
+    let (hour, minute) = match digits.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None if meridiem.is_some() || after_at => (digits.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).map(|time| (time, len))
+}
+
+fn weekday(word: &str) -> Option<chrono::Weekday> {
+    use chrono::Weekday::*;
+    Some(match word {
+        "monday" => Mon,
+        "tuesday" => Tue,
+        "wednesday" => Wed,
+        "thursday" => Thu,
+        "friday" => Fri,
+        "saturday" => Sat,
+        "sunday" => Sun,
+        _ => return None,
+    })
+}
+
+/// The first `day` after `today`.
+fn next_weekday(today: NaiveDate, day: chrono::Weekday) -> NaiveDate {
+    use chrono::Datelike;
+    let ahead = (7 + day.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64 - 1) % 7 + 1;
+    today + chrono::Duration::days(ahead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(input: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(input).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn accepts_every_documented_form() {
+        let rules = DueTimeRules::default();
+        let cases = [
+            ("2025-11-04T15:30:00Z", "2025-11-04T15:30:00Z"),
+            ("2025-11-04T15:30:00+02:00", "2025-11-04T13:30:00Z"),
+            ("2025-11-04 15:30", "2025-11-04T15:30:00Z"),
+            ("2025-11-04T15:30", "2025-11-04T15:30:00Z"),
+            ("2025-11-04T15:30:00", "2025-11-04T15:30:00Z"),
+            ("2025-11-04 15:30:45.5", "2025-11-04T15:30:45.5Z"),
+            ("2025-11-04 15:30Z", "2025-11-04T15:30:00Z"),
+            ("2025-11-04 15:30 UTC", "2025-11-04T15:30:00Z"),
+            ("2025-11-04 15:30+0200", "2025-11-04T13:30:00Z"),
+            ("2025-11-04 15:30-05", "2025-11-04T20:30:00Z"),
+            ("2025-11-04 15:30 Europe/Berlin", "2025-11-04T14:30:00Z"),
+            ("2025-11-04", "2025-11-04T09:00:00Z"),
+            ("  2025-11-04 15:30  ", "2025-11-04T15:30:00Z"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(parse_due_time(input, &rules).unwrap(), utc(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn times_without_a_zone_use_the_configured_defaults() {
+        let rules = DueTimeRules {
+            default_timezone: Some(chrono_tz::America::New_York),
+            default_hour: 7,
+        };
+
+        assert_eq!(parse_due_time("2025-07-01 15:30", &rules).unwrap(), utc("2025-07-01T19:30:00Z"));
+        assert_eq!(parse_due_time("2025-07-01", &rules).unwrap(), utc("2025-07-01T11:00:00Z"));
+        // An explicit zone still wins
+        assert_eq!(parse_due_time("2025-07-01 15:30Z", &rules).unwrap(), utc("2025-07-01T15:30:00Z"));
+    }
+
+    #[test]
+    fn daylight_saving_gaps_and_overlaps() {
+        let rules = DueTimeRules {
+            default_timezone: Some(chrono_tz::Europe::Berlin),
+            ..DueTimeRules::default()
+        };
+
+        // 02:30 is skipped when Berlin springs forward
+        assert!(matches!(
+            parse_due_time("2025-03-30 02:30", &rules),
+            Err(DueTimeError::Nonexistent { .. })
+        ));
+        // 02:30 happens twice when it falls back; the earlier one is taken
+        assert_eq!(parse_due_time("2025-10-26 02:30", &rules).unwrap(), utc("2025-10-26T00:30:00Z"));
+    }
+
+    #[test]
+    fn extracts_the_time_phrase_from_a_sentence() {
+        let now = utc("2025-11-04T10:00:00Z");
+        let rules = DueTimeRules::default();
+        let cases = [
+            ("remind me to call mom tomorrow at 5pm", "2025-11-05T17:00:00Z", "remind me to call mom"),
+            ("stretch in 20 minutes", "2025-11-04T10:20:00Z", "stretch"),
+            ("stretch in 2h", "2025-11-04T12:00:00Z", "stretch"),
+            ("pay rent on friday", "2025-11-07T09:00:00Z", "pay rent"),
+            ("take out the bins tonight", "2025-11-04T20:00:00Z", "take out the bins"),
+            ("standup at 9", "2025-11-05T09:00:00Z", "standup"),
+        ];
+
+        for (text, due, rest) in cases {
+            let extracted = extract_due_time(text, now, &rules).unwrap();
+            assert_eq!(extracted.due_time, utc(due), "{}", text);
+            assert_eq!(extracted.rest, rest, "{}", text);
+        }
+        assert_eq!(extract_due_time("call mom", now, &rules), None);
+    }
+
+    #[test]
+    fn huge_relative_offsets_are_not_a_time() {
+        let now = utc("2025-11-04T10:00:00Z");
+        let rules = DueTimeRules::default();
+
+        for text in ["call mom in 999999999999999 minutes", "call mom in 99999999 weeks", "call mom in 99999999999999w"] {
+            assert_eq!(extract_due_time(text, now, &rules), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn errors_name_the_part_that_could_not_be_read() {
+        let rules = DueTimeRules::default();
+
+        assert!(matches!(parse_due_time("   ", &rules), Err(DueTimeError::Empty)));
+        assert!(matches!(parse_due_time("2025-13-01", &rules), Err(DueTimeError::Date(d)) if d == "2025-13-01"));
+        assert!(matches!(parse_due_time("tomorrow", &rules), Err(DueTimeError::Date(_))));
+        assert!(matches!(parse_due_time("2025-11-04 25:00", &rules), Err(DueTimeError::Time(t)) if t == "25:00"));
+        assert!(matches!(parse_due_time("2025-11-04 15:30 Mars/Olympus", &rules), Err(DueTimeError::Timezone(z)) if z == "Mars/Olympus"));
+        assert!(matches!(parse_due_time("2025-11-04 15:30+25:00", &rules), Err(DueTimeError::Timezone(_))));
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::{bail, Result};
+use chrono::Duration;
+
+/// Longest offset accepted, in days (about a century).
+pub const MAX_OFFSET_DAYS: i64 = 36_525;
+
+/// Parses a compact offset such as `1w`, `3d`, `2h`, or `30m`, up to
+/// `MAX_OFFSET_DAYS`.
+pub fn parse_offset(input: &str) -> Result<Duration> {
+    let input = input.trim().to_lowercase();
+
+    let Some(unit) = input.chars().last() else {
+        bail!("Empty duration");
+    };
+
+    let amount: i64 = input[..input.len() - unit.len_utf8()]
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid duration '{}'. Use a number followed by w, d, h, or m (e.g., 3d)", input))?;
+
+    if amount <= 0 {
+        bail!("Duration '{}' must be positive", input);
+    }
+
+    let duration = match unit {
+        'w' => Duration::try_weeks(amount),
+        'd' => Duration::try_days(amount),
+        'h' => Duration::try_hours(amount),
+        'm' => Duration::try_minutes(amount),
+        _ => bail!("Unknown duration unit in '{}'. Use w, d, h, or m", input),
+    };
+    match duration {
+        Some(duration) if duration <= Duration::days(MAX_OFFSET_DAYS) => Ok(duration),
+        _ => bail!("Duration '{}' is longer than {} days", input, MAX_OFFSET_DAYS),
+    }
+}
+
+/// Parses a comma-separated list of offsets, e.g. `1w,3d,1d,2h`.
+pub fn parse_offsets(input: &str) -> Result<Vec<(String, Duration)>> {
+    input
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| Ok((s.to_string(), parse_offset(s)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_unit() {
+        assert_eq!(parse_offset("1w").unwrap(), Duration::weeks(1));
+        assert_eq!(parse_offset("3D").unwrap(), Duration::days(3));
+        assert_eq!(parse_offset(" 2h ").unwrap(), Duration::hours(2));
+        assert_eq!(parse_offset("30m").unwrap(), Duration::minutes(30));
+    }
+
+    #[test]
+    fn rejects_malformed_offsets() {
+        for input in ["", "d", "3", "3x", "-1d", "0h", "1.5d", "3é"] {
+            assert!(parse_offset(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn huge_offsets_are_rejected_instead_of_overflowing() {
+        for input in ["99999999999999w", "9999999999999999m", "36526d"] {
+            let error = parse_offset(input).unwrap_err();
+            assert!(error.to_string().contains("longer than"), "{}: {}", input, error);
+        }
+        assert_eq!(parse_offset("36525d").unwrap(), Duration::days(MAX_OFFSET_DAYS));
+    }
+
+    #[test]
+    fn lists_keep_their_order_and_skip_blanks() {
+        let offsets = parse_offsets("1w, 3d,,2h").unwrap();
+
+        let labels: Vec<&str> = offsets.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, ["1w", "3d", "2h"]);
+        assert!(parse_offsets("1w,nope").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::storage::StorageError;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// Base64 of a 32-byte key, e.g. from `openssl rand -base64 32`.
+pub const KEY_ENV: &str = "QUIZ_ENCRYPTION_KEY";
+
+/// Marks stored content as `<prefix><base64(nonce || ciphertext)>`, so
+/// plaintext written before encryption was enabled still loads.
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM for file content at rest.
+pub struct ContentCipher {
+    cipher: Aes256Gcm,
+}
+
+impl ContentCipher {
+    /// The cipher for `QUIZ_ENCRYPTION_KEY`, or `None` when it is unset.
+    pub fn from_env() -> Result<Option<Self>, StorageError> {
+        match std::env::var(KEY_ENV).ok().filter(|v| !v.is_empty()) {
+            Some(key) => Self::from_base64(&key).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn from_base64(key: &str) -> Result<Self, StorageError> {
+        let bytes: [u8; 32] = BASE64
+            .decode(key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| StorageError::Encryption(format!("{} must be 32 bytes, base64-encoded", KEY_ENV)))?;
+
+        Ok(Self {
+            cipher: Aes256Gcm::new(&Key::<Aes256Gcm>::from(bytes)),
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, StorageError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| StorageError::Encryption("Failed to encrypt file content".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(sealed)))
+    }
+
+    /// Decrypts content written by `encrypt`; plaintext passes through.
+    pub fn decrypt(&self, stored: &str) -> Result<String, StorageError> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+
+        let failed = || StorageError::Encryption(format!("Failed to decrypt file content; is {} the key it was written with?", KEY_ENV));
+        let sealed = BASE64.decode(encoded).map_err(|_| failed())?;
+        let (nonce, ciphertext) = sealed.split_first_chunk::<NONCE_LEN>().ok_or_else(failed)?;
+        let plaintext = self
+            .cipher
+            .decrypt(&Nonce::from(*nonce), ciphertext)
+            .map_err(|_| failed())?;
+
+        String::from_utf8(plaintext).map_err(|_| failed())
+    }
+}
+
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(ENCRYPTED_PREFIX)
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// One line of the event log, written each time a reminder fires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FireEvent {
+    pub id: String,
+    pub username: Option<String>,
+    pub fired_at: DateTime<Utc>,
+    pub was_recurring: bool,
+}
+
+/// Append-only JSON Lines log of fired notifications. When the file would grow
+/// past `max_bytes` it is moved to `<path>.1` (replacing any older rotation)
+/// and a fresh file is started, so at most two files' worth is kept.
+pub struct EventLog {
+    path: PathBuf,
+    max_bytes: u64,
+}
+
+impl EventLog {
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes,
+        }
+    }
+
+    pub fn append(&self, event: &FireEvent) -> io::Result<()> {
+        let mut line = serde_json::to_string(event)?;
+        line.push('\n');
+
+        let current = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if current > 0 && current + line.len() as u64 > self.max_bytes {
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(".1");
+            fs::rename(&self.path, rotated)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
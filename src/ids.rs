@@ -0,0 +1,113 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Produces ids for new records (reminders, users, files, series).
+pub trait IdGenerator {
+    fn next_id(&self) -> String;
+}
+
+/// Random v4 UUIDs; what every record gets unless a test installs something else.
+#[derive(Debug, Default)]
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn next_id(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+/// `<prefix>-1`, `<prefix>-2`, ..., safe to share between threads.
+#[derive(Debug)]
+pub struct SequentialIds {
+    prefix: String,
+    next: AtomicU64,
+}
+
+impl SequentialIds {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&self) -> String {
+        format!("{}-{}", self.prefix, self.next.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+thread_local! {
+    static INSTALLED: RefCell<Option<Rc<dyn IdGenerator>>> = const { RefCell::new(None) };
+}
+
+/// A new record id, from the generator installed on this thread with
+/// `install`, or a random UUID.
+pub fn next_id() -> String {
+    INSTALLED
+        .with(|installed| installed.borrow().as_ref().map(|generator| generator.next_id()))
+        .unwrap_or_else(|| RandomIds.next_id())
+}
+
+/// Uses `generator` for ids created on this thread until the returned guard
+/// is dropped, so a test can assert on the ids records get. Per thread, so
+/// tests running in parallel don't see each other's generators.
+pub fn install(generator: impl IdGenerator + 'static) -> InstalledIds {
+    let previous = INSTALLED.with(|installed| installed.replace(Some(Rc::new(generator))));
+    InstalledIds { previous }
+}
+
+/// Restores the previously installed generator when dropped.
+pub struct InstalledIds {
+    previous: Option<Rc<dyn IdGenerator>>,
+}
+
+impl Drop for InstalledIds {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        INSTALLED.with(|installed| *installed.borrow_mut() = previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Reminder;
+    use chrono::Utc;
+
+    #[test]
+    fn installed_generator_numbers_new_records() {
+        let _ids = install(SequentialIds::new("reminder"));
+
+        let first = Reminder::new("Stretch".to_string(), Utc::now(), None, None);
+        let second = Reminder::new("Drink water".to_string(), Utc::now(), None, None);
+
+        assert_eq!(first.id, "reminder-1");
+        assert_eq!(second.id, "reminder-2");
+    }
+
+    #[test]
+    fn dropping_the_guard_restores_the_previous_generator() {
+        let _outer = install(SequentialIds::new("outer"));
+        assert_eq!(next_id(), "outer-1");
+
+        {
+            let _inner = install(SequentialIds::new("inner"));
+            assert_eq!(next_id(), "inner-1");
+        }
+
+        assert_eq!(next_id(), "outer-2");
+    }
+
+    #[test]
+    fn other_threads_keep_random_ids() {
+        let _ids = install(SequentialIds::new("local"));
+
+        let elsewhere = std::thread::spawn(next_id).join().unwrap();
+
+        assert!(uuid::Uuid::parse_str(&elsewhere).is_ok(), "{}", elsewhere);
+        assert_eq!(next_id(), "local-1");
+    }
+}
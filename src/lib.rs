@@ -0,0 +1,20 @@
+pub mod auth;
+pub mod build_info;
+pub mod config;
+pub mod due_time;
+pub mod durations;
+pub mod encryption;
+pub mod event_log;
+pub mod ids;
+pub mod models;
+pub mod output;
+pub mod preference_storage;
+pub mod reminder_client;
+pub mod server;
+pub mod storage;
+pub mod summary;
+pub mod task_import;
+pub mod template_storage;
+pub mod tls;
+pub mod user_models;
+pub mod user_storage;
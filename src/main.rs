@@ -1,210 +1,302 @@
-mod models;
-mod storage;
-pub mod user_models;
-pub mod user_storage;
-
-use axum::{
-    extract::State,
-    http::StatusCode,
-    routing::{get, post},
-    Json, Router,
-};
-use chrono::{DateTime, Utc};
-use models::{CreateReminderRequest, CreateReminderResponse, Reminder, ReminderListResponse};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use clap::Parser;
+use reminder_service::build_info;
+use reminder_service::config::{Config, LogFormat};
+use reminder_service::event_log::EventLog;
+use reminder_service::preference_storage::PreferenceStorage;
+use reminder_service::server::{build_router, notification_service, print_upcoming_summary, AppState};
+use reminder_service::storage::{ReminderStorage, StorageLock};
+use reminder_service::template_storage::TemplateStorage;
+use reminder_service::tls;
+use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
-use storage::ReminderStorage;
-use tokio::time::{interval, Duration};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
 
-struct AppState {
-    storage: Arc<ReminderStorage>,
+/// How long open HTTPS connections get to finish after Ctrl+C.
+const HTTPS_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+const ENDPOINTS: &[(&str, &str, &str)] = &[
+    ("GET", "/healthz", "Notification loop health (503 while storage keeps failing or the loop has stopped)"),
+    ("GET", "/version", "Server version and the git commit it was built from"),
+    ("GET", "/capabilities", "Server version, auth and size limits, and accepted input formats"),
+    ("POST", "/reminders", "Create a new reminder (returns the stored reminder and a Location header)"),
+    ("GET", "/reminders", "View upcoming reminders (filter with ?username=&group=&sent=&due_after=&due_before=&tags=a,b&match=any|all)"),
+    ("POST", "/reminders/parse", "Create a reminder from a sentence like 'call mom tomorrow at 5pm'"),
+    ("POST", "/reminders/preview", "Preview the occurrences a new reminder would have"),
+    ("GET", "/reminders/due-soon", "Upcoming reminders due within ?within_minutes= (default 60)"),
+    ("GET", "/reminders/search", "Search upcoming reminders (?q=word)"),
+    ("POST", "/reminders/test-notify", "Send a test notification"),
+    ("POST", "/reminders/import", "Import a task export CSV or .ics calendar (?format=todoist-csv|google-tasks-csv|ics&dry_run=true&before=30m)"),
+    ("POST", "/reminders/purge", "Delete old sent one-time reminders ({\"sent\": true, \"older_than_days\": n})"),
+    ("POST", "/reminders/reassign", "Move all of a user's reminders to another ({\"from\": old, \"username\": new})"),
+    ("POST", "/reminders/transfer", "Admin: give everything a user owns, sent reminders included, to another ({\"from\": old, \"to\": new})"),
+    ("GET", "/reminders/:id", "View a single reminder"),
+    ("PATCH", "/reminders/:id", "Update a reminder"),
+    ("GET", "/reminders/:id/occurrences", "Preview upcoming occurrences"),
+    ("DELETE", "/reminders/:id", "Delete a reminder"),
+    ("POST", "/reminders/:id/snooze", "Snooze a reminder"),
+    ("POST", "/reminders/:id/complete", "Mark a reminder as done"),
+    ("POST", "/reminders/:id/skip-next", "Skip the upcoming occurrence of a recurring reminder"),
+    ("POST", "/reminders/:id/ack", "Acknowledge a reminder ({\"by\": name})"),
+    ("POST", "/reminders/:id/reassign", "Give a reminder to another user ({\"username\": name})"),
+    ("POST", "/groups/:group/complete", "Complete every reminder in a group"),
+    ("DELETE", "/groups/:group", "Delete every reminder in a group"),
+    ("GET", "/preferences/:username", "A user's defaults for new reminders"),
+    ("PUT", "/preferences/:username", "Set a user's timezone, channels, lead offsets, recurrence anchor, and quiet hours"),
+    ("POST", "/templates", "Save a reminder template"),
+    ("GET", "/templates", "List templates (?username=)"),
+    ("DELETE", "/templates/:name", "Delete a template (?username=)"),
+    ("POST", "/templates/:name/instantiate", "Create a reminder from a template"),
+    ("GET", "/admin/reminders", "Export all reminders"),
+    ("POST", "/admin/reminders/import", "Import exported reminders"),
+    ("POST", "/admin/reload", "Re-read the reminders file after editing it (or send SIGHUP)"),
+    ("POST", "/admin/validate", "Report stored reminders that fail the current validation rules (changes nothing)"),
+];
+
+#[derive(Parser)]
+#[command(name = "server")]
+#[command(version = build_info::FULL_VERSION)]
+#[command(about = "Reminder microservice", long_about = None)]
+struct Args {
+    #[arg(long, help = "Path to reminder.toml (default: ./reminder.toml, then ~/.config/reminder/reminder.toml)")]
+    config: Option<PathBuf>,
+
+    #[arg(long, help = "Print the effective configuration (secrets redacted) and exit")]
+    print_config: bool,
+
+    #[arg(long, value_enum, help = "Log output format (overrides server.log_format)")]
+    log_format: Option<LogFormat>,
+
+    #[arg(long, value_name = "PEM", requires = "tls_key", help = "TLS certificate chain; serves HTTPS (overrides server.tls_cert)")]
+    tls_cert: Option<PathBuf>,
+
+    #[arg(long, value_name = "PEM", requires = "tls_cert", help = "TLS private key (overrides server.tls_key)")]
+    tls_key: Option<PathBuf>,
+}
+
+/// Installs the global subscriber. Levels are filtered with `RUST_LOG`
+/// (default `info`).
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    match format {
+        LogFormat::Pretty => builder.init(),
+        LogFormat::Compact => builder.compact().init(),
+        LogFormat::Json => builder.json().init(),
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    let storage = Arc::new(ReminderStorage::new().expect("Failed to initialize storage"));
-    
-    let app_state = Arc::new(AppState {
-        storage: storage.clone(),
-    });
+    let args = Args::parse();
+
+    let (mut config, config_path) = match Config::load(args.config.as_deref()) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("❌ {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if args.print_config {
+        match &config_path {
+            Some(path) => println!("# Loaded from {}", path.display()),
+            None => println!("# No config file found; built-in defaults and environment"),
+        }
+        match config.to_redacted_toml() {
+            Ok(toml) => print!("{}", toml),
+            Err(e) => {
+                eprintln!("❌ {:#}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
+    if let Some(format) = args.log_format {
+        config.server.log_format = format;
+    }
+    if let (Some(cert), Some(key)) = (args.tls_cert, args.tls_key) {
+        config.server.tls_cert = Some(cert);
+        config.server.tls_key = Some(key);
+    }
+    init_tracing(config.server.log_format);
+
+    let tls = match (&config.server.tls_cert, &config.server.tls_key) {
+        (Some(cert), Some(key)) => match tls::load_server_config(cert, key) {
+            Ok(tls) => Some(RustlsConfig::from_config(Arc::new(tls))),
+            Err(e) => {
+                error!(error = format!("{:#}", e), "Failed to load TLS certificate");
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+    if tls.is_none() && config.server.http_bind.is_some() {
+        error!("server.http_bind is only used alongside TLS (set a certificate and key, or use server.bind)");
+        std::process::exit(1);
+    }
+
+    let _storage_lock = match StorageLock::acquire_for(&config.storage.path) {
+        Ok(lock) => lock,
+        Err(e) => {
+            error!(error = %e, "Is another server (or a quiz command in embedded mode) running?");
+            std::process::exit(1);
+        }
+    };
+
+    let storage = match ReminderStorage::open(&config.storage.path) {
+        Ok(storage) => Arc::new(storage),
+        Err(e) => {
+            error!(path = %config.storage.path.display(), error = %e, "Failed to initialize storage");
+            std::process::exit(1);
+        }
+    };
+    let templates = match TemplateStorage::open_beside(&config.storage.path) {
+        Ok(templates) => Arc::new(templates),
+        Err(e) => {
+            error!(error = %e, "Failed to initialize template storage");
+            std::process::exit(1);
+        }
+    };
+    let preferences = match PreferenceStorage::open_beside(&config.storage.path) {
+        Ok(preferences) => Arc::new(preferences),
+        Err(e) => {
+            error!(error = %e, "Failed to initialize preference storage");
+            std::process::exit(1);
+        }
+    };
+    let app_state = Arc::new(AppState::from_config(storage.clone(), templates, preferences, &config));
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
     let notification_storage = storage.clone();
-    tokio::spawn(async move {
-        notification_service(notification_storage).await;
+    let tick = Duration::from_secs(config.notifications.interval_secs);
+    let events = config.notifications.event_log.as_ref().map(|path| {
+        info!(path = %path.display(), "Recording fired notifications");
+        EventLog::new(path, config.notifications.event_log_max_kb * 1024)
+    });
+    let blackout = config.notifications.blackout_window();
+    if let Some(ref window) = blackout {
+        info!(start = %window.start, end = %window.end, "Holding reminders during the blackout window (server-local time)");
+    }
+    let notifier_health = app_state.notifier_health.clone();
+    let notifier = tokio::spawn(async move {
+        notification_service(notification_storage, tick, events, blackout, notifier_health, shutdown_rx).await;
     });
 
-    let app = Router::new()
-        .route("/reminders", post(create_reminder))
-        .route("/reminders", get(get_reminders))
-        .with_state(app_state);
+    let max_body_kb = app_state.max_body_kb;
+    let auth_enabled = app_state.auth_secret.is_some();
+    let app = build_router(app_state);
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000")
-        .await
-        .expect("Failed to bind to port 3000");
-
-    println!("🚀 Reminder microservice running on http://0.0.0.0:3000");
-    println!("📋 Endpoints:");
-    println!("   POST /reminders - Create a new reminder");
-    println!("   GET  /reminders - View upcoming reminders");
-    
-    axum::serve(listener, app)
-        .await
-        .expect("Failed to start server");
-}
+    let bind = &config.server.bind;
+    let listener = bind_or_exit(bind).await;
+    let http_listener = match &config.server.http_bind {
+        Some(http_bind) => Some(bind_or_exit(http_bind).await),
+        None => None,
+    };
 
-async fn create_reminder(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<CreateReminderRequest>,
-) -> Result<(StatusCode, Json<CreateReminderResponse>), (StatusCode, String)> {
-    let due_time: DateTime<Utc> = payload
-        .due_time
-        .parse()
-        .map_err(|_| {
-            (
-                StatusCode::BAD_REQUEST,
-                "Invalid date format. Use ISO 8601 format (e.g., 2025-11-04T15:30:00Z)".to_string(),
-            )
-        })?;
-
-    if payload.message.trim().is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "Message cannot be empty".to_string(),
-        ));
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    info!(%bind, scheme, version = build_info::FULL_VERSION, "Reminder microservice running");
+    if let Some(http_bind) = &config.server.http_bind {
+        info!(bind = %http_bind, scheme = "http", "Also serving plain HTTP");
+    }
+    if let Some(path) = &config_path {
+        info!(path = %path.display(), "Loaded config");
+    }
+    for (method, path, description) in ENDPOINTS {
+        info!(method, path, "{}", description);
+    }
+    info!(max_body_kb, auth_enabled, "Request limits and authentication");
+    if !auth_enabled {
+        info!("Authentication disabled (set REMINDER_SHARED_SECRET to enable)");
     }
 
-    // Validate recurrence if provided
-    if let Some(ref recurrence) = payload.recurrence {
-        let recurrence_lower = recurrence.to_lowercase();
-        
-        if matches!(recurrence_lower.as_str(), "daily" | "weekly") {
-            // Valid preset recurrence patterns
-        } else if let Ok(minutes) = recurrence.parse::<i64>() {
-            // Bare number - must be positive
-            if minutes <= 0 {
-                return Err((
-                    StatusCode::BAD_REQUEST,
-                    "Recurrence interval must be a positive number of minutes".to_string(),
-                ));
-            }
-        } else if recurrence_lower.ends_with("minutes") {
-            // Number with "minutes" suffix - must be positive
-            match recurrence.trim_end_matches("minutes").trim().parse::<i64>() {
-                Ok(minutes) if minutes > 0 => {
-                    // Valid
-                }
-                Ok(_) => {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        "Recurrence interval must be a positive number of minutes".to_string(),
-                    ));
-                }
-                Err(_) => {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        "Invalid recurrence format. Use 'daily', 'weekly', or a positive number (minutes)".to_string(),
-                    ));
-                }
+    print_upcoming_summary(&storage, config.server.log_format == LogFormat::Pretty).await;
+
+    #[cfg(unix)]
+    tokio::spawn(reload_on_hangup(storage.clone()));
+
+    let (stop_tx, stop_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        let _ = stop_tx.send(true);
+    });
+
+    let served = match tls {
+        None => serve_http(listener, app, stop_rx).await,
+        Some(tls) => {
+            let https = serve_https(listener, app.clone(), tls, stop_rx.clone());
+            match http_listener {
+                Some(http_listener) => tokio::try_join!(https, serve_http(http_listener, app, stop_rx)).map(|_| ()),
+                None => https.await,
             }
-        } else {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                "Invalid recurrence format. Use 'daily', 'weekly', or a positive number (minutes)".to_string(),
-            ));
         }
+    };
+    if let Err(e) = served {
+        error!(error = %e, "Server failed");
     }
 
-    let reminder = Reminder::new(
-        payload.message.clone(),
-        due_time,
-        payload.username,
-        payload.recurrence,
-    );
+    info!("Shutting down");
+    let _ = shutdown_tx.send(true);
+    let _ = notifier.await;
+}
 
-    let saved_reminder = state
-        .storage
-        .add_reminder(reminder)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to save reminder: {}", e),
-            )
-        })?;
-
-    let response = CreateReminderResponse {
-        id: saved_reminder.id.clone(),
-        message: saved_reminder.message.clone(),
-        due_time: saved_reminder.due_time.to_rfc3339(),
-    };
+/// Re-reads the reminders file on every SIGHUP.
+#[cfg(unix)]
+async fn reload_on_hangup(storage: Arc<ReminderStorage>) {
+    use tokio::signal::unix::{signal, SignalKind};
 
-    Ok((StatusCode::CREATED, Json(response)))
-}
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            error!(error = %e, "Failed to listen for SIGHUP; use POST /admin/reload to reload reminders");
+            return;
+        }
+    };
 
-async fn get_reminders(
-    State(state): State<Arc<AppState>>,
-) -> Result<Json<ReminderListResponse>, (StatusCode, String)> {
-    let reminders = state
-        .storage
-        .get_upcoming_reminders()
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to retrieve reminders: {}", e),
-            )
-        })?;
-
-    Ok(Json(ReminderListResponse { reminders }))
+    while hangups.recv().await.is_some() {
+        info!("SIGHUP received, reloading reminders");
+        if let Err(e) = storage.reload().await {
+            error!(path = %storage.path().display(), error = %e, "Failed to reload reminders; keeping the current ones");
+        }
+    }
 }
 
-async fn notification_service(storage: Arc<ReminderStorage>) {
-    let mut interval = interval(Duration::from_secs(10));
-
-    loop {
-        interval.tick().await;
-
-        match storage.get_due_reminders().await {
-            Ok(due_reminders) => {
-                for reminder in due_reminders {
-                    let user_info = reminder
-                        .username
-                        .as_ref()
-                        .map(|u| format!(" for {}", u))
-                        .unwrap_or_default();
-
-                    let recurrence_info = reminder
-                        .recurrence
-                        .as_ref()
-                        .map(|r| format!(" (Recurring: {})", r))
-                        .unwrap_or_default();
-
-                    println!("\n🔔 REMINDER{}{}: {}", user_info, recurrence_info, reminder.message);
-                    println!("   Due: {}", reminder.due_time.format("%Y-%m-%d %H:%M:%S UTC"));
-                    println!("   ID: {}", reminder.id);
-
-                    // Handle recurring reminders by updating the existing reminder
-                    if let Some(next_time) = reminder.calculate_next_occurrence() {
-                        println!("   📅 Next occurrence: {}", next_time.format("%Y-%m-%d %H:%M:%S UTC"));
-                        
-                        // Update the existing reminder with the next due time
-                        if let Err(e) = storage.reschedule_reminder(&reminder.id, next_time).await {
-                            eprintln!("Failed to reschedule recurring reminder: {}", e);
-                        } else {
-                            println!("   ✅ Next occurrence scheduled");
-                        }
-                    } else {
-                        // No recurrence, so mark as sent
-                        if let Err(e) = storage.mark_as_sent(&reminder.id).await {
-                            eprintln!("Failed to mark reminder as sent: {}", e);
-                        }
-                    }
-                    
-                    println!();
-                }
-            }
-            Err(e) => {
-                eprintln!("Error checking due reminders: {}", e);
-            }
+async fn bind_or_exit(bind: &str) -> TcpListener {
+    match TcpListener::bind(bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!(%bind, error = %e, "Failed to bind");
+            std::process::exit(1);
         }
     }
 }
+
+async fn stopped(mut stop: watch::Receiver<bool>) {
+    let _ = stop.wait_for(|stopped| *stopped).await;
+}
+
+async fn serve_http(listener: TcpListener, app: Router, stop: watch::Receiver<bool>) -> io::Result<()> {
+    axum::serve(listener, app).with_graceful_shutdown(stopped(stop)).await
+}
+
+async fn serve_https(listener: TcpListener, app: Router, tls: RustlsConfig, stop: watch::Receiver<bool>) -> io::Result<()> {
+    let handle = axum_server::Handle::new();
+    let on_stop = handle.clone();
+    tokio::spawn(async move {
+        stopped(stop).await;
+        on_stop.graceful_shutdown(Some(HTTPS_SHUTDOWN_GRACE));
+    });
+
+    axum_server::from_tcp_rustls(listener.into_std()?, tls)
+        .handle(handle)
+        .serve(app.into_make_service())
+        .await
+}
@@ -1,6 +1,36 @@
-use chrono::{DateTime, Utc, Duration};
+use crate::ids;
+use chrono::{DateTime, Utc, Duration, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+
+/// How a reminder behaves when its due time passed while the server was down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedPolicy {
+    /// Mark as handled without notifying
+    Skip,
+    /// Notify once, however many occurrences were missed
+    #[default]
+    FireOnce,
+    /// Notify for every missed occurrence of a recurring reminder, up to a
+    /// cap per pass
+    FireAll,
+}
+
+/// What a fixed-interval recurrence counts from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceAnchor {
+    /// Keep the original series: a late firing doesn't shift later occurrences
+    #[default]
+    Schedule,
+    /// Count the interval from when the reminder actually fired
+    Completion,
+}
+
+/// Request header a client names itself in (e.g. `quiz`), recorded as the
+/// `source` of the reminders it creates.
+pub const CLIENT_SOURCE_HEADER: &str = "x-client-source";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reminder {
@@ -8,66 +38,459 @@ pub struct Reminder {
     pub message: String,
     pub due_time: DateTime<Utc>,
     pub username: Option<String>,
+    /// Principal allowed to see and change the reminder when authentication
+    /// is enabled. Older reminders get their `username` on load.
+    #[serde(default)]
+    pub owner: Option<String>,
     pub sent: bool,
     pub created_at: DateTime<Utc>,
     pub recurrence: Option<String>,
+    #[serde(default)]
+    pub recurrence_anchor: RecurrenceAnchor,
+    #[serde(default)]
+    pub missed_policy: MissedPolicy,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub series_id: Option<String>,
+    #[serde(default)]
+    pub linked_file_ids: Vec<String>,
+    #[serde(default)]
+    pub linked_tags: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed: bool,
+    /// Who acknowledged the reminder, for reminders that must be signed off
+    #[serde(default)]
+    pub acked_by: Option<String>,
+    #[serde(default)]
+    pub acked_at: Option<DateTime<Utc>>,
+    /// Identifier of the calendar event the reminder was imported from (its
+    /// iCalendar UID), so importing the calendar again updates it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// Last time the reminder was edited or handed to another user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Low-priority reminder: each fire is logged as one compact line
+    #[serde(default)]
+    pub quiet: bool,
+    /// IANA zone the reminder was created in; quiet hours are read in it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Where notifications go, e.g. `stdout` or `log`; empty means stdout
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+    /// Daily window in which firings wait until the window ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+    /// Client that created the reminder, from its `X-Client-Source` header;
+    /// `None` for plain API calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// http(s) URL shown on its own line when the reminder fires, e.g. a
+    /// meeting or document to open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    /// Fire even during the server's blackout window
+    #[serde(default)]
+    pub ignore_blackout: bool,
+    /// User-chosen name for a set of related reminders (e.g. `exam-prep`) that
+    /// can be listed, completed, or deleted together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
 }
 
 impl Reminder {
     pub fn new(message: String, due_time: DateTime<Utc>, username: Option<String>, recurrence: Option<String>) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: ids::next_id(),
             message,
             due_time,
+            owner: username.clone(),
             username,
             sent: false,
             created_at: Utc::now(),
             recurrence,
+            recurrence_anchor: RecurrenceAnchor::default(),
+            missed_policy: MissedPolicy::default(),
+            color: None,
+            title: None,
+            description: None,
+            series_id: None,
+            linked_file_ids: Vec::new(),
+            linked_tags: Vec::new(),
+            tags: Vec::new(),
+            snoozed_until: None,
+            completed: false,
+            acked_by: None,
+            acked_at: None,
+            external_id: None,
+            updated_at: None,
+            quiet: false,
+            timezone: None,
+            channels: Vec::new(),
+            quiet_hours: None,
+            source: None,
+            link: None,
+            ignore_blackout: false,
+            group_id: None,
         }
     }
 
-    pub fn calculate_next_occurrence(&self) -> Option<DateTime<Utc>> {
+    pub fn recurrence_rule(&self) -> Option<RecurrenceRule> {
         let recurrence = self.recurrence.as_ref()?;
-        let now = Utc::now();
-        
-        // Calculate the interval duration
-        let interval = match recurrence.to_lowercase().as_str() {
-            "daily" => Duration::days(1),
-            "weekly" => Duration::weeks(1),
-            custom if custom.ends_with("minutes") || custom.parse::<i64>().is_ok() => {
-                let minutes: i64 = if custom.ends_with("minutes") {
-                    custom.trim_end_matches("minutes").trim().parse().ok()?
-                } else {
-                    custom.parse().ok()?
-                };
-                
-                // Safeguard: reject non-positive intervals
-                if minutes <= 0 {
-                    eprintln!("Warning: Invalid recurrence interval {} minutes, skipping reschedule", minutes);
-                    return None;
-                }
-                
-                Duration::minutes(minutes)
+
+        match parse_recurrence_rule(recurrence) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                // Safeguard: stored reminders predating validation may hold bad intervals
+                tracing::warn!(reminder_id = %self.id, recurrence = %recurrence, error = %e, "Invalid recurrence, skipping reschedule");
+                None
             }
-            _ => return None,
+        }
+    }
+
+    /// The fixed interval between occurrences; `None` for one-off reminders and
+    /// for those that only repeat after being completed.
+    pub fn recurrence_interval(&self) -> Option<Duration> {
+        match self.recurrence_rule()? {
+            RecurrenceRule::Every(interval) => Some(interval),
+            RecurrenceRule::AfterComplete(_) => None,
+        }
+    }
+
+    /// Fire times starting at the stored due time. A non-recurring reminder
+    /// only ever has its single due time. Scheduling, previews, and missed
+    /// occurrences all derive from this so they can't disagree. Ends early
+    /// rather than overflowing past the largest representable time.
+    pub fn occurrence_times(&self) -> impl Iterator<Item = DateTime<Utc>> {
+        let interval = self.recurrence_interval();
+        std::iter::successors(Some(self.due_time), move |t| t.checked_add_signed(interval?))
+    }
+
+    pub fn calculate_next_occurrence(&self) -> Option<DateTime<Utc>> {
+        self.next_occurrence_at(Utc::now())
+    }
+
+    /// The next fire time for a recurring reminder handled at `now`: the first
+    /// later occurrence of the series that is still in the future, or for
+    /// completion-anchored reminders one interval after `now`.
+    pub fn next_occurrence_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let interval = self.recurrence_interval()?;
+
+        match self.recurrence_anchor {
+            RecurrenceAnchor::Schedule => self.occurrence_times().skip(1).find(|t| *t > now),
+            RecurrenceAnchor::Completion => now.checked_add_signed(interval),
+        }
+    }
+
+    /// For `after_complete:` reminders, when to fire again if completed at
+    /// `completed_at`. `None` for every other kind of reminder.
+    pub fn next_occurrence_after_completion(&self, completed_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.recurrence_rule()? {
+            RecurrenceRule::AfterComplete(interval) => completed_at.checked_add_signed(interval),
+            RecurrenceRule::Every(_) => None,
+        }
+    }
+
+    /// The next `count` occurrences starting at the stored due time.
+    pub fn occurrences(&self, count: usize) -> Vec<DateTime<Utc>> {
+        self.occurrence_times().take(count).collect()
+    }
+
+    /// Occurrences between the stored due time and `now`, oldest first.
+    pub fn missed_occurrences(&self, now: DateTime<Utc>) -> impl Iterator<Item = DateTime<Utc>> {
+        self.occurrence_times().take_while(move |t| *t <= now)
+    }
+
+    /// The end of the quiet-hours window `at` falls in, read in the
+    /// reminder's timezone (UTC when unset); `None` outside quiet hours.
+    pub fn quiet_until(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let zone = self.timezone.as_deref().and_then(|z| z.parse::<Tz>().ok()).unwrap_or(Tz::UTC);
+        self.quiet_hours.as_ref()?.window_end(at, &zone)
+    }
+}
+
+/// A daily window, as `HH:MM` local times, when notifications are held back.
+/// A `start` later than `end` runs past midnight (e.g. 22:00 to 07:00).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    /// Parses `START-END`, e.g. `22:00-07:00`.
+    pub fn parse_range(range: &str) -> Result<Self, String> {
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| format!("Invalid time window '{}'. Use START-END, e.g. 22:00-07:00", range))?;
+        let window = Self { start: start.trim().to_string(), end: end.trim().to_string() };
+        window.validate()?;
+        Ok(window)
+    }
+
+    /// Checks both times, e.g. for `{"start": "22:00", "end": "07:00"}`.
+    pub fn validate(&self) -> Result<(), String> {
+        let (start, end) = self.bounds()?;
+        if start == end {
+            return Err("Quiet hours must start and end at different times".to_string());
+        }
+        Ok(())
+    }
+
+    fn bounds(&self) -> Result<(NaiveTime, NaiveTime), String> {
+        let parse = |time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .map_err(|_| format!("Invalid quiet hours time '{}'. Use HH:MM, e.g. 22:00", time))
         };
+        Ok((parse(&self.start)?, parse(&self.end)?))
+    }
+
+    /// When the window containing `at` ends in `zone`, or `None` if `at` is
+    /// outside it.
+    pub fn window_end<Z: TimeZone>(&self, at: DateTime<Utc>, zone: &Z) -> Option<DateTime<Utc>> {
+        let (start, end) = self.bounds().ok()?;
+        let local = at.with_timezone(zone);
+        let time = local.time();
 
-        // Keep adding the interval until we get a future time
-        let mut next_time = self.due_time + interval;
-        while next_time <= now {
-            next_time = next_time + interval;
+        let inside = if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+        if !inside {
+            return None;
         }
-        
-        Some(next_time)
+
+        // Before `end` today, or (for windows past midnight) at `end` tomorrow
+        let date = if time < end { local.date_naive() } else { local.date_naive().succ_opt()? };
+        zone.from_local_datetime(&date.and_time(end))
+            .earliest()
+            .map(|end| end.with_timezone(&Utc))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How a recurring reminder repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceRule {
+    /// On a fixed schedule from the original due time
+    Every(Duration),
+    /// This long after the reminder is completed (`after_complete:3d`)
+    AfterComplete(Duration),
+}
+
+/// Parses a full recurrence string: an interval pattern (see `parse_recurrence`),
+/// optionally prefixed with `after_complete:` to repeat relative to completion.
+pub fn parse_recurrence_rule(recurrence: &str) -> Result<RecurrenceRule, String> {
+    match recurrence.trim().strip_prefix("after_complete:") {
+        Some(interval) => parse_recurrence(interval).map(RecurrenceRule::AfterComplete),
+        None => parse_recurrence(recurrence).map(RecurrenceRule::Every),
+    }
+}
+
+/// Longest recurrence interval accepted, in days (about ten years).
+pub const MAX_RECURRENCE_DAYS: i64 = 3650;
+
+/// Parses a recurrence pattern into its interval: `daily`, `weekly`, a number of
+/// minutes (`30`, `30 minutes`), or a number of days (`3 days`, `1 day`, `3d`).
+/// Intervals longer than `MAX_RECURRENCE_DAYS` are rejected.
+pub fn parse_recurrence(recurrence: &str) -> Result<Duration, String> {
+    let lower = recurrence.trim().to_lowercase();
+
+    match lower.as_str() {
+        "daily" => return Ok(Duration::days(1)),
+        "weekly" => return Ok(Duration::weeks(1)),
+        _ => {}
+    }
+
+    let invalid = || {
+        "Invalid recurrence format. Use 'daily', 'weekly', a positive number of minutes, or 'N days' (prefix with 'after_complete:' to repeat after completion)".to_string()
+    };
+
+    let (amount, unit_days) = if let Some(n) = lower.strip_suffix("minutes") {
+        (n, false)
+    } else if let Some(n) = lower
+        .strip_suffix("days")
+        .or_else(|| lower.strip_suffix("day"))
+        .or_else(|| lower.strip_suffix('d'))
+    {
+        (n, true)
+    } else {
+        (lower.as_str(), false)
+    };
+
+    let amount: i64 = amount.trim().parse().map_err(|_| invalid())?;
+
+    if amount <= 0 {
+        return Err(if unit_days {
+            "Recurrence interval must be a positive number of days".to_string()
+        } else {
+            "Recurrence interval must be a positive number of minutes".to_string()
+        });
+    }
+
+    let interval = if unit_days { Duration::try_days(amount) } else { Duration::try_minutes(amount) };
+    match interval {
+        Some(interval) if interval <= Duration::days(MAX_RECURRENCE_DAYS) => Ok(interval),
+        _ => Err(format!("Recurrence interval can be at most {} days", MAX_RECURRENCE_DAYS)),
+    }
+}
+
+/// The recurrence formats `parse_recurrence_rule` accepts, as (pattern,
+/// example, meaning). Keep in step with the parser.
+pub const RECURRENCE_FORMATS: &[(&str, &str, &str)] = &[
+    ("daily", "daily", "Every day at the original time"),
+    ("weekly", "weekly", "Every week at the original time"),
+    ("N", "30", "Every N minutes"),
+    ("N minutes", "90 minutes", "Every N minutes"),
+    ("N days, N day, Nd", "3 days", "Every N days"),
+    ("after_complete:<interval>", "after_complete:3d", "Any interval above, counted from when the reminder is completed"),
+];
+
+/// One entry of `RECURRENCE_FORMATS`, as served by `GET /capabilities`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurrenceFormat {
+    pub pattern: String,
+    pub example: String,
+    pub description: String,
+}
+
+pub fn recurrence_formats() -> Vec<RecurrenceFormat> {
+    RECURRENCE_FORMATS
+        .iter()
+        .map(|(pattern, example, description)| RecurrenceFormat {
+            pattern: pattern.to_string(),
+            example: example.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+/// Checks that `link` is an absolute http or https URL with a host.
+pub fn validate_link(link: &str) -> Result<(), String> {
+    let invalid = || format!("Invalid link '{}'. Use an http:// or https:// URL", link);
+    let url = reqwest::Url::parse(link.trim()).map_err(|_| invalid())?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none_or(str::is_empty) {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Whether `color` is a `#RRGGBB` hex color.
+pub fn is_valid_hex_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateReminderRequest {
     pub message: String,
+    /// May be left out when `fire_immediately` is set
+    #[serde(default)]
     pub due_time: String,
     pub username: Option<String>,
     pub recurrence: Option<String>,
+    pub recurrence_anchor: Option<RecurrenceAnchor>,
+    pub missed_policy: Option<MissedPolicy>,
+    pub color: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub series_id: Option<String>,
+    #[serde(default)]
+    pub linked_file_ids: Vec<String>,
+    #[serde(default)]
+    pub linked_tags: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Make the first occurrence due now instead of at `due_time`; a
+    /// recurrence then counts on from now
+    #[serde(default)]
+    pub fire_immediately: bool,
+    /// Log each fire as one compact line instead of the full block
+    #[serde(default)]
+    pub quiet: bool,
+    /// http(s) URL to open when the reminder fires
+    pub link: Option<String>,
+    /// Fire even during the server's blackout window
+    #[serde(default)]
+    pub ignore_blackout: bool,
+    pub group_id: Option<String>,
+    /// The remaining fields fall back to the user's preferences, then to the
+    /// server config, when left out
+    pub timezone: Option<String>,
+    pub channels: Option<Vec<String>>,
+    /// Extra reminders before the first due time, e.g. `10m` or `1d`
+    pub lead_offsets: Option<Vec<String>>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// `POST /reminders/parse`: a reminder written as one sentence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseReminderRequest {
+    /// e.g. "remind me to call mom tomorrow at 5pm"
+    pub text: String,
+    pub username: Option<String>,
+    /// Zone for the times in `text`, e.g. Europe/Berlin; defaults to the
+    /// user's preferred timezone, then the server's
+    /// `scheduling.default_timezone`
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateReminderRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet: Option<bool>,
+}
+
+/// Validated changes to apply to a stored reminder. `None` leaves a field as is.
+#[derive(Debug, Clone, Default)]
+pub struct ReminderChanges {
+    pub message: Option<String>,
+    pub due_time: Option<DateTime<Utc>>,
+    pub recurrence: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub quiet: Option<bool>,
+}
+
+/// Which sent reminders a purge removes. Pending and recurring reminders
+/// never match.
+#[derive(Debug, Clone, Default)]
+pub struct PurgeFilter {
+    /// Only reminders that were due before this
+    pub due_before: Option<DateTime<Utc>>,
+    pub username: Option<String>,
+    pub owner: Option<String>,
+}
+
+impl PurgeFilter {
+    pub fn matches(&self, reminder: &Reminder) -> bool {
+        reminder.sent
+            && reminder.recurrence.is_none()
+            && self.due_before.is_none_or(|cutoff| reminder.due_time < cutoff)
+            && self.username.as_ref().is_none_or(|u| reminder.username.as_ref() == Some(u))
+            && self.owner.as_ref().is_none_or(|o| reminder.owner.as_ref() == Some(o))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,9 +498,519 @@ pub struct CreateReminderResponse {
     pub id: String,
     pub message: String,
     pub due_time: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Non-fatal notes about the request, such as a likely duplicate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// The reminder as stored, including everything the server filled in
+    pub reminder: Reminder,
+    /// One per lead offset that was still in the future
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lead_reminders: Vec<Reminder>,
+}
+
+/// A user's defaults for new reminders. `POST /reminders` copies them onto
+/// each reminder it creates for the user, for the fields the request leaves
+/// out, so changing them later doesn't touch existing reminders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderPreferences {
+    pub username: String,
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<String>,
+    #[serde(default)]
+    pub lead_offsets: Vec<String>,
+    /// Only applied to reminders with a fixed-interval recurrence
+    pub recurrence_anchor: Option<RecurrenceAnchor>,
+    pub quiet_hours: Option<QuietHours>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// `PUT /preferences/:username` replaces all of a user's preferences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreferencesRequest {
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<String>,
+    #[serde(default)]
+    pub lead_offsets: Vec<String>,
+    pub recurrence_anchor: Option<RecurrenceAnchor>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl From<ReminderPreferences> for PreferencesRequest {
+    fn from(preferences: ReminderPreferences) -> Self {
+        Self {
+            timezone: preferences.timezone,
+            channels: preferences.channels,
+            lead_offsets: preferences.lead_offsets,
+            recurrence_anchor: preferences.recurrence_anchor,
+            quiet_hours: preferences.quiet_hours,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReminderListResponse {
     pub reminders: Vec<Reminder>,
 }
+
+/// How a multi-tag filter combines its tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatchMode {
+    /// Reminder carries at least one of the tags
+    #[default]
+    Any,
+    /// Reminder carries every tag
+    All,
+}
+
+/// Body format for `GET /reminders`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReminderListQuery {
+    /// Overrides the `Accept` header
+    pub format: Option<ListFormat>,
+    pub username: Option<String>,
+    /// Lists sent (`true`) or not-yet-sent (`false`) reminders, overdue ones
+    /// included; without it only upcoming reminders are listed
+    pub sent: Option<bool>,
+    /// Only reminders due at or after this
+    pub due_after: Option<DateTime<Utc>>,
+    /// Only reminders due at or before this
+    pub due_before: Option<DateTime<Utc>>,
+    pub tag: Option<String>,
+    /// Comma-separated tags, combined according to `match`
+    pub tags: Option<String>,
+    #[serde(default, rename = "match")]
+    pub match_mode: TagMatchMode,
+    /// Only reminders in this group
+    pub group: Option<String>,
+}
+
+impl ReminderListQuery {
+    /// The requested tags, trimmed and lowercased; empty means no filter.
+    pub fn tag_filter(&self) -> Vec<String> {
+        self.tag
+            .iter()
+            .chain(self.tags.iter())
+            .flat_map(|t| t.split(','))
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect()
+    }
+}
+
+impl Reminder {
+    /// Whether the message, title, or description contains `query`, ignoring case.
+    pub fn matches_text(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [Some(&self.message), self.title.as_ref(), self.description.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+
+    pub fn matches_tags(&self, tags: &[String], mode: TagMatchMode) -> bool {
+        let has = |tag: &String| self.tags.iter().any(|t| t.to_lowercase() == *tag);
+        match mode {
+            TagMatchMode::Any => tags.iter().any(has),
+            TagMatchMode::All => tags.iter().all(has),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DueSoonQuery {
+    /// How far ahead to look; defaults to an hour
+    pub within_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OccurrencesQuery {
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OccurrencesResponse {
+    pub id: String,
+    pub recurrence: Option<String>,
+    pub occurrences: Vec<DateTime<Utc>>,
+}
+
+/// Fire times a create request would produce, computed without storing anything.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewResponse {
+    pub recurrence: Option<String>,
+    pub occurrences: Vec<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnoozeRequest {
+    pub minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AckRequest {
+    pub by: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReassignRequest {
+    /// The new username and owner
+    pub username: String,
+}
+
+/// Hands every reminder of one user to another, e.g. when someone leaves.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkReassignRequest {
+    /// Current username of the reminders to move
+    pub from: String,
+    pub username: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReassignResponse {
+    pub reassigned: usize,
+    pub reminders: Vec<Reminder>,
+}
+
+/// Result of completing or deleting a whole group.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupActionResponse {
+    pub group: String,
+    /// Reminders completed or deleted
+    pub affected: usize,
+    pub reminders: Vec<Reminder>,
+}
+
+/// `POST /reminders/transfer`: an admin moving everything a user had to
+/// someone else, e.g. when they leave.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferRequest {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransferResponse {
+    pub transferred: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PurgeRequest {
+    /// Must be true; only sent reminders can be purged
+    #[serde(default)]
+    pub sent: bool,
+    /// Only reminders due more than this many days ago
+    pub older_than_days: Option<u32>,
+    pub username: Option<String>,
+    /// Required to purge without `older_than_days` or `username`
+    #[serde(default)]
+    pub confirm_all: bool,
+    /// Report what would be removed without removing it
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeResponse {
+    pub removed: usize,
+    pub dry_run: bool,
+    pub reminders: Vec<Reminder>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CompleteQuery {
+    /// Advance a recurring reminder to its next occurrence instead of completing it
+    #[serde(default)]
+    pub skip_occurrence: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestNotifyRequest {
+    pub message: Option<String>,
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestNotifyResponse {
+    pub delivered: bool,
+    pub channel: String,
+    pub error: Option<String>,
+}
+
+/// Reusable defaults for reminders created over and over with different times.
+/// Names are unique per username (or among templates without one).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReminderTemplate {
+    pub name: String,
+    pub username: Option<String>,
+    pub message: String,
+    pub recurrence: Option<String>,
+    /// Extra reminders before the due time, e.g. `1d` or `2h`
+    #[serde(default)]
+    pub lead_offsets: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    pub username: Option<String>,
+    pub message: String,
+    pub recurrence: Option<String>,
+    #[serde(default)]
+    pub lead_offsets: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TemplateQuery {
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TemplateListResponse {
+    pub templates: Vec<ReminderTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    pub due_time: String,
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstantiateTemplateResponse {
+    pub reminder: Reminder,
+    /// One per lead offset that was still in the future
+    pub lead_reminders: Vec<Reminder>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub preserve_ids: bool,
+}
+
+/// What this server build supports and how it is configured, so clients
+/// can tailor help and validation without hard-coding a copy.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CapabilitiesResponse {
+    /// Server version, from the crate version
+    pub version: String,
+    /// Whether requests need a bearer token
+    pub auth_required: bool,
+    pub max_body_kb: usize,
+    pub recurrence_formats: Vec<RecurrenceFormat>,
+    pub due_time_examples: Vec<String>,
+    pub task_import_formats: Vec<TaskImportFormat>,
+}
+
+/// `GET /version`: which build is running, for bug reports.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionResponse {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+}
+
+/// `GET /healthz`: whether the notification loop can reach its storage.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HealthResponse {
+    /// `ok`, `degraded` while the loop keeps failing, or `stopped` once it
+    /// has exited (e.g. after a panic)
+    pub status: String,
+    /// Notification passes in a row that hit a storage error
+    pub consecutive_failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// When a notification pass last completed without storage errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+}
+
+/// Task manager exports `POST /reminders/import` understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskImportFormat {
+    /// Todoist's CSV export (TYPE, CONTENT, DESCRIPTION, ..., DATE, TIMEZONE)
+    TodoistCsv,
+    /// Google Tasks exported to CSV (Title, Notes, Due, Status, Completed)
+    GoogleTasksCsv,
+    /// An iCalendar file; each VEVENT becomes a reminder at its DTSTART
+    Ics,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskImportQuery {
+    pub format: TaskImportFormat,
+    /// Report the conversion without storing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Import completed tasks too, as completed reminders that never fire
+    #[serde(default)]
+    pub include_completed: bool,
+    pub username: Option<String>,
+    /// Remind this long before each task or event is due, e.g. `30m`
+    pub before: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskImportStatus {
+    Converted,
+    /// Same calendar event as a reminder from an earlier import, which is updated
+    Updated,
+    Skipped,
+    Failed,
+}
+
+/// How one row of a task export was mapped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskImportRow {
+    /// Line in the CSV file, counting the header as line 1
+    pub line: u64,
+    pub task: String,
+    pub status: TaskImportStatus,
+    pub due_time: Option<DateTime<Utc>>,
+    pub recurrence: Option<String>,
+    /// Why the row was skipped or could not be converted, or what was lost
+    /// converting it
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskImportResponse {
+    pub imported: usize,
+    #[serde(default)]
+    pub updated: usize,
+    pub dry_run: bool,
+    pub rows: Vec<TaskImportRow>,
+}
+
+/// A stored reminder that would not pass the current validation rules.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub id: String,
+    pub problems: Vec<String>,
+}
+
+/// Result of re-validating every stored reminder. Nothing is changed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub checked: usize,
+    pub invalid: Vec<ValidationIssue>,
+}
+
+/// What a reload of the reminders file changed, by reminder id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReloadSummary {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    /// Reminders whose delivery state was kept to avoid firing them twice
+    pub kept_delivery: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportRemindersResponse {
+    pub imported: usize,
+    pub skipped: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hourly(anchor: RecurrenceAnchor) -> Reminder {
+        let due = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+        let mut reminder = Reminder::new("Stretch".to_string(), due, None, Some("60 minutes".to_string()));
+        reminder.recurrence_anchor = anchor;
+        reminder
+    }
+
+    #[test]
+    fn day_intervals_parse_in_every_spelling() {
+        for pattern in ["3 days", "3days", "3 DAYS", "3d", " 3 d "] {
+            assert_eq!(parse_recurrence(pattern), Ok(Duration::days(3)), "{}", pattern);
+        }
+        assert_eq!(parse_recurrence("1 day"), Ok(Duration::days(1)));
+    }
+
+    #[test]
+    fn huge_recurrence_intervals_are_rejected_instead_of_overflowing() {
+        for pattern in ["9999999999999 days", "9999999999999d", "9999999999999999 minutes", "3651 days"] {
+            let error = parse_recurrence(pattern).unwrap_err();
+            assert!(error.contains("at most"), "{}: {}", pattern, error);
+        }
+        assert_eq!(parse_recurrence("3650 days"), Ok(Duration::days(MAX_RECURRENCE_DAYS)));
+    }
+
+    #[test]
+    fn occurrences_end_at_the_largest_representable_time() {
+        let due = DateTime::<Utc>::MAX_UTC - Duration::days(2);
+        let reminder = Reminder::new("Stretch".to_string(), due, None, Some("daily".to_string()));
+
+        assert_eq!(reminder.occurrences(5), vec![due, due + Duration::days(1), DateTime::<Utc>::MAX_UTC]);
+        assert_eq!(reminder.next_occurrence_at(DateTime::<Utc>::MAX_UTC), None);
+    }
+
+    #[test]
+    fn completion_anchor_near_the_largest_time_has_no_next_occurrence() {
+        let mut reminder = hourly(RecurrenceAnchor::Completion);
+        reminder.recurrence = Some(format!("{} days", MAX_RECURRENCE_DAYS));
+
+        assert_eq!(reminder.next_occurrence_at(DateTime::<Utc>::MAX_UTC - Duration::days(1)), None);
+    }
+
+    #[test]
+    fn schedule_anchor_keeps_the_original_series_when_handled_late() {
+        let reminder = hourly(RecurrenceAnchor::Schedule);
+        let handled_at = reminder.due_time + Duration::minutes(95);
+
+        let next = reminder.next_occurrence_at(handled_at).unwrap();
+
+        assert_eq!(next, Utc.with_ymd_and_hms(2030, 1, 1, 11, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn completion_anchor_counts_from_when_it_was_handled() {
+        let reminder = hourly(RecurrenceAnchor::Completion);
+        let handled_at = reminder.due_time + Duration::minutes(95);
+
+        let next = reminder.next_occurrence_at(handled_at).unwrap();
+
+        assert_eq!(next, handled_at + Duration::hours(1));
+    }
+
+    #[test]
+    fn both_anchors_agree_when_handled_on_time() {
+        let schedule = hourly(RecurrenceAnchor::Schedule);
+        let completion = hourly(RecurrenceAnchor::Completion);
+
+        assert_eq!(
+            schedule.next_occurrence_at(schedule.due_time),
+            completion.next_occurrence_at(completion.due_time)
+        );
+    }
+}
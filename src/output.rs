@@ -0,0 +1,219 @@
+use prettytable::{format, Cell, Table};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set to `1` to get plain output without passing `--plain`.
+pub const PLAIN_ENV: &str = "QUIZ_PLAIN";
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Whether plain output was asked for by `--plain`, `QUIZ_PLAIN=1`, or a
+/// non-empty `NO_COLOR`.
+pub fn plain_requested(flag: bool) -> bool {
+    flag || std::env::var(PLAIN_ENV).is_ok_and(|v| v == "1") || std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty())
+}
+
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Prints a line to stdout, rendered for the current mode.
+pub fn line(text: &str) {
+    if let Some(text) = render(text) {
+        println!("{}", text);
+    }
+}
+
+/// Prints a line to stderr, rendered for the current mode.
+pub fn error_line(text: &str) {
+    if let Some(text) = render(text) {
+        eprintln!("{}", text);
+    }
+}
+
+/// Prints one list entry: the heading, then each detail on its own indented
+/// line and a blank line after. Plain mode puts the details on the heading's
+/// line instead, separated by ` | `.
+pub fn record(heading: &str, details: &[String]) {
+    if !is_plain() {
+        println!("{}", heading);
+        for detail in details {
+            println!("   {}", detail);
+        }
+        println!();
+        return;
+    }
+
+    let mut parts = vec![plain_text(heading)];
+    parts.extend(details.iter().map(|d| plain_text(d).trim().to_string()).filter(|d| !d.is_empty()));
+    println!("{}", parts.join(" | "));
+}
+
+/// Prints a table; plain mode drops the borders so each row is one line.
+pub fn print_table(mut table: Table) {
+    if !is_plain() {
+        table.printstd();
+        return;
+    }
+
+    println!("{}", plain_table(&mut table));
+}
+
+/// The table's rows as ASCII lines without borders.
+fn plain_table(table: &mut Table) -> String {
+    // Cells are rewritten before layout so the columns stay aligned
+    for row in table.row_iter_mut() {
+        for cell in row.iter_mut() {
+            *cell = Cell::new(&plain_text(&cell.get_content()));
+        }
+    }
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.to_string().lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+}
+
+/// `text` as it should appear in the current mode, or `None` when it is
+/// only decoration that plain mode leaves out.
+pub fn render(text: &str) -> Option<Cow<'_, str>> {
+    if !is_plain() {
+        return Some(Cow::Borrowed(text));
+    }
+
+    let lines: Vec<String> = text
+        .split('\n')
+        .filter(|line| !is_rule(line))
+        .map(plain_text)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(Cow::Owned(lines.join("\n")))
+}
+
+/// A horizontal rule such as `═════`.
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && line.chars().all(|c| matches!(c, '═' | '─' | '━'))
+}
+
+/// Drops emoji (with the spaces after them) and terminal escapes, and spells
+/// the remaining symbols in ASCII.
+fn plain_text(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                // ANSI sequence: ESC [ params final-byte
+                if chars.next_if_eq(&'[').is_some() {
+                    while chars.next().is_some_and(|c| !c.is_ascii_alphabetic()) {}
+                }
+            }
+            '…' => out.push_str("..."),
+            '→' => out.push_str("->"),
+            '█' => out.push('#'),
+            '▁' => out.push('.'),
+            '═' | '━' => out.push('='),
+            '─' => out.push('-'),
+            c if is_emoji(c) => {
+                while chars.next_if(|&c| is_emoji(c) || c == ' ').is_some() {}
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.trim_end().to_string()
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF // pictographs, emoticons, transport, symbols
+        | 0x2300..=0x23FF // technical symbols such as ⏰
+        | 0x2600..=0x27BF // miscellaneous symbols and dingbats
+        | 0x2B00..=0x2BFF // stars and arrows
+        | 0x2139          // ℹ
+        | 0x200D          // zero-width joiner
+        | 0xFE0F          // emoji presentation selector
+    )
+}
+
+/// `println!` through `output::line`.
+#[macro_export]
+macro_rules! outln {
+    () => {
+        $crate::output::line("")
+    };
+    ($($arg:tt)*) => {
+        $crate::output::line(&format!($($arg)*))
+    };
+}
+
+/// `eprintln!` through `output::error_line`.
+#[macro_export]
+macro_rules! errln {
+    () => {
+        $crate::output::error_line("")
+    };
+    ($($arg:tt)*) => {
+        $crate::output::error_line(&format!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prettytable::row;
+
+    #[test]
+    fn plain_text_spells_symbols_in_ascii_and_drops_emoji() {
+        let cases = [
+            ("✅ Reminder created successfully!", "Reminder created successfully!"),
+            ("⏰ Due: 2025-11-04 15:30 → in 2 hours", "Due: 2025-11-04 15:30 -> in 2 hours"),
+            ("   🔁 Recurrence: daily", "   Recurrence: daily"),
+            ("🗑️  Deleted 3 reminders…", "Deleted 3 reminders..."),
+            ("Progress ███▁▁ 60%", "Progress ###.. 60%"),
+            ("\x1b[1mBold\x1b[0m and plain", "Bold and plain"),
+            ("── Section ──", "-- Section --"),
+            ("ℹ️ Nothing to do", "Nothing to do"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(plain_text(input), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn plain_tables_have_no_borders_and_stay_aligned() {
+        let mut table = Table::new();
+        table.add_row(row!["ID", "📝 Message", "Due"]);
+        table.add_row(row!["1a2b3c4d", "✅ Call mom", "2025-11-04 15:30"]);
+        table.add_row(row!["5e6f", "Stretch…", "2025-11-05 09:00"]);
+
+        assert_eq!(
+            plain_table(&mut table),
+            [
+                " ID        Message     Due",
+                " 1a2b3c4d  Call mom    2025-11-04 15:30",
+                " 5e6f      Stretch...  2025-11-05 09:00",
+            ]
+            .join("\n")
+        );
+    }
+
+    // The only test that switches the global mode, so others can't race it
+    #[test]
+    fn plain_render_drops_rules_and_decoration_only_lines() {
+        set_plain(true);
+        let banner = render("════════\n📋 Your reminders\n════════").map(Cow::into_owned);
+        let rule = render("──────").map(Cow::into_owned);
+        set_plain(false);
+
+        assert_eq!(banner.as_deref(), Some("Your reminders"));
+        assert_eq!(rule, None);
+        assert_eq!(render("📋 Your reminders").as_deref(), Some("📋 Your reminders"));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::models::ReminderPreferences;
+use crate::storage::{read_json, write_json, StorageError};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+pub struct PreferenceStorage {
+    preferences: RwLock<Vec<ReminderPreferences>>,
+    path: PathBuf,
+}
+
+impl PreferenceStorage {
+    /// Opens the preferences file that sits beside the reminder storage file.
+    pub fn open_beside(storage_path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::open(storage_path.as_ref().with_file_name(PREFERENCES_FILE))
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, StorageError> {
+        let path = path.into();
+        let preferences = read_json(&path)?;
+
+        Ok(Self {
+            preferences: RwLock::new(preferences),
+            path,
+        })
+    }
+
+    /// `username`'s preferences, or `None` if they never set any.
+    pub async fn get(&self, username: &str) -> Option<ReminderPreferences> {
+        let preferences = self.preferences.read().await;
+        preferences.iter().find(|p| p.username == username).cloned()
+    }
+
+    /// Stores `preferences`, replacing any the user had before.
+    pub async fn put(&self, preferences: ReminderPreferences) -> Result<ReminderPreferences, StorageError> {
+        let mut all = self.preferences.write().await;
+
+        match all.iter_mut().find(|p| p.username == preferences.username) {
+            Some(existing) => *existing = preferences.clone(),
+            None => all.push(preferences.clone()),
+        }
+
+        write_json(&self.path, &*all)?;
+        Ok(preferences)
+    }
+}
@@ -0,0 +1,352 @@
+use crate::models::{
+    AckRequest, BulkReassignRequest, CapabilitiesResponse, CreateReminderRequest, CreateReminderResponse, CreateTemplateRequest, GroupActionResponse, ImportRemindersResponse,
+    InstantiateTemplateRequest, InstantiateTemplateResponse, OccurrencesResponse, PreferencesRequest, PreviewResponse, PurgeRequest,
+    PurgeResponse, ReassignRequest, ReassignResponse, Reminder, TaskImportQuery, TaskImportResponse, ReminderListQuery, ReminderListResponse, ReminderPreferences, ReminderTemplate, SnoozeRequest, TemplateListResponse, TestNotifyRequest, TestNotifyResponse,
+    UpdateReminderRequest, CLIENT_SOURCE_HEADER,
+};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+/// Why a call to the reminder service failed.
+#[derive(Debug)]
+pub enum ClientError {
+    /// The server could not be reached at all
+    Connect(reqwest::Error),
+    /// The server answered with a non-success status and this message, plus
+    /// the machine-readable error code when the server sent one
+    Api { status: StatusCode, message: String, code: Option<String> },
+    /// The server reported success but the body isn't the JSON the call
+    /// expects, e.g. an HTML page from a proxy. `body` is the text as received.
+    UnexpectedBody { status: StatusCode, body: String },
+    /// Any other transport or decoding failure
+    Http(reqwest::Error),
+}
+
+impl ClientError {
+    pub fn is_connect(&self) -> bool {
+        matches!(self, ClientError::Connect(_))
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Api { status, .. } | ClientError::UnexpectedBody { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// The server's error code (e.g. `not_found`, `conflict`), if any.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            ClientError::Api { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Connect(e) => write!(f, "Could not connect to the reminder service: {}", e),
+            ClientError::Api { status, message, .. } => write!(f, "{} ({})", message, status),
+            ClientError::UnexpectedBody { status, body } if body.trim().is_empty() => {
+                write!(f, "The reminder service sent an empty response ({})", status)
+            }
+            ClientError::UnexpectedBody { status, body } => {
+                write!(f, "The reminder service sent a response that isn't JSON ({}): {}", status, body.trim())
+            }
+            ClientError::Http(e) => write!(f, "Request to the reminder service failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClientError::Connect(e) | ClientError::Http(e) => Some(e),
+            ClientError::Api { .. } | ClientError::UnexpectedBody { .. } => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_connect() {
+            ClientError::Connect(e)
+        } else {
+            ClientError::Http(e)
+        }
+    }
+}
+
+/// Typed client for the reminder service's HTTP API.
+#[derive(Debug, Clone)]
+pub struct ReminderClient {
+    base_url: String,
+    http: reqwest::Client,
+    token: Option<String>,
+    source: Option<String>,
+}
+
+impl ReminderClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            token: None,
+            source: None,
+        }
+    }
+
+    /// Skips certificate verification for `https` URLs, for self-signed
+    /// certificates while testing. Never use this against a real deployment.
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        if insecure {
+            self.http = reqwest::Client::builder()
+                .danger_accept_invalid_certs(true)
+                .build()
+                .expect("TLS backend initializes");
+        }
+        self
+    }
+
+    /// Sends `token` as a bearer token on every request.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Names the calling program in the `X-Client-Source` header, so the
+    /// reminders it creates record where they came from.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub async fn create(&self, request: &CreateReminderRequest) -> Result<CreateReminderResponse, ClientError> {
+        self.send(self.request(Method::POST, "/reminders").json(request)).await
+    }
+
+    /// Upcoming reminders.
+    pub async fn list(&self) -> Result<Vec<Reminder>, ClientError> {
+        let list: ReminderListResponse = self.send(self.request(Method::GET, "/reminders")).await?;
+        Ok(list.reminders)
+    }
+
+    /// Reminders matching the query's filters; see `ReminderListQuery`.
+    pub async fn list_matching(&self, query: &ReminderListQuery) -> Result<Vec<Reminder>, ClientError> {
+        let list: ReminderListResponse = self.send(self.request(Method::GET, "/reminders").query(query)).await?;
+        Ok(list.reminders)
+    }
+
+    /// Every stored reminder, including sent and completed ones.
+    pub async fn list_all(&self) -> Result<Vec<Reminder>, ClientError> {
+        let list: ReminderListResponse = self.send(self.request(Method::GET, "/admin/reminders")).await?;
+        Ok(list.reminders)
+    }
+
+    /// Upcoming reminders whose message, title, or description contains `query`.
+    pub async fn search(&self, query: &str) -> Result<Vec<Reminder>, ClientError> {
+        let request = self.request(Method::GET, "/reminders/search").query(&[("q", query)]);
+        let list: ReminderListResponse = self.send(request).await?;
+        Ok(list.reminders)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Reminder, ClientError> {
+        self.send(self.request(Method::GET, &format!("/reminders/{}", id))).await
+    }
+
+    /// The next `count` fire times of a stored reminder.
+    pub async fn occurrences(&self, id: &str, count: usize) -> Result<OccurrencesResponse, ClientError> {
+        let request = self
+            .request(Method::GET, &format!("/reminders/{}/occurrences", id))
+            .query(&[("count", count)]);
+        self.send(request).await
+    }
+
+    /// Validates `request` and returns its first `count` fire times without creating it.
+    pub async fn preview(&self, request: &CreateReminderRequest, count: usize) -> Result<PreviewResponse, ClientError> {
+        let request = self
+            .request(Method::POST, "/reminders/preview")
+            .query(&[("count", count)])
+            .json(request);
+        self.send(request).await
+    }
+
+    pub async fn update(&self, id: &str, changes: &UpdateReminderRequest) -> Result<Reminder, ClientError> {
+        self.send(self.request(Method::PATCH, &format!("/reminders/{}", id)).json(changes)).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<(), ClientError> {
+        check(self.request(Method::DELETE, &format!("/reminders/{}", id)).send().await?).await?;
+        Ok(())
+    }
+
+    pub async fn snooze(&self, id: &str, minutes: Option<i64>) -> Result<Reminder, ClientError> {
+        let request = self
+            .request(Method::POST, &format!("/reminders/{}/snooze", id))
+            .json(&SnoozeRequest { minutes });
+        self.send(request).await
+    }
+
+    pub async fn complete(&self, id: &str, skip_occurrence: bool) -> Result<Reminder, ClientError> {
+        let request = self
+            .request(Method::POST, &format!("/reminders/{}/complete", id))
+            .query(&[("skip_occurrence", skip_occurrence)]);
+        self.send(request).await
+    }
+
+    /// Moves a recurring reminder past its upcoming occurrence.
+    pub async fn skip_next(&self, id: &str) -> Result<Reminder, ClientError> {
+        self.send(self.request(Method::POST, &format!("/reminders/{}/skip-next", id))).await
+    }
+
+    pub async fn ack(&self, id: &str, by: &str) -> Result<Reminder, ClientError> {
+        let request = self
+            .request(Method::POST, &format!("/reminders/{}/ack", id))
+            .json(&AckRequest { by: by.to_string() });
+        self.send(request).await
+    }
+
+    pub async fn reassign(&self, id: &str, username: &str) -> Result<Reminder, ClientError> {
+        let request = self
+            .request(Method::POST, &format!("/reminders/{}/reassign", id))
+            .json(&ReassignRequest { username: username.to_string() });
+        self.send(request).await
+    }
+
+    /// Moves every reminder of user `from` to `username`.
+    pub async fn reassign_all(&self, from: &str, username: &str) -> Result<ReassignResponse, ClientError> {
+        let request = self.request(Method::POST, "/reminders/reassign").json(&BulkReassignRequest {
+            from: from.to_string(),
+            username: username.to_string(),
+        });
+        self.send(request).await
+    }
+
+    pub async fn capabilities(&self) -> Result<CapabilitiesResponse, ClientError> {
+        self.send(self.request(Method::GET, "/capabilities")).await
+    }
+
+    pub async fn purge(&self, request: &PurgeRequest) -> Result<PurgeResponse, ClientError> {
+        self.send(self.request(Method::POST, "/reminders/purge").json(request)).await
+    }
+
+    pub async fn test_notify(&self, request: &TestNotifyRequest) -> Result<TestNotifyResponse, ClientError> {
+        self.send(self.request(Method::POST, "/reminders/test-notify").json(request)).await
+    }
+
+    /// Imports exported reminders. Entries are sent as raw JSON so the server
+    /// can skip malformed ones individually.
+    pub async fn import(&self, entries: &[serde_json::Value], preserve_ids: bool) -> Result<ImportRemindersResponse, ClientError> {
+        let request = self
+            .request(Method::POST, "/admin/reminders/import")
+            .query(&[("preserve_ids", preserve_ids)])
+            .json(entries);
+        self.send(request).await
+    }
+
+    /// Imports a Todoist or Google Tasks CSV export.
+    pub async fn import_tasks(&self, csv: String, query: &TaskImportQuery) -> Result<TaskImportResponse, ClientError> {
+        let request = self
+            .request(Method::POST, "/reminders/import")
+            .query(query)
+            .header(reqwest::header::CONTENT_TYPE, "text/csv")
+            .body(csv);
+        self.send(request).await
+    }
+
+    pub async fn create_template(&self, request: &CreateTemplateRequest) -> Result<ReminderTemplate, ClientError> {
+        self.send(self.request(Method::POST, "/templates").json(request)).await
+    }
+
+    pub async fn list_templates(&self, username: Option<&str>) -> Result<Vec<ReminderTemplate>, ClientError> {
+        let request = self.request(Method::GET, "/templates").query(&[("username", username)]);
+        let list: TemplateListResponse = self.send(request).await?;
+        Ok(list.templates)
+    }
+
+    pub async fn delete_template(&self, name: &str, username: Option<&str>) -> Result<(), ClientError> {
+        let request = self
+            .request(Method::DELETE, &format!("/templates/{}", name))
+            .query(&[("username", username)]);
+        check(request.send().await?).await?;
+        Ok(())
+    }
+
+    pub async fn instantiate_template(
+        &self,
+        name: &str,
+        request: &InstantiateTemplateRequest,
+    ) -> Result<InstantiateTemplateResponse, ClientError> {
+        let request = self
+            .request(Method::POST, &format!("/templates/{}/instantiate", name))
+            .json(request);
+        self.send(request).await
+    }
+
+    pub async fn complete_group(&self, group: &str) -> Result<GroupActionResponse, ClientError> {
+        self.send(self.request(Method::POST, &format!("/groups/{}/complete", group))).await
+    }
+
+    pub async fn delete_group(&self, group: &str) -> Result<GroupActionResponse, ClientError> {
+        self.send(self.request(Method::DELETE, &format!("/groups/{}", group))).await
+    }
+
+    pub async fn preferences(&self, username: &str) -> Result<ReminderPreferences, ClientError> {
+        self.send(self.request(Method::GET, &format!("/preferences/{}", username))).await
+    }
+
+    pub async fn set_preferences(&self, username: &str, request: &PreferencesRequest) -> Result<ReminderPreferences, ClientError> {
+        self.send(self.request(Method::PUT, &format!("/preferences/{}", username)).json(request)).await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self.http.request(method, format!("{}{}", self.base_url, path));
+        if let Some(source) = &self.source {
+            request = request.header(CLIENT_SOURCE_HEADER, source);
+        }
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let response = check(request.send().await?).await?;
+        let status = response.status();
+        let body = response.text().await?;
+        serde_json::from_str(&body).map_err(|_| ClientError::UnexpectedBody { status, body })
+    }
+}
+
+/// Turns a non-success response into `ClientError::Api`, taking the message
+/// and code from a JSON `{"error": ..., "code": ...}` body when there is one and
+/// the raw text otherwise.
+async fn check(response: Response) -> Result<Response, ClientError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let json = serde_json::from_str::<serde_json::Value>(&body).ok();
+    let field = |name: &str| {
+        json.as_ref()
+            .and_then(|v| v.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let code = field("code");
+    let message = field("error").unwrap_or(body);
+    let message = if message.trim().is_empty() {
+        status.canonical_reason().unwrap_or("Request failed").to_string()
+    } else {
+        message
+    };
+
+    Err(ClientError::Api { status, message, code })
+}
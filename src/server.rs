@@ -0,0 +1,2139 @@
+use crate::auth;
+use crate::build_info;
+use crate::config::Config;
+use crate::due_time::{self, DueTimeRules};
+use crate::durations;
+use crate::event_log::{EventLog, FireEvent};
+use crate::ids;
+use crate::models::{
+    is_valid_hex_color, parse_recurrence_rule, validate_link, CLIENT_SOURCE_HEADER, recurrence_formats, AckRequest, BulkReassignRequest, CapabilitiesResponse, CompleteQuery, CreateReminderRequest, CreateReminderResponse, DueSoonQuery,
+    CreateTemplateRequest, GroupActionResponse, ImportQuery, ImportRemindersResponse, InstantiateTemplateRequest,
+    InstantiateTemplateResponse, ListFormat, MissedPolicy, OccurrencesQuery, OccurrencesResponse, ParseReminderRequest, PreferencesRequest, PreviewResponse,
+    PurgeFilter, PurgeRequest, PurgeResponse, ReassignRequest, ReassignResponse, RecurrenceAnchor, ReloadSummary, RecurrenceRule, Reminder, ReminderChanges, ReminderListQuery, ReminderListResponse,
+    QuietHours, ReminderPreferences, ReminderTemplate, SearchQuery, SnoozeRequest, TaskImportFormat, TaskImportQuery, TaskImportResponse, TaskImportStatus, TemplateListResponse, TemplateQuery, TestNotifyRequest,
+    HealthResponse, TransferRequest, TransferResponse, VersionResponse, TestNotifyResponse, UpdateReminderRequest, ValidationIssue, ValidationReport,
+};
+use crate::preference_storage::PreferenceStorage;
+use crate::storage::{ReminderStorage, StorageError};
+use crate::task_import::{self, TaskImportOptions};
+use crate::template_storage::TemplateStorage;
+use axum::{
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{
+        header::{ACCEPT, AUTHORIZATION, CONTENT_DISPOSITION, CONTENT_TYPE, LOCATION},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    routing::{delete, get, patch, post, put},
+    Json, Router,
+};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, error, info, warn};
+
+const MAX_MESSAGE_LENGTH: usize = 1000;
+const MAX_SOURCE_LENGTH: usize = 64;
+const MAX_GROUP_LENGTH: usize = 64;
+const DEFAULT_OCCURRENCES: usize = 5;
+const STARTUP_SUMMARY_COUNT: usize = 3;
+const MAX_OCCURRENCES: usize = 100;
+const DEFAULT_SNOOZE_MINUTES: i64 = 30;
+const DEFAULT_DUE_SOON_MINUTES: i64 = 60;
+// Reminders overdue by more than this were missed (server down) rather than just picked up late
+const MISSED_THRESHOLD_SECS: i64 = 60;
+/// Most missed occurrences a `fire_all` reminder delivers in one pass; the
+/// rest are dropped so a long outage can't stall the loop.
+const MAX_CATCH_UP_FIRES: usize = 50;
+// Longest the notification loop waits between attempts while storage keeps failing
+const MAX_FAILURE_BACKOFF: Duration = Duration::from_secs(300);
+// Consecutive failed passes after which the loop re-reads the reminders file
+// and /healthz reports the service as degraded
+const RELOAD_AFTER_FAILURES: u32 = 3;
+
+/// Where a reminder's notifications can be sent: printed to stdout, or
+/// written to the server log.
+pub const NOTIFICATION_CHANNELS: &[&str] = &["stdout", "log"];
+
+pub struct AppState {
+    pub storage: Arc<ReminderStorage>,
+    pub templates: Arc<TemplateStorage>,
+    pub preferences: Arc<PreferenceStorage>,
+    /// Shared secret for signed bearer tokens; `None` runs unauthenticated
+    pub auth_secret: Option<String>,
+    pub max_body_kb: usize,
+    /// Principals that see and change every reminder when authentication is enabled
+    pub admins: Vec<String>,
+    pub due_time_rules: DueTimeRules,
+    /// Shared with the notification loop, which records its storage failures here
+    pub notifier_health: Arc<NotifierHealth>,
+}
+
+impl AppState {
+    pub fn from_config(
+        storage: Arc<ReminderStorage>,
+        templates: Arc<TemplateStorage>,
+        preferences: Arc<PreferenceStorage>,
+        config: &Config,
+    ) -> Self {
+        Self {
+            storage,
+            templates,
+            preferences,
+            auth_secret: config.server.shared_secret.clone(),
+            max_body_kb: config.limits.max_body_kb,
+            admins: config.server.admins.clone(),
+            due_time_rules: config.scheduling.due_time_rules(),
+            notifier_health: Arc::new(NotifierHealth::default()),
+        }
+    }
+}
+
+/// How the notification loop has been faring with storage, and whether it is
+/// still running at all.
+#[derive(Debug, Default)]
+pub struct NotifierHealth {
+    consecutive_failures: AtomicU32,
+    stopped: AtomicBool,
+    last_error: Mutex<Option<String>>,
+    last_success: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl NotifierHealth {
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.load(Ordering::Relaxed)
+    }
+
+    /// Records a failed pass and returns how many have failed in a row.
+    fn record_failure(&self, error: &StorageError) -> u32 {
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(error.to_string());
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Records a clean pass and returns how many had failed before it.
+    fn record_success(&self) -> u32 {
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        *self.last_success.lock().unwrap_or_else(|e| e.into_inner()) = Some(Utc::now());
+        self.consecutive_failures.swap(0, Ordering::Relaxed)
+    }
+
+    /// Whether the notification loop has exited, normally or by panicking.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    fn report(&self) -> HealthResponse {
+        let consecutive_failures = self.consecutive_failures();
+        let status = if self.is_stopped() {
+            "stopped"
+        } else if consecutive_failures >= RELOAD_AFTER_FAILURES {
+            "degraded"
+        } else {
+            "ok"
+        };
+        HealthResponse {
+            status: status.to_string(),
+            consecutive_failures,
+            last_error: self.last_error.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            last_success: *self.last_success.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+/// A failed request. Serialized as `{"error": <message>, "code": <code>}`
+/// with the status from `status()`.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("{0}")]
+    Forbidden(String),
+    /// The request is valid but the reminder's state doesn't allow it
+    #[error("{0}")]
+    Conflict(String),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Storage(e) => match e {
+                StorageError::NotFound(_) => StatusCode::NOT_FOUND,
+                StorageError::Conflict(_) => StatusCode::CONFLICT,
+                StorageError::QuotaExceeded(_) => StatusCode::INSUFFICIENT_STORAGE,
+                StorageError::Invalid(_) => StatusCode::BAD_REQUEST,
+                StorageError::Io { .. } | StorageError::Corrupt { .. } | StorageError::Encryption(_) => {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            },
+        }
+    }
+
+    /// Stable identifier for the kind of failure, for clients to branch on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Storage(e) => match e {
+                StorageError::NotFound(_) => "not_found",
+                StorageError::Conflict(_) => "conflict",
+                StorageError::QuotaExceeded(_) => "quota_exceeded",
+                StorageError::Invalid(_) => "invalid",
+                StorageError::Io { .. } => "storage_io",
+                StorageError::Corrupt { .. } => "storage_corrupt",
+                StorageError::Encryption(_) => "storage_encryption",
+            },
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status.is_server_error() {
+            error!(code = self.code(), error = %self, "Request failed");
+        }
+
+        let body = serde_json::json!({ "error": self.to_string(), "code": self.code() });
+        (status, Json(body)).into_response()
+    }
+}
+
+fn reminder_not_found(id: &str) -> ApiError {
+    StorageError::NotFound(format!("Reminder {}", id)).into()
+}
+
+pub fn build_router(state: Arc<AppState>) -> Router {
+    let max_body_kb = state.max_body_kb;
+
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/version", get(version))
+        .route("/capabilities", get(capabilities))
+        .route("/reminders", post(create_reminder))
+        .route("/reminders", get(get_reminders))
+        .route("/reminders/search", get(search_reminders))
+        .route("/reminders/due-soon", get(due_soon_reminders))
+        .route("/reminders/preview", post(preview_reminder))
+        .route("/reminders/parse", post(create_reminder_from_text))
+        .route("/reminders/test-notify", post(test_notify))
+        .route("/reminders/purge", post(purge_reminders))
+        .route("/reminders/import", post(import_tasks))
+        .route("/reminders/reassign", post(reassign_all_reminders))
+        .route("/reminders/transfer", post(transfer_reminders))
+        .route("/reminders/:id", get(get_reminder))
+        .route("/reminders/:id", delete(delete_reminder))
+        .route("/reminders/:id", patch(update_reminder))
+        .route("/reminders/:id/occurrences", get(get_occurrences))
+        .route("/reminders/:id/snooze", post(snooze_reminder))
+        .route("/reminders/:id/complete", post(complete_reminder))
+        .route("/reminders/:id/ack", post(ack_reminder))
+        .route("/reminders/:id/skip-next", post(skip_next_occurrence))
+        .route("/reminders/:id/reassign", post(reassign_reminder))
+        .route("/groups/:group", delete(delete_group))
+        .route("/groups/:group/complete", post(complete_group))
+        .route("/preferences/:username", get(get_preferences))
+        .route("/preferences/:username", put(put_preferences))
+        .route("/templates", post(create_template))
+        .route("/templates", get(list_templates))
+        .route("/templates/:name", delete(delete_template))
+        .route("/templates/:name/instantiate", post(instantiate_template))
+        .route("/admin/reminders", get(export_reminders))
+        .route("/admin/reminders/import", post(import_reminders))
+        .route("/admin/reload", post(reload_reminders))
+        .route("/admin/validate", post(validate_store))
+        .layer(DefaultBodyLimit::max(max_body_kb.saturating_mul(1024)))
+        .with_state(state)
+}
+
+/// 200 while the notification loop is keeping up, 503 once it has failed
+/// `RELOAD_AFTER_FAILURES` passes in a row or has stopped running.
+async fn healthz(State(state): State<Arc<AppState>>) -> (StatusCode, Json<HealthResponse>) {
+    let report = state.notifier_health.report();
+    let status = if report.status == "ok" { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+async fn version() -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: build_info::VERSION.to_string(),
+        git_commit: build_info::GIT_COMMIT.map(str::to_string),
+    })
+}
+
+/// Version, settings clients need to know, and accepted input formats.
+/// Needs no authentication.
+async fn capabilities(State(state): State<Arc<AppState>>) -> Json<CapabilitiesResponse> {
+    Json(CapabilitiesResponse {
+        version: build_info::VERSION.to_string(),
+        auth_required: state.auth_secret.is_some(),
+        max_body_kb: state.max_body_kb,
+        recurrence_formats: recurrence_formats(),
+        due_time_examples: due_time::ACCEPTED_EXAMPLES.iter().map(|e| e.to_string()).collect(),
+        task_import_formats: vec![TaskImportFormat::TodoistCsv, TaskImportFormat::GoogleTasksCsv, TaskImportFormat::Ics],
+    })
+}
+
+async fn create_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateReminderRequest>,
+) -> Result<Response, ApiError> {
+    insert_reminder(&state, &headers, payload).await
+}
+
+/// Creates a reminder from one sentence such as "remind me to call mom
+/// tomorrow at 5pm", for chat integrations. `POST /reminders` stays the main
+/// API; this only fills in its message and due time.
+async fn create_reminder_from_text(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<ParseReminderRequest>,
+) -> Result<Response, ApiError> {
+    let timezone = match (payload.timezone, payload.username.as_deref()) {
+        (Some(zone), _) => Some(zone),
+        (None, Some(username)) => state.preferences.get(username).await.and_then(|p| p.timezone),
+        (None, None) => None,
+    };
+    let mut rules = state.due_time_rules;
+    if let Some(ref zone) = timezone {
+        rules.default_timezone = Some(parse_timezone(zone)?);
+    }
+
+    let extracted = due_time::extract_due_time(&payload.text, Utc::now(), &rules).ok_or_else(|| {
+        ApiError::BadRequest("No time found in the text. Try e.g. 'tomorrow at 5pm', 'friday', or 'in 2 hours'".to_string())
+    })?;
+
+    let request = CreateReminderRequest {
+        message: strip_reminder_phrase(&extracted.rest).to_string(),
+        due_time: extracted.due_time.to_rfc3339(),
+        username: payload.username,
+        timezone,
+        ..Default::default()
+    };
+    insert_reminder(&state, &headers, request).await
+}
+
+/// "remind me to call mom" -> "call mom".
+fn strip_reminder_phrase(text: &str) -> &str {
+    const PHRASES: &[&str] = &["remind me to ", "remind me ", "reminder to ", "reminder: ", "to "];
+
+    let text = text.trim().trim_end_matches([',', ';', ':']);
+    PHRASES
+        .iter()
+        .find_map(|phrase| {
+            text.get(..phrase.len())
+                .filter(|start| start.eq_ignore_ascii_case(phrase))
+                .map(|_| text[phrase.len()..].trim_start())
+        })
+        .unwrap_or(text)
+}
+
+/// Validates, stores, and answers a create request: 201 with the stored
+/// reminder and its `Location`. Fields the request leaves out come from the
+/// user's preferences, then from the server config.
+async fn insert_reminder(
+    state: &AppState,
+    headers: &HeaderMap,
+    mut payload: CreateReminderRequest,
+) -> Result<Response, ApiError> {
+    let caller = authenticate(state, headers)?;
+    let owner = authorize(state, headers, payload.username.as_deref())?;
+
+    if let Some(username) = payload.username.as_deref().or(owner.as_deref()) {
+        if let Some(preferences) = state.preferences.get(username).await {
+            apply_preferences(&mut payload, &preferences);
+        }
+    }
+
+    let lead_offsets = payload.lead_offsets.take().unwrap_or_default();
+    let mut reminder = build_reminder(payload, &state.due_time_rules)?;
+    reminder.owner = owner;
+    reminder.source = client_source(headers);
+    if !lead_offsets.is_empty() && reminder.series_id.is_none() {
+        reminder.series_id = Some(ids::next_id());
+    }
+    let leads = build_lead_reminders(&reminder, &lead_offsets, Utc::now())?;
+
+    let warnings = near_duplicate_warnings(state, &caller, &reminder).await?;
+    let saved_reminder = state.storage.add_reminder(reminder).await?;
+    let mut lead_reminders = Vec::new();
+    for lead in leads {
+        lead_reminders.push(state.storage.add_reminder(lead).await?);
+    }
+
+    let response = CreateReminderResponse {
+        id: saved_reminder.id.clone(),
+        message: saved_reminder.message.clone(),
+        due_time: saved_reminder.due_time.to_rfc3339(),
+        tags: saved_reminder.tags.clone(),
+        warnings,
+        reminder: saved_reminder,
+        lead_reminders,
+    };
+    let location = format!("/reminders/{}", response.id);
+
+    Ok((StatusCode::CREATED, [(LOCATION, location)], Json(response)).into_response())
+}
+
+/// Fills the fields `payload` leaves out from the user's preferences.
+fn apply_preferences(payload: &mut CreateReminderRequest, preferences: &ReminderPreferences) {
+    if payload.timezone.is_none() {
+        payload.timezone = preferences.timezone.clone();
+    }
+    if payload.channels.is_none() && !preferences.channels.is_empty() {
+        payload.channels = Some(preferences.channels.clone());
+    }
+    if payload.lead_offsets.is_none() && !preferences.lead_offsets.is_empty() {
+        payload.lead_offsets = Some(preferences.lead_offsets.clone());
+    }
+    if payload.quiet_hours.is_none() {
+        payload.quiet_hours = preferences.quiet_hours.clone();
+    }
+
+    // A preferred completion anchor only fits fixed-interval recurrences;
+    // other reminders keep the schedule default rather than being rejected
+    let anchor_fits = match preferences.recurrence_anchor {
+        Some(RecurrenceAnchor::Completion) => is_fixed_interval(payload.recurrence.as_deref()),
+        _ => true,
+    };
+    if payload.recurrence_anchor.is_none() && anchor_fits {
+        payload.recurrence_anchor = preferences.recurrence_anchor;
+    }
+}
+
+/// One earlier reminder per lead offset before `reminder`'s first due time,
+/// skipping those already past. They share its series, owner, and delivery
+/// settings but don't repeat.
+fn build_lead_reminders(reminder: &Reminder, offsets: &[String], now: DateTime<Utc>) -> Result<Vec<Reminder>, ApiError> {
+    let mut leads = Vec::new();
+    for offset in offsets {
+        let due_time = reminder
+            .due_time
+            .checked_sub_signed(parse_lead_offset(offset)?)
+            .ok_or_else(|| ApiError::BadRequest(format!("Lead offset '{}' reaches too far back", offset)))?;
+        if due_time <= now {
+            continue;
+        }
+
+        let mut lead = Reminder::new(
+            format!("{} (in {})", reminder.message, offset),
+            due_time,
+            reminder.username.clone(),
+            None,
+        );
+        lead.owner = reminder.owner.clone();
+        lead.series_id = reminder.series_id.clone();
+        lead.tags = reminder.tags.clone();
+        lead.quiet = reminder.quiet;
+        lead.timezone = reminder.timezone.clone();
+        lead.channels = reminder.channels.clone();
+        lead.quiet_hours = reminder.quiet_hours.clone();
+        lead.source = reminder.source.clone();
+        lead.link = reminder.link.clone();
+        lead.ignore_blackout = reminder.ignore_blackout;
+        lead.group_id = reminder.group_id.clone();
+        leads.push(lead);
+    }
+    Ok(leads)
+}
+
+/// How close two of a user's pending reminders have to be before a create
+/// warns that it may be a duplicate.
+const NEAR_DUPLICATE_WINDOW_SECS: i64 = 60;
+
+/// Warnings for pending reminders of the same user due within
+/// `NEAR_DUPLICATE_WINDOW_SECS` of `reminder`, among those `caller` may see.
+/// These never block the create.
+async fn near_duplicate_warnings(state: &AppState, caller: &Caller, reminder: &Reminder) -> Result<Vec<String>, ApiError> {
+    let existing = state.storage.get_all_reminders().await?;
+
+    Ok(existing
+        .iter()
+        .filter(|other| caller.may_access(other.owner.as_deref()))
+        .filter(|other| !other.sent && other.username == reminder.username)
+        .filter(|other| (other.due_time - reminder.due_time).num_seconds().abs() <= NEAR_DUPLICATE_WINDOW_SECS)
+        .map(|other| {
+            format!(
+                "Reminder {} ('{}') is already due at {}, within {}s of this one",
+                other.id,
+                other.message,
+                other.due_time.to_rfc3339(),
+                NEAR_DUPLICATE_WINDOW_SECS
+            )
+        })
+        .collect())
+}
+
+/// Validates a create request and builds the reminder it describes, without
+/// storing it.
+fn build_reminder(payload: CreateReminderRequest, rules: &DueTimeRules) -> Result<Reminder, ApiError> {
+    let mut rules = *rules;
+    if let Some(ref zone) = payload.timezone {
+        rules.default_timezone = Some(parse_timezone(zone)?);
+    }
+
+    let due_time = if payload.fire_immediately {
+        if !payload.due_time.trim().is_empty() {
+            return Err(ApiError::BadRequest(
+                "Set either due_time or fire_immediately, not both".to_string(),
+            ));
+        }
+        Utc::now()
+    } else {
+        parse_due_time(&payload.due_time, &rules)?
+    };
+
+    validate_message(&payload.message)?;
+
+    // Validate recurrence if provided
+    if let Some(ref recurrence) = payload.recurrence {
+        validate_recurrence(recurrence)?;
+    }
+
+    let recurrence_anchor = payload.recurrence_anchor.unwrap_or_default();
+    validate_anchor(recurrence_anchor, payload.recurrence.as_deref())?;
+
+    let group_id = match payload.group_id {
+        Some(ref group) => Some(validate_group(group)?.to_string()),
+        None => None,
+    };
+
+    let link = payload.link.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+    if let Some(ref link) = link {
+        validate_link(link).map_err(ApiError::BadRequest)?;
+    }
+
+    let channels = clean_tags(payload.channels.unwrap_or_default());
+    validate_channels(&channels)?;
+
+    if let Some(ref quiet_hours) = payload.quiet_hours {
+        quiet_hours.validate().map_err(ApiError::BadRequest)?;
+    }
+
+    if let Some(ref color) = payload.color {
+        if !is_valid_hex_color(color) {
+            return Err(ApiError::BadRequest("Invalid color. Use a hex color like #AABBCC".to_string()));
+        }
+    }
+
+    let mut reminder = Reminder::new(
+        payload.message,
+        due_time,
+        payload.username,
+        payload.recurrence,
+    );
+    reminder.recurrence_anchor = recurrence_anchor;
+    reminder.missed_policy = payload.missed_policy.unwrap_or_default();
+    reminder.color = payload.color;
+    reminder.title = payload.title;
+    reminder.description = payload.description;
+    reminder.series_id = payload.series_id;
+    reminder.linked_file_ids = payload.linked_file_ids;
+    reminder.linked_tags = payload.linked_tags;
+    reminder.tags = clean_tags(payload.tags);
+    reminder.quiet = payload.quiet;
+    reminder.timezone = rules.default_timezone.map(|zone| zone.name().to_string());
+    reminder.channels = channels;
+    reminder.quiet_hours = payload.quiet_hours;
+    reminder.link = link;
+    reminder.ignore_blackout = payload.ignore_blackout;
+    reminder.group_id = group_id;
+
+    Ok(reminder)
+}
+
+fn validate_anchor(anchor: RecurrenceAnchor, recurrence: Option<&str>) -> Result<(), ApiError> {
+    if anchor == RecurrenceAnchor::Completion && !is_fixed_interval(recurrence) {
+        return Err(ApiError::BadRequest(
+            "recurrence_anchor 'completion' needs a fixed-interval recurrence (e.g., '30 minutes')".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn is_fixed_interval(recurrence: Option<&str>) -> bool {
+    recurrence.is_some_and(|r| matches!(parse_recurrence_rule(r), Ok(RecurrenceRule::Every(_))))
+}
+
+fn parse_timezone(zone: &str) -> Result<Tz, ApiError> {
+    zone.parse()
+        .map_err(|_| ApiError::BadRequest(format!("Unknown timezone '{}'. Use an IANA name such as Europe/Berlin", zone)))
+}
+
+fn validate_group(group: &str) -> Result<&str, ApiError> {
+    let group = group.trim();
+    if group.is_empty() || group.contains('/') || group.chars().count() > MAX_GROUP_LENGTH {
+        return Err(ApiError::BadRequest(format!(
+            "Group names must be 1 to {} characters without '/'",
+            MAX_GROUP_LENGTH
+        )));
+    }
+    Ok(group)
+}
+
+fn validate_channels(channels: &[String]) -> Result<(), ApiError> {
+    match channels.iter().find(|c| !NOTIFICATION_CHANNELS.contains(&c.as_str())) {
+        Some(unknown) => Err(ApiError::BadRequest(format!(
+            "Unknown notification channel '{}'. Available: {}",
+            unknown,
+            NOTIFICATION_CHANNELS.join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The `X-Client-Source` header, trimmed and cut to `MAX_SOURCE_LENGTH`
+/// characters; `None` when missing, empty, or not text.
+fn client_source(headers: &HeaderMap) -> Option<String> {
+    let source = headers.get(CLIENT_SOURCE_HEADER)?.to_str().ok()?.trim();
+    (!source.is_empty()).then(|| source.chars().take(MAX_SOURCE_LENGTH).collect())
+}
+
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    tags.iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Who a request acts as.
+enum Caller {
+    /// Authentication is disabled: every request may see and change everything
+    Open,
+    User(String),
+    /// A principal listed in `server.admins`
+    Admin(String),
+}
+
+impl Caller {
+    /// Whether the caller may see or change a reminder owned by `owner`.
+    fn may_access(&self, owner: Option<&str>) -> bool {
+        match self {
+            Caller::Open | Caller::Admin(_) => true,
+            Caller::User(principal) => owner == Some(principal.as_str()),
+        }
+    }
+
+    /// The owner listings are limited to; `None` lists everyone's reminders.
+    fn scope(&self) -> Option<&str> {
+        match self {
+            Caller::User(principal) => Some(principal),
+            Caller::Open | Caller::Admin(_) => None,
+        }
+    }
+}
+
+/// When authentication is enabled, requires a valid bearer token and
+/// returns the principal it was issued to.
+fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<Caller, ApiError> {
+    let Some(ref secret) = state.auth_secret else {
+        return Ok(Caller::Open);
+    };
+
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or(ApiError::Unauthorized("Missing bearer token".to_string()))?;
+
+    let principal = auth::verify_token(token, secret, Utc::now())
+        .map_err(|e| ApiError::Unauthorized(format!("Invalid token: {}", e)))?;
+
+    if state.admins.contains(&principal) {
+        Ok(Caller::Admin(principal))
+    } else {
+        Ok(Caller::User(principal))
+    }
+}
+
+/// Authenticates a request that creates records for `username` and returns
+/// the owner to store. Users may only act for themselves; admins for anyone.
+fn authorize(state: &AppState, headers: &HeaderMap, username: Option<&str>) -> Result<Option<String>, ApiError> {
+    match authenticate(state, headers)? {
+        Caller::Open => Ok(username.map(str::to_string)),
+        Caller::Admin(principal) => Ok(Some(username.unwrap_or(&principal).to_string())),
+        Caller::User(principal) => match username {
+            Some(username) if username != principal => Err(ApiError::Forbidden(format!("Authenticated as '{}' but the reminder belongs to '{}'", principal, username))),
+            _ => Ok(Some(principal)),
+        },
+    }
+}
+
+/// What a template request does with the templates it reaches.
+#[derive(PartialEq)]
+enum TemplateAccess {
+    Read,
+    Change,
+}
+
+/// Authenticates a template request and returns whose templates it works
+/// with: `username`'s, or the shared ones (no owner) when it names no one.
+/// Users only reach their own and the shared ones, and only admins may
+/// create or delete shared templates.
+fn template_owner(state: &AppState, headers: &HeaderMap, username: Option<&str>, access: TemplateAccess) -> Result<Option<String>, ApiError> {
+    match (authenticate(state, headers)?, username) {
+        (Caller::User(principal), None) if access == TemplateAccess::Change => {
+            Err(ApiError::Forbidden(format!("'{}' is not an admin and cannot change shared templates", principal)))
+        }
+        (Caller::User(principal), Some(username)) if username != principal => {
+            Err(ApiError::Forbidden(format!("Authenticated as '{}' but the template belongs to '{}'", principal, username)))
+        }
+        _ => Ok(username.map(str::to_string)),
+    }
+}
+
+/// Requires the caller to own the stored reminder `id` (or be an admin).
+/// Unknown ids pass through so the handler can answer 404.
+async fn authorize_existing(state: &AppState, headers: &HeaderMap, id: &str) -> Result<(), ApiError> {
+    let caller = authenticate(state, headers)?;
+    if let Caller::Open = caller {
+        return Ok(());
+    }
+
+    let Some(reminder) = state.storage.get_reminder(id).await? else {
+        return Ok(());
+    };
+
+    if caller.may_access(reminder.owner.as_deref()) {
+        return Ok(());
+    }
+
+    let owner = reminder.owner.map(|o| format!("'{}'", o)).unwrap_or_else(|| "no one".to_string());
+    Err(ApiError::Forbidden(format!("Reminder {} belongs to {}", id, owner)))
+}
+
+/// `/admin` endpoints are limited to admins once authentication is enabled.
+fn authorize_admin(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    match authenticate(state, headers)? {
+        Caller::Open | Caller::Admin(_) => Ok(()),
+        Caller::User(principal) => Err(ApiError::Forbidden(format!("'{}' is not an admin", principal))),
+    }
+}
+
+/// The caller's upcoming reminders, or everyone's for admins and open mode.
+async fn visible_upcoming(state: &AppState, caller: &Caller) -> Result<Vec<Reminder>, ApiError> {
+    let reminders = match caller.scope() {
+        Some(owner) => state.storage.get_upcoming_reminders_owned_by(owner).await?,
+        None => state.storage.get_upcoming_reminders().await?,
+    };
+    Ok(reminders)
+}
+
+/// Every reminder the caller may see, sent and overdue ones included, by due time.
+async fn visible_all(state: &AppState, caller: &Caller) -> Result<Vec<Reminder>, ApiError> {
+    let mut reminders = state.storage.get_all_reminders().await?;
+    reminders.retain(|r| caller.may_access(r.owner.as_deref()));
+    reminders.sort_by_key(|r| r.due_time);
+    Ok(reminders)
+}
+
+fn parse_due_time(due_time: &str, rules: &DueTimeRules) -> Result<DateTime<Utc>, ApiError> {
+    due_time::parse_due_time(due_time, rules).map_err(|e| {
+        ApiError::BadRequest(format!(
+            "Invalid due_time '{}': {}. Accepted formats: {}",
+            due_time,
+            e,
+            due_time::accepted_examples()
+        ))
+    })
+}
+
+fn validate_message(message: &str) -> Result<(), ApiError> {
+    if message.trim().is_empty() {
+        return Err(ApiError::BadRequest("Message cannot be empty".to_string()));
+    }
+
+    if message.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(ApiError::BadRequest(format!("Message cannot exceed {} characters", MAX_MESSAGE_LENGTH)));
+    }
+
+    Ok(())
+}
+
+fn validate_recurrence(recurrence: &str) -> Result<(), ApiError> {
+    parse_recurrence_rule(recurrence)
+        .map(|_| ())
+        .map_err(ApiError::BadRequest)
+}
+
+/// Upcoming reminders as JSON, or as CSV with `?format=csv` or
+/// `Accept: text/csv`.
+async fn get_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ReminderListQuery>,
+) -> Result<Response, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+    let mut reminders = match query.sent {
+        Some(sent) => {
+            let mut reminders = visible_all(&state, &caller).await?;
+            reminders.retain(|r| r.sent == sent);
+            reminders
+        }
+        None => visible_upcoming(&state, &caller).await?,
+    };
+
+    if let Some(ref username) = query.username {
+        reminders.retain(|r| r.username.as_ref() == Some(username));
+    }
+    if let Some(ref group) = query.group {
+        reminders.retain(|r| r.group_id.as_ref() == Some(group));
+    }
+    if let Some(after) = query.due_after {
+        reminders.retain(|r| r.due_time >= after);
+    }
+    if let Some(before) = query.due_before {
+        reminders.retain(|r| r.due_time <= before);
+    }
+
+    let tags = query.tag_filter();
+    if !tags.is_empty() {
+        reminders.retain(|r| r.matches_tags(&tags, query.match_mode));
+    }
+
+    let accepts_csv = headers
+        .get(ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.split(',').any(|media| media.trim().starts_with("text/csv")));
+    let format = query.format.unwrap_or(if accepts_csv { ListFormat::Csv } else { ListFormat::Json });
+
+    Ok(match format {
+        ListFormat::Json => Json(ReminderListResponse { reminders }).into_response(),
+        ListFormat::Csv => (
+            [
+                (CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (CONTENT_DISPOSITION, "attachment; filename=reminders.csv"),
+            ],
+            reminders_csv(&reminders),
+        )
+            .into_response(),
+    })
+}
+
+const CSV_COLUMNS: &[&str] = &["id", "message", "due_time", "username", "recurrence", "sent", "created_at"];
+
+/// RFC 4180 CSV: CRLF line endings, fields quoted when they contain a comma,
+/// quote, or line break, with quotes doubled.
+fn reminders_csv(reminders: &[Reminder]) -> String {
+    let mut out = String::new();
+    push_csv_row(&mut out, CSV_COLUMNS.iter().map(|c| c.to_string()));
+
+    for reminder in reminders {
+        push_csv_row(&mut out, [
+            reminder.id.clone(),
+            reminder.message.clone(),
+            reminder.due_time.to_rfc3339(),
+            reminder.username.clone().unwrap_or_default(),
+            reminder.recurrence.clone().unwrap_or_default(),
+            reminder.sent.to_string(),
+            reminder.created_at.to_rfc3339(),
+        ]);
+    }
+    out
+}
+
+fn push_csv_row(out: &mut String, fields: impl IntoIterator<Item = String>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// Upcoming reminders whose message, title, or description contains `q`.
+async fn search_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<ReminderListResponse>, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+
+    let q = query.q.trim();
+    if q.is_empty() {
+        return Err(ApiError::BadRequest("Search query cannot be empty".to_string()));
+    }
+
+    let mut reminders = visible_upcoming(&state, &caller).await?;
+    reminders.retain(|r| r.matches_text(q));
+
+    Ok(Json(ReminderListResponse { reminders }))
+}
+
+/// Upcoming reminders due within the next `within_minutes`, soonest first.
+async fn due_soon_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<DueSoonQuery>,
+) -> Result<Json<ReminderListResponse>, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+
+    let minutes = query.within_minutes.unwrap_or(DEFAULT_DUE_SOON_MINUTES);
+    if minutes <= 0 {
+        return Err(ApiError::BadRequest("within_minutes must be positive".to_string()));
+    }
+
+    // Windows too large to represent reach past every due time anyway
+    let cutoff = chrono::Duration::try_minutes(minutes).and_then(|window| Utc::now().checked_add_signed(window));
+    let mut reminders = visible_upcoming(&state, &caller).await?;
+    reminders.retain(|r| cutoff.is_none_or(|cutoff| r.due_time <= cutoff));
+
+    Ok(Json(ReminderListResponse { reminders }))
+}
+
+async fn test_notify(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<TestNotifyRequest>,
+) -> Result<Json<TestNotifyResponse>, ApiError> {
+    let owner = authorize(&state, &headers, payload.username.as_deref())?;
+
+    let message = payload
+        .message
+        .unwrap_or_else(|| "This is a test notification".to_string());
+    let mut reminder = Reminder::new(message, Utc::now(), payload.username, None);
+    reminder.owner = owner;
+
+    let result = deliver_notification(&reminder, reminder.due_time);
+
+    Ok(Json(TestNotifyResponse {
+        delivered: result.is_ok(),
+        channel: "stdout".to_string(),
+        error: result.err().map(|e| e.to_string()),
+    }))
+}
+
+async fn get_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+
+    let reminder = state.storage.get_reminder(&id).await?;
+
+    reminder.map(Json).ok_or_else(|| reminder_not_found(&id))
+}
+
+async fn get_occurrences(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query): Query<OccurrencesQuery>,
+) -> Result<Json<OccurrencesResponse>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+
+    let reminder = state.storage.get_reminder(&id).await?
+        .ok_or_else(|| reminder_not_found(&id))?;
+
+    Ok(Json(OccurrencesResponse {
+        occurrences: reminder.occurrences(occurrence_count(&query)),
+        id: reminder.id,
+        recurrence: reminder.recurrence,
+    }))
+}
+
+/// Dry run of `POST /reminders`: validates the request the same way and
+/// returns the first occurrences it would fire at, storing nothing.
+async fn preview_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<OccurrencesQuery>,
+    Json(payload): Json<CreateReminderRequest>,
+) -> Result<Json<PreviewResponse>, ApiError> {
+    authorize(&state, &headers, payload.username.as_deref())?;
+
+    let reminder = build_reminder(payload, &state.due_time_rules)?;
+
+    Ok(Json(PreviewResponse {
+        occurrences: reminder.occurrences(occurrence_count(&query)),
+        recurrence: reminder.recurrence,
+    }))
+}
+
+fn occurrence_count(query: &OccurrencesQuery) -> usize {
+    query
+        .count
+        .unwrap_or(DEFAULT_OCCURRENCES)
+        .clamp(1, MAX_OCCURRENCES)
+}
+
+async fn update_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateReminderRequest>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+
+    let due_time = payload
+        .due_time
+        .as_deref()
+        .map(|due_time| parse_due_time(due_time, &state.due_time_rules))
+        .transpose()?;
+
+    if let Some(ref message) = payload.message {
+        validate_message(message)?;
+    }
+
+    if let Some(ref recurrence) = payload.recurrence {
+        validate_recurrence(recurrence)?;
+        if let Some(existing) = state.storage.get_reminder(&id).await? {
+            validate_anchor(existing.recurrence_anchor, Some(recurrence))?;
+        }
+    }
+
+    let updated = state
+        .storage
+        .update_reminder(&id, ReminderChanges {
+            message: payload.message,
+            due_time,
+            recurrence: payload.recurrence,
+            title: payload.title,
+            description: payload.description,
+            quiet: payload.quiet,
+        })
+        .await?;
+
+    Ok(Json(updated))
+}
+
+async fn delete_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+
+    state.storage.delete_reminder(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Looks up a reminder that can still be acted on: 404 if unknown, 409 if
+/// already completed.
+async fn get_active_reminder(state: &AppState, id: &str) -> Result<Reminder, ApiError> {
+    let reminder = state.storage.get_reminder(id).await?
+        .ok_or_else(|| reminder_not_found(id))?;
+
+    if reminder.completed {
+        return Err(ApiError::Conflict(format!("Reminder {} is already completed", id)));
+    }
+
+    Ok(reminder)
+}
+
+async fn snooze_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<SnoozeRequest>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+    get_active_reminder(&state, &id).await?;
+
+    let minutes = payload.minutes.unwrap_or(DEFAULT_SNOOZE_MINUTES);
+    if minutes <= 0 {
+        return Err(ApiError::BadRequest("Snooze minutes must be positive".to_string()));
+    }
+
+    let until = chrono::Duration::try_minutes(minutes)
+        .and_then(|snooze| Utc::now().checked_add_signed(snooze))
+        .ok_or_else(|| ApiError::BadRequest("Snooze minutes out of range".to_string()))?;
+    let updated = state.storage.snooze_reminder(&id, until).await?;
+
+    Ok(Json(updated))
+}
+
+async fn complete_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(query): Query<CompleteQuery>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+    let reminder = get_active_reminder(&state, &id).await?;
+
+    // Completion-relative reminders start their next cycle now instead of finishing
+    if let Some(next_time) = reminder.next_occurrence_after_completion(Utc::now()) {
+        state.storage.reschedule_reminder(&id, next_time).await?;
+        let updated = state.storage.get_reminder(&id).await?;
+        return updated.map(Json).ok_or_else(|| reminder_not_found(&id));
+    }
+
+    if query.skip_occurrence {
+        return skip_occurrence(&state, &reminder).await.map(Json);
+    }
+
+    let updated = state.storage.complete_reminder(&id).await?;
+
+    Ok(Json(updated))
+}
+
+/// Moves a recurring reminder past its upcoming occurrence without firing it.
+async fn skip_next_occurrence(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+    let reminder = get_active_reminder(&state, &id).await?;
+
+    skip_occurrence(&state, &reminder).await.map(Json)
+}
+
+async fn skip_occurrence(state: &AppState, reminder: &Reminder) -> Result<Reminder, ApiError> {
+    let next_time = reminder.calculate_next_occurrence().ok_or_else(|| {
+        ApiError::BadRequest(format!("Reminder {} is not recurring; complete it instead", reminder.id))
+    })?;
+
+    state.storage.reschedule_reminder(&reminder.id, next_time).await?;
+    let updated = state.storage.get_reminder(&reminder.id).await?;
+
+    updated.ok_or_else(|| reminder_not_found(&reminder.id))
+}
+
+async fn ack_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<AckRequest>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+
+    let by = payload.by.trim();
+    if by.is_empty() {
+        return Err(ApiError::BadRequest("'by' cannot be empty".to_string()));
+    }
+
+    let reminder = state.storage.ack_reminder(&id, by, Utc::now()).await?;
+    Ok(Json(reminder))
+}
+
+fn validate_username(username: &str) -> Result<&str, ApiError> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err(ApiError::BadRequest("'username' cannot be empty".to_string()));
+    }
+    Ok(username)
+}
+
+/// Hands a reminder to another user. Only its owner or an admin may do so.
+async fn reassign_reminder(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(payload): Json<ReassignRequest>,
+) -> Result<Json<Reminder>, ApiError> {
+    authorize_existing(&state, &headers, &id).await?;
+    let to = validate_username(&payload.username)?;
+
+    let reassigned = state.storage.reassign_reminders(|r| r.id == id, to).await?;
+    let reminder = reassigned.into_iter().next().ok_or_else(|| reminder_not_found(&id))?;
+
+    info!(reminder_id = %id, to = %to, "Reassigned reminder");
+    Ok(Json(reminder))
+}
+
+/// Hands every reminder with username `from` to another user; users only
+/// move reminders they own.
+async fn reassign_all_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<BulkReassignRequest>,
+) -> Result<Json<ReassignResponse>, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+    let from = validate_username(&payload.from)?;
+    let to = validate_username(&payload.username)?;
+
+    let reminders = state
+        .storage
+        .reassign_reminders(|r| r.username.as_deref() == Some(from) && caller.may_access(r.owner.as_deref()), to)
+        .await?;
+
+    info!(count = reminders.len(), from = %from, to = %to, "Reassigned reminders");
+    Ok(Json(ReassignResponse {
+        reassigned: reminders.len(),
+        reminders,
+    }))
+}
+
+/// Moves all of a user's reminders, sent ones included, to another user.
+/// Unlike `/reminders/reassign` this ignores who the caller is, so once
+/// authentication is enabled only admins may use it.
+async fn transfer_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<TransferRequest>,
+) -> Result<Json<TransferResponse>, ApiError> {
+    authorize_admin(&state, &headers)?;
+    let from = validate_username(&payload.from)?;
+    let to = validate_username(&payload.to)?;
+    if from == to {
+        return Err(ApiError::BadRequest("'from' and 'to' must be different users".to_string()));
+    }
+
+    let transferred = state.storage.transfer_owner(from, to).await?;
+
+    info!(transferred, from = %from, to = %to, "Transferred reminders");
+    Ok(Json(TransferResponse { transferred }))
+}
+
+/// Deletes old sent one-shot reminders; users only purge their own. A purge
+/// with neither `older_than_days` nor `username` has to set `confirm_all`.
+async fn purge_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<PurgeRequest>,
+) -> Result<Json<PurgeResponse>, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+
+    if !payload.sent {
+        return Err(ApiError::BadRequest("Only sent reminders can be purged; set \"sent\": true".to_string()));
+    }
+    if payload.older_than_days.is_none() && payload.username.is_none() && !payload.confirm_all {
+        return Err(ApiError::BadRequest(
+            "Refusing to purge every sent reminder; set older_than_days or username, or confirm_all".to_string(),
+        ));
+    }
+
+    let due_before = match payload.older_than_days {
+        Some(days) => Some(
+            chrono::Duration::try_days(days.into())
+                .and_then(|age| Utc::now().checked_sub_signed(age))
+                .ok_or_else(|| ApiError::BadRequest("older_than_days out of range".to_string()))?,
+        ),
+        None => None,
+    };
+
+    let filter = PurgeFilter {
+        due_before,
+        username: payload.username,
+        owner: caller.scope().map(str::to_string),
+    };
+    let purged = state.storage.purge_reminders(&filter, payload.dry_run).await?;
+
+    if !payload.dry_run {
+        info!(count = purged.len(), "Purged sent reminders");
+    }
+
+    Ok(Json(PurgeResponse {
+        removed: purged.len(),
+        dry_run: payload.dry_run,
+        reminders: purged,
+    }))
+}
+
+/// Completes every reminder in `group` the caller may change, recurring ones
+/// included: unlike completing a single after-completion reminder, nothing
+/// starts a new cycle, since the point is to finish the whole set.
+async fn complete_group(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(group): Path<String>,
+) -> Result<Json<GroupActionResponse>, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+
+    let reminders = state
+        .storage
+        .complete_reminders(|r| r.group_id.as_ref() == Some(&group) && caller.may_access(r.owner.as_deref()))
+        .await?;
+
+    info!(group = %group, count = reminders.len(), "Completed reminder group");
+    Ok(Json(GroupActionResponse { group, affected: reminders.len(), reminders }))
+}
+
+/// Deletes every reminder in `group` the caller may change.
+async fn delete_group(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(group): Path<String>,
+) -> Result<Json<GroupActionResponse>, ApiError> {
+    let caller = authenticate(&state, &headers)?;
+
+    let reminders = state
+        .storage
+        .delete_reminders(|r| r.group_id.as_ref() == Some(&group) && caller.may_access(r.owner.as_deref()))
+        .await?;
+
+    info!(group = %group, count = reminders.len(), "Deleted reminder group");
+    Ok(Json(GroupActionResponse { group, affected: reminders.len(), reminders }))
+}
+
+async fn get_preferences(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+) -> Result<Json<ReminderPreferences>, ApiError> {
+    authorize(&state, &headers, Some(&username))?;
+
+    state
+        .preferences
+        .get(&username)
+        .await
+        .map(Json)
+        .ok_or_else(|| StorageError::NotFound(format!("Preferences for '{}'", username)).into())
+}
+
+/// Replaces a user's preferences. They only affect reminders created
+/// afterwards.
+async fn put_preferences(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(username): Path<String>,
+    Json(payload): Json<PreferencesRequest>,
+) -> Result<Json<ReminderPreferences>, ApiError> {
+    authorize(&state, &headers, Some(&username))?;
+    let username = validate_username(&username)?.to_string();
+
+    if let Some(ref zone) = payload.timezone {
+        parse_timezone(zone)?;
+    }
+
+    let channels = clean_tags(payload.channels);
+    validate_channels(&channels)?;
+
+    let lead_offsets = clean_tags(payload.lead_offsets);
+    for offset in &lead_offsets {
+        parse_lead_offset(offset)?;
+    }
+
+    if let Some(ref quiet_hours) = payload.quiet_hours {
+        quiet_hours.validate().map_err(ApiError::BadRequest)?;
+    }
+
+    let preferences = ReminderPreferences {
+        username,
+        timezone: payload.timezone,
+        channels,
+        lead_offsets,
+        recurrence_anchor: payload.recurrence_anchor,
+        quiet_hours: payload.quiet_hours,
+        updated_at: Utc::now(),
+    };
+
+    Ok(Json(state.preferences.put(preferences).await?))
+}
+
+async fn create_template(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateTemplateRequest>,
+) -> Result<(StatusCode, Json<ReminderTemplate>), ApiError> {
+    let owner = template_owner(&state, &headers, payload.username.as_deref(), TemplateAccess::Change)?;
+
+    let name = payload.name.trim();
+    if name.is_empty() || name.contains('/') {
+        return Err(ApiError::BadRequest("Template name cannot be empty or contain '/'".to_string()));
+    }
+
+    validate_message(&payload.message)?;
+
+    if let Some(ref recurrence) = payload.recurrence {
+        validate_recurrence(recurrence)?;
+    }
+
+    let lead_offsets = clean_tags(payload.lead_offsets);
+    for offset in &lead_offsets {
+        parse_lead_offset(offset)?;
+    }
+
+    let template = ReminderTemplate {
+        name: name.to_string(),
+        username: owner,
+        message: payload.message,
+        recurrence: payload.recurrence,
+        lead_offsets,
+        tags: clean_tags(payload.tags),
+        created_at: Utc::now(),
+    };
+
+    let saved = state.templates.add(template).await?;
+    Ok((StatusCode::CREATED, Json(saved)))
+}
+
+async fn list_templates(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<TemplateQuery>,
+) -> Result<Json<TemplateListResponse>, ApiError> {
+    let owner = template_owner(&state, &headers, query.username.as_deref(), TemplateAccess::Read)?;
+
+    let templates = state.templates.list(owner.as_deref()).await?;
+    Ok(Json(TemplateListResponse { templates }))
+}
+
+async fn delete_template(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<TemplateQuery>,
+) -> Result<StatusCode, ApiError> {
+    let owner = template_owner(&state, &headers, query.username.as_deref(), TemplateAccess::Change)?;
+
+    state.templates.delete(&name, owner.as_deref()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Creates a reminder from a template at the given due time, plus one earlier
+/// reminder per lead offset that hasn't already passed. Everything goes
+/// through the same validation as `POST /reminders` before anything is stored.
+async fn instantiate_template(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(payload): Json<InstantiateTemplateRequest>,
+) -> Result<(StatusCode, Json<InstantiateTemplateResponse>), ApiError> {
+    let template_owner = template_owner(&state, &headers, payload.username.as_deref(), TemplateAccess::Read)?;
+    let owner = authorize(&state, &headers, payload.username.as_deref())?;
+
+    let template = state.templates.get(&name, template_owner.as_deref()).await?;
+    let series_id = (!template.lead_offsets.is_empty()).then(ids::next_id);
+
+    let request = CreateReminderRequest {
+        message: template.message.clone(),
+        due_time: payload.due_time,
+        username: template.username.clone(),
+        recurrence: template.recurrence.clone(),
+        series_id: series_id.clone(),
+        tags: template.tags.clone(),
+        ..Default::default()
+    };
+    let mut reminder = build_reminder(request, &state.due_time_rules)?;
+    reminder.owner = owner;
+    reminder.source = client_source(&headers);
+    let leads = build_lead_reminders(&reminder, &template.lead_offsets, Utc::now())?;
+
+    let reminder = state.storage.add_reminder(reminder).await?;
+    let mut lead_reminders = Vec::new();
+    for lead in leads {
+        lead_reminders.push(state.storage.add_reminder(lead).await?);
+    }
+
+    Ok((StatusCode::CREATED, Json(InstantiateTemplateResponse { reminder, lead_reminders })))
+}
+
+fn parse_lead_offset(offset: &str) -> Result<chrono::Duration, ApiError> {
+    durations::parse_offset(offset).map_err(|e| ApiError::BadRequest(format!("Invalid lead offset: {}", e)))
+}
+
+async fn export_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ReminderListResponse>, ApiError> {
+    authorize_admin(&state, &headers)?;
+
+    let reminders = state.storage.get_all_reminders().await?;
+
+    Ok(Json(ReminderListResponse { reminders }))
+}
+
+async fn import_reminders(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ImportQuery>,
+    Json(payload): Json<Vec<serde_json::Value>>,
+) -> Result<Json<ImportRemindersResponse>, ApiError> {
+    authorize_admin(&state, &headers)?;
+
+    let mut reminders = Vec::new();
+    let mut skipped = Vec::new();
+
+    // Parse entries individually so one malformed reminder doesn't reject the whole import
+    for (index, value) in payload.into_iter().enumerate() {
+        match serde_json::from_value::<Reminder>(value) {
+            Ok(reminder) => reminders.push(reminder),
+            Err(e) => skipped.push(format!("entry {}: {}", index, e)),
+        }
+    }
+
+    let (imported, duplicates) = state.storage.import_reminders(reminders, query.preserve_ids).await?;
+
+    skipped.extend(duplicates.into_iter().map(|id| format!("{}: id already exists", id)));
+
+    Ok(Json(ImportRemindersResponse { imported, skipped }))
+}
+
+/// Converts a Todoist or Google Tasks CSV export or an iCalendar file (the
+/// request body) into reminders and stores them in one write, unless
+/// `dry_run` is set. Calendar events already imported (same UID) are
+/// updated. Every row is reported with how it mapped or why it didn't.
+async fn import_tasks(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<TaskImportQuery>,
+    body: String,
+) -> Result<Json<TaskImportResponse>, ApiError> {
+    let owner = authorize(&state, &headers, query.username.as_deref())?;
+    let before = match query.before.as_deref() {
+        Some(before) => parse_lead_offset(before)?,
+        None => chrono::Duration::zero(),
+    };
+
+    let options = TaskImportOptions {
+        include_completed: query.include_completed,
+        username: query.username.clone(),
+        rules: state.due_time_rules,
+        before,
+        now: Utc::now(),
+    };
+    let converted = task_import::convert_tasks(query.format, &body, &options).map_err(ApiError::BadRequest)?;
+    let existing = state.storage.get_all_reminders().await?;
+
+    let mut rows = Vec::new();
+    let mut reminders = Vec::new();
+    for (mut row, reminder) in converted {
+        if let Some(mut reminder) = reminder {
+            match validate_message(&reminder.message) {
+                Ok(()) => {
+                    reminder.owner = owner.clone();
+                    let imported_before = existing.iter().any(|r| {
+                        r.external_id.is_some() && r.external_id == reminder.external_id && r.owner == reminder.owner
+                    });
+                    if imported_before {
+                        row.status = TaskImportStatus::Updated;
+                    }
+                    reminders.push(reminder);
+                }
+                Err(e) => {
+                    row.status = TaskImportStatus::Failed;
+                    row.reason = Some(e.to_string());
+                }
+            }
+        }
+        rows.push(row);
+    }
+
+    let (imported, updated) = if query.dry_run || reminders.is_empty() {
+        (0, 0)
+    } else {
+        state.storage.upsert_imported(reminders).await?
+    };
+    if imported + updated > 0 {
+        info!(imported, updated, format = ?query.format, "Imported tasks");
+    }
+
+    Ok(Json(TaskImportResponse {
+        imported,
+        updated,
+        dry_run: query.dry_run,
+        rows,
+    }))
+}
+
+/// Re-reads the reminders file after it was edited by hand.
+async fn reload_reminders(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Result<Json<ReloadSummary>, ApiError> {
+    authorize_admin(&state, &headers)?;
+
+    let summary = state.storage.reload().await?;
+    Ok(Json(summary))
+}
+
+/// Re-checks every stored reminder against the rules a create would apply
+/// today, without changing anything, so a stricter rule can be planned for.
+async fn validate_store(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Result<Json<ValidationReport>, ApiError> {
+    authorize_admin(&state, &headers)?;
+
+    let reminders = state.storage.get_all_reminders().await?;
+    let invalid = reminders
+        .iter()
+        .filter_map(|reminder| {
+            let problems = reminder_problems(reminder, &state.due_time_rules);
+            (!problems.is_empty()).then(|| ValidationIssue { id: reminder.id.clone(), problems })
+        })
+        .collect();
+
+    Ok(Json(ValidationReport { checked: reminders.len(), invalid }))
+}
+
+/// Why `reminder` would be rejected or misbehave under the current rules.
+fn reminder_problems(reminder: &Reminder, rules: &DueTimeRules) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if let Err(e) = validate_message(&reminder.message) {
+        problems.push(e.to_string());
+    }
+    if let Err(e) = parse_due_time(&reminder.due_time.to_rfc3339(), rules) {
+        problems.push(format!("Due time {} cannot be written as a valid due time: {}", reminder.due_time, e));
+    }
+
+    match reminder.recurrence.as_deref().map(parse_recurrence_rule) {
+        Some(Err(e)) => problems.push(e),
+        Some(Ok(rule)) => {
+            if reminder.recurrence_anchor == RecurrenceAnchor::Completion && !matches!(rule, RecurrenceRule::Every(_)) {
+                problems.push("recurrence_anchor 'completion' needs a fixed-interval recurrence".to_string());
+            }
+            if reminder.sent && !reminder.completed {
+                problems.push("Recurring reminder is marked as sent, so it will never fire again".to_string());
+            }
+        }
+        None => {}
+    }
+
+    if let Some(ref link) = reminder.link {
+        if let Err(e) = validate_link(link) {
+            problems.push(e);
+        }
+    }
+
+    if let Some(ref color) = reminder.color {
+        if !is_valid_hex_color(color) {
+            problems.push(format!("Invalid color '{}'", color));
+        }
+    }
+
+    problems
+}
+
+pub async fn print_upcoming_summary(storage: &ReminderStorage, verbose: bool) {
+    let upcoming = match storage.get_upcoming_reminders().await {
+        Ok(upcoming) => upcoming,
+        Err(e) => {
+            error!(path = %storage.path().display(), error = %e, "Failed to load upcoming reminders");
+            return;
+        }
+    };
+
+    let Some(next) = upcoming.first() else {
+        info!("No upcoming reminders");
+        return;
+    };
+
+    info!(count = upcoming.len(), next_due = %next.due_time, "Upcoming reminders");
+
+    if verbose {
+        for reminder in upcoming.iter().take(STARTUP_SUMMARY_COUNT) {
+            info!(
+                reminder_id = %reminder.id,
+                due = %reminder.due_time,
+                message = reminder.message.lines().next().unwrap_or(""),
+                "Upcoming reminder"
+            );
+        }
+    }
+}
+
+/// Runs `process_due` every `every` until `shutdown` is set to true (or its
+/// sender is dropped), recording each firing in `events` if given. While
+/// storage keeps failing, the wait grows exponentially (with jitter) up to
+/// `MAX_FAILURE_BACKOFF`, and every `RELOAD_AFTER_FAILURES` failures the
+/// reminders file is re-read. The first clean pass afterwards delivers
+/// whatever fell due during the outage, under each reminder's missed policy.
+/// `health` is marked stopped when the loop exits, including by panic.
+pub async fn notification_service(
+    storage: Arc<ReminderStorage>,
+    every: Duration,
+    events: Option<EventLog>,
+    blackout: Option<QuietHours>,
+    health: Arc<NotifierHealth>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    debug!(interval_secs = every.as_secs(), "Notification loop started");
+    let _running = StopOnDrop(health.clone());
+    let mut wait = Duration::ZERO;
+
+    loop {
+        tokio::select! {
+            _ = sleep(wait) => {}
+            changed = shutdown.changed() => {
+                if changed.is_err() || *shutdown.borrow() {
+                    break;
+                }
+                continue;
+            }
+        }
+
+        // After a failure the file may be behind memory, so recovery means
+        // being able to write it again, not just a pass with nothing due
+        let caught_up = match health.consecutive_failures() {
+            0 => Ok(()),
+            _ => storage.persist().await,
+        };
+
+        wait = match caught_up.and(process_due(&storage, events.as_ref(), blackout.as_ref()).await) {
+            Ok(()) => {
+                let failures = health.record_success();
+                if failures > 0 {
+                    info!(failures, "Reminder storage recovered; notifications are back on schedule");
+                }
+                every
+            }
+            Err(e) => {
+                let failures = health.record_failure(&e);
+                let backoff = failure_backoff(every, failures);
+                warn!(failures, retry_in_secs = backoff.as_secs(), error = %e, "Notification pass failed; backing off");
+                if failures.is_multiple_of(RELOAD_AFTER_FAILURES) {
+                    match storage.reload().await {
+                        Ok(_) => info!(path = %storage.path().display(), "Reloaded reminders file after repeated failures"),
+                        Err(e) => error!(path = %storage.path().display(), error = %e, "Failed to reload reminders file"),
+                    }
+                }
+                backoff
+            }
+        };
+    }
+
+    debug!("Notification loop stopped");
+}
+
+/// Marks the loop stopped when dropped, so a panic unwinding out of it
+/// still shows up in /healthz.
+struct StopOnDrop(Arc<NotifierHealth>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// `every` doubled for each consecutive failure, capped at
+/// `MAX_FAILURE_BACKOFF`, plus up to a quarter more so restarted replicas
+/// don't retry in lockstep.
+fn failure_backoff(every: Duration, failures: u32) -> Duration {
+    let base = every
+        .checked_mul(2u32.saturating_pow(failures.min(16)))
+        .unwrap_or(MAX_FAILURE_BACKOFF)
+        .min(MAX_FAILURE_BACKOFF);
+    let jitter_range = base.as_millis() / 4 + 1;
+    let jitter = Duration::from_millis((uuid::Uuid::new_v4().as_u128() % jitter_range) as u64);
+    (base + jitter).min(MAX_FAILURE_BACKOFF)
+}
+
+/// Delivers every reminder that is currently due, then reschedules recurring
+/// ones and marks the rest as sent. Reminders inside the `blackout` window
+/// (server-local time) or their own quiet hours wait for the next pass. A
+/// storage error while rescheduling or marking doesn't stop the pass; the
+/// last one is returned at the end.
+pub async fn process_due(
+    storage: &ReminderStorage,
+    events: Option<&EventLog>,
+    blackout: Option<&QuietHours>,
+) -> Result<(), StorageError> {
+    let path = storage.path().display();
+
+    let due_reminders = match storage.get_due_reminders().await {
+        Ok(due_reminders) => due_reminders,
+        Err(e) => {
+            error!(path = %path, error = %e, "Failed to check due reminders");
+            return Err(e);
+        }
+    };
+    let mut failure = None;
+
+    let now = Utc::now();
+
+    for reminder in due_reminders {
+        if let Some(until) = held_until(&reminder, blackout, now) {
+            debug!(reminder_id = %reminder.id, until = %until, "Holding reminder during blackout or quiet hours");
+            continue;
+        }
+
+        // A held firing is only late counting from when the hold ended
+        let due_from = held_until(&reminder, blackout, reminder.due_time).unwrap_or(reminder.due_time);
+        let missed = now - due_from > chrono::Duration::seconds(MISSED_THRESHOLD_SECS);
+
+        let fire_times = match (missed, reminder.missed_policy) {
+            (false, _) | (true, MissedPolicy::FireOnce) => vec![reminder.due_time],
+            (true, MissedPolicy::Skip) => {
+                info!(reminder_id = %reminder.id, due = %reminder.due_time, "Skipping missed reminder");
+                Vec::new()
+            }
+            (true, MissedPolicy::FireAll) => catch_up_times(&reminder, now),
+        };
+
+        for due_time in fire_times {
+            if let Err(e) = deliver_notification(&reminder, due_time) {
+                error!(reminder_id = %reminder.id, error = %e, "Failed to deliver reminder");
+                continue;
+            }
+
+            info!(
+                reminder_id = %reminder.id,
+                username = reminder.username.as_deref(),
+                due = %due_time,
+                missed,
+                "Notification fired"
+            );
+
+            if let Some(events) = events {
+                let event = FireEvent {
+                    id: reminder.id.clone(),
+                    username: reminder.username.clone(),
+                    fired_at: Utc::now(),
+                    was_recurring: reminder.recurrence.is_some(),
+                };
+                if let Err(e) = events.append(&event) {
+                    warn!(reminder_id = %reminder.id, path = %events.path().display(), error = %e, "Failed to write event log");
+                }
+            }
+        }
+
+        // Handle recurring reminders by updating the existing reminder
+        if let Some(next_time) = reminder.calculate_next_occurrence() {
+            match storage.reschedule_reminder(&reminder.id, next_time).await {
+                Ok(()) => info!(reminder_id = %reminder.id, next_due = %next_time, "Next occurrence scheduled"),
+                Err(e) => {
+                    error!(
+                        reminder_id = %reminder.id,
+                        path = %path,
+                        error = %e,
+                        "Failed to reschedule recurring reminder"
+                    );
+                    failure = Some(e);
+                }
+            }
+        } else if let Err(e) = storage.mark_as_sent(&reminder.id).await {
+            // No recurrence, so it only needed marking as sent
+            error!(reminder_id = %reminder.id, path = %path, error = %e, "Failed to mark reminder as sent");
+            failure = Some(e);
+        }
+    }
+
+    failure.map_or(Ok(()), Err)
+}
+
+/// The first `MAX_CATCH_UP_FIRES` occurrences a `fire_all` reminder missed.
+fn catch_up_times(reminder: &Reminder, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let mut missed = reminder.missed_occurrences(now);
+    let times: Vec<DateTime<Utc>> = missed.by_ref().take(MAX_CATCH_UP_FIRES).collect();
+    let dropped = missed.count();
+    if dropped > 0 {
+        warn!(reminder_id = %reminder.id, fired = times.len(), dropped, "Too many missed occurrences; dropping the rest");
+    }
+    times
+}
+
+/// When the blackout window or the reminder's quiet hours that `at` falls in
+/// end (the later of the two), or `None` if nothing holds a firing at `at`.
+fn held_until(reminder: &Reminder, blackout: Option<&QuietHours>, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let blackout_end = blackout
+        .filter(|_| !reminder.ignore_blackout)
+        .and_then(|window| window.window_end(at, &Local));
+    blackout_end.max(reminder.quiet_until(at))
+}
+
+/// Delivers a notification through each of the reminder's channels (stdout
+/// when it has none).
+fn deliver_notification(reminder: &Reminder, due_time: DateTime<Utc>) -> std::io::Result<()> {
+    if reminder.channels.is_empty() {
+        return print_notification(reminder, due_time);
+    }
+
+    for channel in &reminder.channels {
+        match channel.as_str() {
+            "stdout" => print_notification(reminder, due_time)?,
+            "log" => info!(
+                target: "notification",
+                reminder_id = %reminder.id,
+                username = reminder.username.as_deref(),
+                due = %due_time,
+                message = %reminder.message,
+                link = reminder.link.as_deref(),
+                "Reminder"
+            ),
+            other => {
+                // Only reachable through a hand-edited file; creates reject unknown channels
+                warn!(reminder_id = %reminder.id, channel = other, "Unknown notification channel, printing instead");
+                print_notification(reminder, due_time)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints a notification to stdout. Quiet reminders get a single line, plus
+/// one for the link if they have one.
+fn print_notification(reminder: &Reminder, due_time: DateTime<Utc>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut out = std::io::stdout().lock();
+    if reminder.quiet {
+        writeln!(out, "🔕 {} {}", due_time.format("%Y-%m-%d %H:%M:%S UTC"), reminder.message)?;
+        if let Some(ref link) = reminder.link {
+            writeln!(out, "   🔗 {}", link)?;
+        }
+        return out.flush();
+    }
+
+    let user_info = reminder
+        .username
+        .as_ref()
+        .map(|u| format!(" for {}", u))
+        .unwrap_or_default();
+
+    let recurrence_info = reminder
+        .recurrence
+        .as_ref()
+        .map(|r| format!(" (Recurring: {})", r))
+        .unwrap_or_default();
+
+    writeln!(out, "\n🔔 REMINDER{}{}: {}", user_info, recurrence_info, reminder.message)?;
+    writeln!(out, "   Due: {}", due_time.format("%Y-%m-%d %H:%M:%S UTC"))?;
+    if let Some(ref link) = reminder.link {
+        writeln!(out, "   Link: {}", link)?;
+    }
+    writeln!(out, "   ID: {}", reminder.id)?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn storage() -> (TempDir, Arc<ReminderStorage>) {
+        let dir = TempDir::new().unwrap();
+        let storage = ReminderStorage::open(dir.path().join("reminders.json")).unwrap();
+        (dir, Arc::new(storage))
+    }
+
+    fn reminder(due_in_secs: i64, recurrence: Option<&str>) -> Reminder {
+        Reminder::new(
+            "Stretch".to_string(),
+            Utc::now() + chrono::Duration::seconds(due_in_secs),
+            None,
+            recurrence.map(str::to_string),
+        )
+    }
+
+    #[tokio::test]
+    async fn process_due_marks_a_due_one_off_reminder_sent() {
+        let (_dir, storage) = storage();
+        let due = storage.add_reminder(reminder(-5, None)).await.unwrap();
+        let later = storage.add_reminder(reminder(3600, None)).await.unwrap();
+
+        process_due(&storage, None, None).await.unwrap();
+
+        assert!(storage.get_reminder(&due.id).await.unwrap().unwrap().sent);
+        assert!(!storage.get_reminder(&later.id).await.unwrap().unwrap().sent);
+    }
+
+    #[tokio::test]
+    async fn process_due_reschedules_a_due_recurring_reminder() {
+        let (_dir, storage) = storage();
+        let due = storage.add_reminder(reminder(-5, Some("daily"))).await.unwrap();
+
+        process_due(&storage, None, None).await.unwrap();
+
+        let stored = storage.get_reminder(&due.id).await.unwrap().unwrap();
+        assert!(!stored.sent);
+        assert_eq!(stored.due_time, due.due_time + chrono::Duration::days(1));
+    }
+
+    #[tokio::test]
+    async fn fire_all_catches_up_on_at_most_max_catch_up_fires() {
+        let (dir, storage) = storage();
+        let events = EventLog::new(dir.path().join("events.jsonl"), 1024 * 1024);
+        let mut week_old = reminder(-7 * 24 * 3600, Some("1"));
+        week_old.missed_policy = MissedPolicy::FireAll;
+        let week_old = storage.add_reminder(week_old).await.unwrap();
+
+        process_due(&storage, Some(&events), None).await.unwrap();
+
+        let fired = std::fs::read_to_string(events.path()).unwrap().lines().count();
+        assert_eq!(fired, MAX_CATCH_UP_FIRES);
+        let stored = storage.get_reminder(&week_old.id).await.unwrap().unwrap();
+        assert!(stored.due_time > Utc::now(), "the dropped occurrences are not retried");
+    }
+
+    #[test]
+    fn csv_round_trips_commas_quotes_and_line_breaks() {
+        let messages = ["plain", "milk, eggs", "say \"hi\"", "line one\nline two", "crlf\r\nend", ""];
+        let reminders: Vec<Reminder> = messages
+            .iter()
+            .map(|m| Reminder::new(m.to_string(), Utc::now(), Some("al,ice".to_string()), None))
+            .collect();
+
+        let csv = reminders_csv(&reminders);
+
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        assert_eq!(reader.headers().unwrap().iter().collect::<Vec<_>>(), CSV_COLUMNS);
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), reminders.len());
+        for (row, reminder) in rows.iter().zip(&reminders) {
+            assert_eq!(&row[0], reminder.id);
+            assert_eq!(&row[1], reminder.message);
+            assert_eq!(&row[2], reminder.due_time.to_rfc3339());
+            assert_eq!(&row[3], "al,ice");
+            assert_eq!(&row[5], "false");
+        }
+    }
+
+    #[test]
+    fn lead_offset_reaching_past_the_earliest_time_is_rejected() {
+        let mut early = reminder(0, None);
+        early.due_time = DateTime::<Utc>::MIN_UTC + chrono::Duration::days(1);
+
+        let error = build_lead_reminders(&early, &["36525d".to_string()], DateTime::<Utc>::MIN_UTC).unwrap_err();
+
+        assert!(matches!(error, ApiError::BadRequest(ref message) if message.contains("too far back")), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn storage_errors_map_to_status_and_code() {
+        let cases = [
+            (StorageError::NotFound("Reminder abc".to_string()), StatusCode::NOT_FOUND, "not_found"),
+            (StorageError::Conflict("Template 'daily' already exists".to_string()), StatusCode::CONFLICT, "conflict"),
+            (
+                StorageError::io("reminders.json", std::io::Error::from(std::io::ErrorKind::PermissionDenied)),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "storage_io",
+            ),
+        ];
+
+        for (error, status, code) in cases {
+            let message = error.to_string();
+            let response = ApiError::from(error).into_response();
+
+            assert_eq!(response.status(), status, "{}", message);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["code"], code);
+            assert_eq!(body["error"], message);
+        }
+    }
+
+    /// Collects what a subscriber writes so tests can inspect the events.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn events(&self) -> Vec<serde_json::Value> {
+            let logs = self.0.lock().unwrap();
+            String::from_utf8_lossy(&logs).lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn fired_notification_is_logged_with_structured_fields() {
+        let (_dir, storage) = storage();
+        let mut due = reminder(-5, None);
+        due.username = Some("alice".to_string());
+        due.channels = vec!["log".to_string()];
+        let due = storage.add_reminder(due).await.unwrap();
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt().json().with_writer(move || writer.clone()).finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        process_due(&storage, None, None).await.unwrap();
+
+        let events = logs.events();
+        let fired = events
+            .iter()
+            .find(|e| e["fields"]["message"] == "Notification fired")
+            .expect("no 'Notification fired' event");
+        assert_eq!(fired["level"], "INFO");
+        assert_eq!(fired["fields"]["reminder_id"], due.id.as_str());
+        assert_eq!(fired["fields"]["username"], "alice");
+        assert_eq!(fired["fields"]["missed"], false);
+        let delivered = events
+            .iter()
+            .find(|e| e["target"] == "notification")
+            .expect("no event on the notification target");
+        assert_eq!(delivered["fields"]["reminder_id"], due.id.as_str());
+    }
+
+    #[tokio::test]
+    async fn notification_loop_recovers_once_storage_works_again() {
+        let (dir, storage) = storage();
+        let due = storage.add_reminder(reminder(-5, None)).await.unwrap();
+        storage.fail_next_saves(2);
+        let health = Arc::new(NotifierHealth::default());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let notifier = tokio::spawn(notification_service(
+            storage.clone(),
+            Duration::from_millis(10),
+            None,
+            None,
+            health.clone(),
+            shutdown_rx,
+        ));
+
+        let recovered = async {
+            while health.report().last_success.is_none() {
+                sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), recovered).await.unwrap();
+        shutdown_tx.send(true).unwrap();
+        notifier.await.unwrap();
+
+        let report = health.report();
+        assert_eq!(report.status, "stopped");
+        assert_eq!(report.consecutive_failures, 0);
+        assert_eq!(report.last_error, None);
+        // The file caught up with the delivery made while saves were failing
+        let reopened = ReminderStorage::open(dir.path().join("reminders.json")).unwrap();
+        assert!(reopened.get_reminder(&due.id).await.unwrap().unwrap().sent);
+    }
+
+    #[tokio::test]
+    async fn failed_passes_degrade_health_until_a_clean_one() {
+        let (_dir, storage) = storage();
+        storage.add_reminder(reminder(-5, None)).await.unwrap();
+        storage.fail_next_saves(RELOAD_AFTER_FAILURES);
+        let health = NotifierHealth::default();
+
+        for _ in 0..RELOAD_AFTER_FAILURES {
+            health.record_failure(&storage.persist().await.unwrap_err());
+        }
+        let report = health.report();
+        assert_eq!(report.status, "degraded");
+        assert!(report.last_error.unwrap().contains("injected save failure"));
+
+        process_due(&storage, None, None).await.unwrap();
+        assert_eq!(health.record_success(), RELOAD_AFTER_FAILURES);
+        assert_eq!(health.report().status, "ok");
+    }
+
+    #[tokio::test]
+    async fn notification_loop_exits_when_shutdown_is_signalled() {
+        let (_dir, storage) = storage();
+        let health = Arc::new(NotifierHealth::default());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let notifier = tokio::spawn(notification_service(
+            storage,
+            Duration::from_millis(10),
+            None,
+            None,
+            health.clone(),
+            shutdown_rx,
+        ));
+
+        shutdown_tx.send(true).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), notifier).await.unwrap().unwrap();
+        assert!(health.is_stopped());
+    }
+}
@@ -1,40 +1,269 @@
-use crate::models::Reminder;
-use anyhow::{Context, Result};
-use chrono::Utc;
+use crate::ids;
+use crate::models::{parse_recurrence_rule, PurgeFilter, ReloadSummary, Reminder, ReminderChanges};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 use tokio::sync::RwLock;
+use tracing::{info, warn};
 
-const STORAGE_FILE: &str = "reminders.json";
+/// Where the server keeps reminders unless configured otherwise.
+pub const STORAGE_FILE: &str = "reminders.json";
+
+/// Why a storage operation failed. Shared by the reminder and user stores so
+/// callers can tell a missing record from a broken disk.
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    /// No record matches; holds what was looked up, e.g. "Reminder <id>"
+    #[error("{0} not found")]
+    NotFound(String),
+    /// The change clashes with what is already stored (duplicate name, held lock)
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    QuotaExceeded(String),
+    /// The data was rejected before anything was written
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Failed to access {}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+    /// A storage file exists but isn't valid JSON for what it should hold
+    #[error("{} is corrupt: {source}", .path.display())]
+    Corrupt { path: PathBuf, source: serde_json::Error },
+    /// Stored content could not be encrypted or decrypted with the configured key
+    #[error("{0}")]
+    Encryption(String),
+}
+
+impl StorageError {
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        StorageError::Io { path: path.into(), source }
+    }
+}
+
+/// Exclusive claim on a storage file. The server holds it while running and
+/// embedded clients hold it for the duration of a direct read or write, so the
+/// two never touch the file concurrently. Released on drop.
+pub struct StorageLock {
+    path: PathBuf,
+}
+
+impl StorageLock {
+    /// Locks the default storage file.
+    pub fn acquire() -> Result<Self, StorageError> {
+        Self::acquire_for(STORAGE_FILE)
+    }
+
+    /// Locks the storage file at `storage_path` via a `.lock` file beside it.
+    pub fn acquire_for(storage_path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = storage_path.as_ref().with_extension("lock");
+
+        for _ in 0..2 {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id()).map_err(|e| StorageError::io(&path, e))?;
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    let holder = fs::read_to_string(&path).unwrap_or_default();
+                    let holder = holder.trim();
+                    if process_is_alive(holder) {
+                        return Err(StorageError::Conflict(format!(
+                            "Reminder storage is locked by process {} ({})",
+                            holder,
+                            path.display()
+                        )));
+                    }
+                    // Left behind by a process that exited without cleaning up
+                    fs::remove_file(&path).map_err(|e| StorageError::io(&path, e))?;
+                }
+                Err(e) => return Err(StorageError::io(&path, e)),
+            }
+        }
+
+        Err(StorageError::Conflict(format!(
+            "Could not acquire the reminder storage lock ({})",
+            path.display()
+        )))
+    }
+}
+
+impl Drop for StorageLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Serializes data for a storage file: pretty-printed by default, compact when
+/// `REMINDER_PRETTY_JSON` is `0`, `false`, `off`, or `no`. Readers accept both.
+pub fn to_storage_json<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    let pretty = std::env::var("REMINDER_PRETTY_JSON")
+        .map(|v| !matches!(v.trim().to_lowercase().as_str(), "0" | "false" | "off" | "no"))
+        .unwrap_or(true);
+
+    if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    }
+}
+
+/// Loads a storage file, or `T::default()` if it doesn't exist yet.
+pub(crate) fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, StorageError> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let data = fs::read_to_string(path).map_err(|e| StorageError::io(path, e))?;
+    serde_json::from_str(&data).map_err(|source| StorageError::Corrupt {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+pub(crate) fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), StorageError> {
+    let json = to_storage_json(value).map_err(|e| StorageError::io(path, e.into()))?;
+    fs::write(path, json).map_err(|e| StorageError::io(path, e))
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn process_is_alive(pid: &str) -> bool {
+    match pid.parse::<u32>() {
+        Ok(pid) if cfg!(target_os = "linux") => Path::new(&format!("/proc/{}", pid)).exists(),
+        // No cheap liveness check elsewhere, so assume the holder is still running
+        Ok(_) => true,
+        Err(_) => false,
+    }
+}
 
 pub struct ReminderStorage {
     reminders: RwLock<Vec<Reminder>>,
+    path: PathBuf,
+    /// Modification time of the file as last read or written, to notice
+    /// edits made behind the server's back
+    modified: Mutex<Option<SystemTime>>,
+    /// How many of the next saves fail, for exercising error handling
+    #[cfg(test)]
+    failing_saves: std::sync::atomic::AtomicU32,
 }
 
 impl ReminderStorage {
-    pub fn new() -> Result<Self> {
-        let reminders = if Path::new(STORAGE_FILE).exists() {
-            let data = fs::read_to_string(STORAGE_FILE)
-                .context("Failed to read storage file")?;
-            serde_json::from_str(&data)
-                .context("Failed to parse storage file")?
-        } else {
-            Vec::new()
-        };
+    pub fn new() -> Result<Self, StorageError> {
+        Self::open(STORAGE_FILE)
+    }
+
+    /// Loads reminders from `path`, starting empty if the file doesn't exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, StorageError> {
+        let path = path.into();
+        let mut reminders: Vec<Reminder> = read_json(&path)?;
+
+        check_recurrences(&mut reminders, &path)?;
+        assign_owners(&mut reminders, &path)?;
 
         Ok(Self {
             reminders: RwLock::new(reminders),
+            modified: Mutex::new(modified_time(&path)),
+            path,
+            #[cfg(test)]
+            failing_saves: Default::default(),
         })
     }
 
-    pub async fn add_reminder(&self, reminder: Reminder) -> Result<Reminder> {
+    /// Writes the in-memory reminders to the file, for catching up after a
+    /// failed save left the file behind.
+    pub async fn persist(&self) -> Result<(), StorageError> {
+        let reminders = self.reminders.read().await;
+        self.save_to_disk(&reminders)
+    }
+
+    /// Replaces the in-memory reminders with the file's, for edits made while
+    /// the server runs. The file wins, except where that would deliver a
+    /// reminder again: if the file has a reminder as unsent and due but the
+    /// server has already delivered it (a copy edited from before the
+    /// delivery), its `sent`, `due_time`, and `snoozed_until` stay as the
+    /// server has them. Moving such a reminder into the future re-arms it.
+    pub async fn reload(&self) -> Result<ReloadSummary, StorageError> {
+        let mut reminders = self.reminders.write().await;
+
+        fs::metadata(&self.path).map_err(|e| StorageError::io(&self.path, e))?;
+        let mut loaded: Vec<Reminder> = read_json(&self.path)?;
+        check_recurrences(&mut loaded, &self.path)?;
+        assign_owners(&mut loaded, &self.path)?;
+
+        let now = Utc::now();
+        let mut summary = ReloadSummary::default();
+
+        for reminder in loaded.iter_mut() {
+            let Some(current) = reminders.iter().find(|r| r.id == reminder.id) else {
+                summary.added.push(reminder.id.clone());
+                continue;
+            };
+
+            let due_on_disk = !reminder.sent && reminder.due_time <= now;
+            let due_in_memory = !current.sent && current.due_time <= now;
+            if due_on_disk && !due_in_memory {
+                reminder.sent = current.sent;
+                reminder.due_time = current.due_time;
+                reminder.snoozed_until = current.snoozed_until;
+                summary.kept_delivery.push(reminder.id.clone());
+            }
+
+            if serde_json::to_value(&*reminder).ok() != serde_json::to_value(current).ok() {
+                summary.changed.push(reminder.id.clone());
+            }
+        }
+        summary.removed = reminders
+            .iter()
+            .filter(|r| !loaded.iter().any(|l| l.id == r.id))
+            .map(|r| r.id.clone())
+            .collect();
+
+        *reminders = loaded;
+        *self.modified.lock().unwrap_or_else(|e| e.into_inner()) = modified_time(&self.path);
+
+        info!(
+            path = %self.path.display(),
+            added = summary.added.len(),
+            removed = summary.removed.len(),
+            changed = summary.changed.len(),
+            "Reloaded reminders from disk"
+        );
+        if !summary.kept_delivery.is_empty() {
+            warn!(
+                reminders = %summary.kept_delivery.join(", "),
+                "Kept the delivery state of reminders the file lists as due but that were already delivered"
+            );
+        }
+
+        Ok(summary)
+    }
+
+    /// Makes the next `count` saves fail with an I/O error.
+    #[cfg(test)]
+    pub(crate) fn fail_next_saves(&self, count: u32) {
+        self.failing_saves.store(count, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// The file reminders are persisted to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub async fn add_reminder(&self, reminder: Reminder) -> Result<Reminder, StorageError> {
         let mut reminders = self.reminders.write().await;
         reminders.push(reminder.clone());
         self.save_to_disk(&reminders)?;
         Ok(reminder)
     }
 
-    pub async fn get_upcoming_reminders(&self) -> Result<Vec<Reminder>> {
+    pub async fn get_upcoming_reminders(&self) -> Result<Vec<Reminder>, StorageError> {
         let reminders = self.reminders.read().await;
         let now = Utc::now();
         
@@ -44,11 +273,18 @@ impl ReminderStorage {
             .cloned()
             .collect();
         
-        upcoming.sort_by(|a, b| a.due_time.cmp(&b.due_time));
+        upcoming.sort_by_key(|r| r.due_time);
+        Ok(upcoming)
+    }
+
+    /// `get_upcoming_reminders` limited to those `owner` owns.
+    pub async fn get_upcoming_reminders_owned_by(&self, owner: &str) -> Result<Vec<Reminder>, StorageError> {
+        let mut upcoming = self.get_upcoming_reminders().await?;
+        upcoming.retain(|r| r.owner.as_deref() == Some(owner));
         Ok(upcoming)
     }
 
-    pub async fn get_due_reminders(&self) -> Result<Vec<Reminder>> {
+    pub async fn get_due_reminders(&self) -> Result<Vec<Reminder>, StorageError> {
         let reminders = self.reminders.read().await;
         let now = Utc::now();
         
@@ -59,39 +295,452 @@ impl ReminderStorage {
             .collect())
     }
 
-    pub async fn mark_as_sent(&self, id: &str) -> Result<()> {
+    pub async fn mark_as_sent(&self, id: &str) -> Result<(), StorageError> {
         let mut reminders = self.reminders.write().await;
         
         if let Some(reminder) = reminders.iter_mut().find(|r| r.id == id) {
             reminder.sent = true;
+            reminder.snoozed_until = None;
             self.save_to_disk(&reminders)?;
         }
         
         Ok(())
     }
 
-    pub async fn reschedule_reminder(&self, id: &str, next_due_time: chrono::DateTime<Utc>) -> Result<()> {
+    pub async fn reschedule_reminder(&self, id: &str, next_due_time: DateTime<Utc>) -> Result<(), StorageError> {
         let mut reminders = self.reminders.write().await;
         
         if let Some(reminder) = reminders.iter_mut().find(|r| r.id == id) {
             reminder.due_time = next_due_time;
             reminder.sent = false;
+            reminder.snoozed_until = None;
             self.save_to_disk(&reminders)?;
         }
         
         Ok(())
     }
 
-    pub async fn get_all_reminders(&self) -> Result<Vec<Reminder>> {
+    pub async fn get_reminder(&self, id: &str) -> Result<Option<Reminder>, StorageError> {
+        let reminders = self.reminders.read().await;
+        Ok(reminders.iter().find(|r| r.id == id).cloned())
+    }
+
+    /// Applies the given changes and returns the updated reminder. Moving a sent
+    /// reminder into the future re-arms it.
+    pub async fn update_reminder(&self, id: &str, changes: ReminderChanges) -> Result<Reminder, StorageError> {
+        let mut reminders = self.reminders.write().await;
+
+        let reminder = reminders
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| StorageError::NotFound(format!("Reminder {}", id)))?;
+
+        if let Some(message) = changes.message {
+            reminder.message = message;
+        }
+        if let Some(due_time) = changes.due_time {
+            reminder.due_time = due_time;
+            if due_time > Utc::now() {
+                reminder.sent = false;
+            }
+        }
+        if let Some(recurrence) = changes.recurrence {
+            reminder.recurrence = Some(recurrence);
+        }
+        if let Some(title) = changes.title {
+            reminder.title = Some(title);
+        }
+        if let Some(description) = changes.description {
+            reminder.description = Some(description);
+        }
+        if let Some(quiet) = changes.quiet {
+            reminder.quiet = quiet;
+        }
+        reminder.updated_at = Some(Utc::now());
+
+        let updated = reminder.clone();
+        self.save_to_disk(&reminders)?;
+        Ok(updated)
+    }
+
+    /// Pushes a reminder back to `until` and re-arms it, returning the updated
+    /// reminder.
+    pub async fn snooze_reminder(&self, id: &str, until: DateTime<Utc>) -> Result<Reminder, StorageError> {
+        let mut reminders = self.reminders.write().await;
+
+        let reminder = reminders
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| StorageError::NotFound(format!("Reminder {}", id)))?;
+
+        reminder.due_time = until;
+        reminder.sent = false;
+        reminder.snoozed_until = Some(until);
+
+        let updated = reminder.clone();
+        self.save_to_disk(&reminders)?;
+        Ok(updated)
+    }
+
+    /// Marks a reminder as completed so it never fires again, recurring or not.
+    pub async fn complete_reminder(&self, id: &str) -> Result<Reminder, StorageError> {
+        let mut reminders = self.reminders.write().await;
+
+        let reminder = reminders
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| StorageError::NotFound(format!("Reminder {}", id)))?;
+
+        reminder.completed = true;
+        reminder.sent = true;
+        reminder.snoozed_until = None;
+
+        let updated = reminder.clone();
+        self.save_to_disk(&reminders)?;
+        Ok(updated)
+    }
+
+    /// Records who acknowledged a reminder and marks it sent. A reminder is
+    /// only acknowledged once; later calls return the original record as is.
+    pub async fn ack_reminder(&self, id: &str, by: &str, at: DateTime<Utc>) -> Result<Reminder, StorageError> {
+        let mut reminders = self.reminders.write().await;
+
+        let reminder = reminders
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| StorageError::NotFound(format!("Reminder {}", id)))?;
+
+        if reminder.acked_at.is_some() {
+            return Ok(reminder.clone());
+        }
+
+        reminder.acked_by = Some(by.to_string());
+        reminder.acked_at = Some(at);
+        reminder.sent = true;
+
+        let updated = reminder.clone();
+        self.save_to_disk(&reminders)?;
+        Ok(updated)
+    }
+
+    pub async fn delete_reminder(&self, id: &str) -> Result<(), StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let before = reminders.len();
+        reminders.retain(|r| r.id != id);
+
+        if reminders.len() == before {
+            return Err(StorageError::NotFound(format!("Reminder {}", id)));
+        }
+
+        self.save_to_disk(&reminders)
+    }
+
+    /// Removes every reminder `filter` matches in a single write and returns
+    /// them. With `dry_run` the matches are returned but kept.
+    pub async fn purge_reminders(&self, filter: &PurgeFilter, dry_run: bool) -> Result<Vec<Reminder>, StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let (purged, kept): (Vec<Reminder>, Vec<Reminder>) = reminders.iter().cloned().partition(|r| filter.matches(r));
+
+        if !dry_run && !purged.is_empty() {
+            self.save_to_disk(&kept)?;
+            *reminders = kept;
+        }
+
+        Ok(purged)
+    }
+
+    /// Gives every reminder matching `filter` to `to`, as both its username
+    /// and owner, returning the reassigned reminders.
+    pub async fn reassign_reminders(&self, filter: impl Fn(&Reminder) -> bool, to: &str) -> Result<Vec<Reminder>, StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let now = Utc::now();
+        let mut reassigned = Vec::new();
+
+        for reminder in reminders.iter_mut().filter(|r| filter(r)) {
+            reminder.username = Some(to.to_string());
+            reminder.owner = Some(to.to_string());
+            reminder.updated_at = Some(now);
+            reassigned.push(reminder.clone());
+        }
+
+        if !reassigned.is_empty() {
+            self.save_to_disk(&reminders)?;
+        }
+        Ok(reassigned)
+    }
+
+    /// Marks every reminder matching `filter` that isn't already completed as
+    /// completed, and returns them.
+    pub async fn complete_reminders(&self, filter: impl Fn(&Reminder) -> bool) -> Result<Vec<Reminder>, StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let mut completed = Vec::new();
+
+        for reminder in reminders.iter_mut().filter(|r| !r.completed && filter(r)) {
+            reminder.completed = true;
+            reminder.sent = true;
+            reminder.snoozed_until = None;
+            completed.push(reminder.clone());
+        }
+
+        if !completed.is_empty() {
+            self.save_to_disk(&reminders)?;
+        }
+        Ok(completed)
+    }
+
+    /// Deletes every reminder matching `filter` and returns them.
+    pub async fn delete_reminders(&self, filter: impl Fn(&Reminder) -> bool) -> Result<Vec<Reminder>, StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let (deleted, kept): (Vec<Reminder>, Vec<Reminder>) = reminders.iter().cloned().partition(|r| filter(r));
+
+        if !deleted.is_empty() {
+            self.save_to_disk(&kept)?;
+            *reminders = kept;
+        }
+        Ok(deleted)
+    }
+
+    /// Hands every reminder that belongs to `from` (by username or owner),
+    /// sent ones included, to `to`. Returns how many moved.
+    pub async fn transfer_owner(&self, from: &str, to: &str) -> Result<usize, StorageError> {
+        let moved = self
+            .reassign_reminders(|r| r.username.as_deref() == Some(from) || r.owner.as_deref() == Some(from), to)
+            .await?;
+        Ok(moved.len())
+    }
+
+    pub async fn get_all_reminders(&self) -> Result<Vec<Reminder>, StorageError> {
         let reminders = self.reminders.read().await;
         Ok(reminders.clone())
     }
 
-    fn save_to_disk(&self, reminders: &[Reminder]) -> Result<()> {
-        let json = serde_json::to_string_pretty(reminders)
-            .context("Failed to serialize reminders")?;
-        fs::write(STORAGE_FILE, json)
-            .context("Failed to write to storage file")?;
+    /// Adds previously exported reminders in a single write. When `preserve_ids` is
+    /// false every reminder gets a fresh id; otherwise reminders whose id already
+    /// exists are skipped and their ids returned.
+    pub async fn import_reminders(&self, imported: Vec<Reminder>, preserve_ids: bool) -> Result<(usize, Vec<String>), StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let mut added = 0;
+        let mut skipped = Vec::new();
+
+        for mut reminder in imported {
+            if reminder.owner.is_none() {
+                reminder.owner = reminder.username.clone();
+            }
+            if !preserve_ids {
+                reminder.id = ids::next_id();
+            } else if reminders.iter().any(|r| r.id == reminder.id) {
+                skipped.push(reminder.id);
+                continue;
+            }
+            reminders.push(reminder);
+            added += 1;
+        }
+
+        if added > 0 {
+            self.save_to_disk(&reminders)?;
+        }
+
+        Ok((added, skipped))
+    }
+
+    /// Adds imported reminders in a single write. One with the `external_id`
+    /// and owner of a stored reminder updates that reminder instead; moving
+    /// it into the future re-arms it. Returns how many were added and updated.
+    pub async fn upsert_imported(&self, imported: Vec<Reminder>) -> Result<(usize, usize), StorageError> {
+        let mut reminders = self.reminders.write().await;
+        let now = Utc::now();
+        let (mut added, mut updated) = (0, 0);
+
+        for reminder in imported {
+            let existing = reminders.iter_mut().find(|r| {
+                r.external_id.is_some() && r.external_id == reminder.external_id && r.owner == reminder.owner
+            });
+
+            match existing {
+                Some(existing) => {
+                    existing.message = reminder.message;
+                    existing.description = reminder.description;
+                    existing.recurrence = reminder.recurrence;
+                    existing.due_time = reminder.due_time;
+                    if reminder.due_time > now {
+                        existing.sent = false;
+                    }
+                    updated += 1;
+                }
+                None => {
+                    reminders.push(reminder);
+                    added += 1;
+                }
+            }
+        }
+
+        if added + updated > 0 {
+            self.save_to_disk(&reminders)?;
+        }
+
+        Ok((added, updated))
+    }
+
+    /// Writes the reminders out, warning first if the file was changed by
+    /// something else since it was last read or written, as those changes
+    /// are about to be overwritten.
+    fn save_to_disk(&self, reminders: &[Reminder]) -> Result<(), StorageError> {
+        #[cfg(test)]
+        {
+            use std::sync::atomic::Ordering;
+            if self.failing_saves.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1)).is_ok() {
+                return Err(StorageError::io(&self.path, io::Error::other("injected save failure")));
+            }
+        }
+
+        let mut modified = self.modified.lock().unwrap_or_else(|e| e.into_inner());
+
+        let on_disk = modified_time(&self.path);
+        if on_disk.is_some() && on_disk != *modified {
+            warn!(
+                path = %self.path.display(),
+                "Reminders file was modified outside the server and is being overwritten; send SIGHUP or POST /admin/reload after editing it to keep your changes"
+            );
+        }
+
+        write_json(&self.path, reminders)?;
+        *modified = modified_time(&self.path);
         Ok(())
     }
 }
+
+/// Warns about stored recurrences the parser rejects (hand-edited, or from an
+/// older grammar), since those reminders would never reschedule. With
+/// `REMINDER_STRICT_RECURRENCE=1` the bad recurrences are cleared instead,
+/// turning those reminders into one-offs.
+fn check_recurrences(reminders: &mut [Reminder], path: &Path) -> Result<(), StorageError> {
+    let invalid: Vec<usize> = reminders
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.recurrence.as_deref().is_some_and(|rec| parse_recurrence_rule(rec).is_err()))
+        .map(|(index, _)| index)
+        .collect();
+
+    if invalid.is_empty() {
+        return Ok(());
+    }
+
+    let listed: Vec<String> = invalid
+        .iter()
+        .map(|&i| format!("{} ('{}')", reminders[i].id, reminders[i].recurrence.as_deref().unwrap_or_default()))
+        .collect();
+    let strict = std::env::var("REMINDER_STRICT_RECURRENCE").is_ok_and(|v| v == "1");
+
+    if !strict {
+        warn!(
+            path = %path.display(),
+            count = invalid.len(),
+            reminders = %listed.join(", "),
+            "Stored reminders have invalid recurrences and won't reschedule (set REMINDER_STRICT_RECURRENCE=1 to clear them)"
+        );
+        return Ok(());
+    }
+
+    for &i in &invalid {
+        reminders[i].recurrence = None;
+    }
+    write_json(path, reminders)?;
+    warn!(
+        path = %path.display(),
+        count = invalid.len(),
+        reminders = %listed.join(", "),
+        "Cleared invalid recurrences; these reminders are now one-offs"
+    );
+    Ok(())
+}
+
+/// Gives reminders stored before ownership existed their `username` as
+/// owner, saving if anything changed. Reminders without a username stay
+/// ownerless, which only matters once authentication is enabled.
+fn assign_owners(reminders: &mut [Reminder], path: &Path) -> Result<(), StorageError> {
+    let mut assigned = 0;
+    for reminder in reminders.iter_mut().filter(|r| r.owner.is_none() && r.username.is_some()) {
+        reminder.owner = reminder.username.clone();
+        assigned += 1;
+    }
+
+    if assigned > 0 {
+        write_json(path, reminders)?;
+        info!(path = %path.display(), count = assigned, "Assigned reminder owners from usernames");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn reminder(message: &str, due_in: Duration) -> Reminder {
+        Reminder::new(message.to_string(), Utc::now() + due_in, None, None)
+    }
+
+    /// Rewrites the file the way an editor would, behind the storage's back.
+    fn edit_file(path: &Path, edit: impl FnOnce(&mut Vec<Reminder>)) {
+        let mut reminders: Vec<Reminder> = read_json(path).unwrap();
+        edit(&mut reminders);
+        fs::write(path, serde_json::to_string_pretty(&reminders).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reload_picks_up_external_edits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(STORAGE_FILE);
+        let storage = ReminderStorage::open(&path).unwrap();
+        let kept = storage.add_reminder(reminder("Stretch", Duration::hours(1))).await.unwrap();
+        let edited = storage.add_reminder(reminder("Water plants", Duration::hours(2))).await.unwrap();
+        let deleted = storage.add_reminder(reminder("Old task", Duration::hours(3))).await.unwrap();
+        let added = reminder("Added by hand", Duration::hours(4));
+
+        edit_file(&path, |reminders| {
+            reminders.retain(|r| r.id != deleted.id);
+            reminders.iter_mut().find(|r| r.id == edited.id).unwrap().message = "Water all the plants".to_string();
+            reminders.push(added.clone());
+        });
+        let summary = storage.reload().await.unwrap();
+
+        assert_eq!(summary.added, [added.id.as_str()]);
+        assert_eq!(summary.removed, [deleted.id.as_str()]);
+        assert_eq!(summary.changed, [edited.id.as_str()]);
+        assert!(summary.kept_delivery.is_empty());
+        let messages: Vec<String> = storage.get_all_reminders().await.unwrap().into_iter().map(|r| r.message).collect();
+        assert_eq!(messages, ["Stretch", "Water all the plants", "Added by hand"]);
+        assert_eq!(storage.get_reminder(&kept.id).await.unwrap().unwrap().message, "Stretch");
+    }
+
+    #[tokio::test]
+    async fn reload_does_not_rearm_a_reminder_delivered_since_the_file_was_copied() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(STORAGE_FILE);
+        let storage = ReminderStorage::open(&path).unwrap();
+        let due = storage.add_reminder(reminder("Stretch", Duration::minutes(-1))).await.unwrap();
+        let stale_copy = fs::read_to_string(&path).unwrap();
+
+        storage.mark_as_sent(&due.id).await.unwrap();
+        fs::write(&path, stale_copy).unwrap();
+        let summary = storage.reload().await.unwrap();
+
+        assert_eq!(summary.kept_delivery, [due.id.as_str()]);
+        assert!(storage.get_reminder(&due.id).await.unwrap().unwrap().sent);
+        assert!(storage.get_due_reminders().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reload_leaves_memory_alone_when_the_file_is_corrupt() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(STORAGE_FILE);
+        let storage = ReminderStorage::open(&path).unwrap();
+        storage.add_reminder(reminder("Stretch", Duration::hours(1))).await.unwrap();
+
+        fs::write(&path, "[{ half an edit").unwrap();
+
+        assert!(matches!(storage.reload().await, Err(StorageError::Corrupt { .. })));
+        assert_eq!(storage.get_all_reminders().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::user_storage::{normalize_tag, validate_tag};
+use std::collections::HashMap;
+
+/// Fewest words a file needs before a summary says anything useful.
+pub const MIN_WORDS: usize = 20;
+
+/// Share of control or replacement characters above which content is
+/// treated as binary rather than text.
+const MAX_BINARY_RATIO: f64 = 0.05;
+
+/// Common English words that say nothing about what a text is about.
+const STOPWORDS: &[&str] = &[
+    "about", "above", "after", "again", "against", "all", "also", "and", "any", "are", "because", "been",
+    "before", "being", "below", "between", "both", "but", "can", "could", "did", "does", "doing", "down",
+    "during", "each", "else", "even", "every", "few", "for", "from", "further", "had", "has", "have",
+    "having", "her", "here", "hers", "herself", "him", "himself", "his", "how", "however", "into", "its",
+    "itself", "just", "may", "might", "more", "most", "much", "must", "nor", "not", "now", "off", "once",
+    "one", "only", "other", "our", "ours", "out", "over", "own", "same", "she", "should", "since", "some",
+    "such", "than", "that", "the", "their", "theirs", "them", "then", "there", "these", "they", "this",
+    "those", "through", "too", "under", "until", "upon", "use", "used", "very", "was", "way", "were",
+    "what", "when", "where", "which", "while", "who", "whom", "why", "will", "with", "within", "without",
+    "would", "yet", "you", "your", "yours",
+];
+
+/// A compact description of a text file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSummary {
+    /// First non-empty line, without Markdown heading markers
+    pub title: String,
+    /// Most frequent words that aren't stopwords, with their counts
+    pub key_terms: Vec<(String, usize)>,
+    /// Runs of non-blank lines separated by blank lines
+    pub sections: usize,
+    pub words: usize,
+}
+
+/// Summarizes `content` with up to `term_count` key terms, or `None` when it
+/// looks binary or has fewer than `MIN_WORDS` words.
+pub fn summarize(content: &str, term_count: usize) -> Option<FileSummary> {
+    if looks_binary(content) {
+        return None;
+    }
+
+    let words = words(content).count();
+    if words < MIN_WORDS {
+        return None;
+    }
+
+    let title = content
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())?
+        .to_string();
+
+    Some(FileSummary {
+        title,
+        key_terms: key_terms(content, term_count),
+        sections: count_sections(content),
+        words,
+    })
+}
+
+/// The `limit` most frequent words in `text`, lowercased, skipping stopwords,
+/// numbers, and words shorter than three letters. Ties are broken
+/// alphabetically so the result doesn't depend on hash order.
+pub fn key_terms(text: &str, limit: usize) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in words(text) {
+        let word = word.to_lowercase();
+        if word.chars().count() < 3 || word.chars().all(|c| c.is_numeric()) || STOPWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *counts.entry(word).or_insert(0) += 1;
+    }
+
+    let mut terms: Vec<(String, usize)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms.truncate(limit);
+    terms
+}
+
+/// A proposed tag for a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSuggestion {
+    pub tag: String,
+    /// How often the tag's words appear in the text
+    pub mentions: usize,
+    /// Whether the user already uses this tag on another file
+    pub existing: bool,
+}
+
+/// Up to `limit` tags for `text`, best first. Tags from `vocabulary` (the
+/// user's existing tags) whose words appear in the text come first, then
+/// `key_terms` that aren't already used; each group is ordered by mentions,
+/// then alphabetically. Tags in `current` are never suggested.
+pub fn suggest_tags(text: &str, vocabulary: &[String], current: &[String], limit: usize) -> Vec<TagSuggestion> {
+    let words: Vec<String> = words(text).map(str::to_lowercase).collect();
+    let current: Vec<String> = current.iter().map(|t| normalize_tag(t)).collect();
+
+    let mut vocabulary: Vec<String> = vocabulary.iter().map(|t| normalize_tag(t)).collect();
+    vocabulary.sort();
+    vocabulary.dedup();
+
+    let mut known: Vec<TagSuggestion> = vocabulary
+        .iter()
+        .filter(|tag| !current.contains(tag))
+        .map(|tag| TagSuggestion { tag: tag.clone(), mentions: count_phrase(&words, tag), existing: true })
+        .filter(|s| s.mentions > 0)
+        .collect();
+    known.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.tag.cmp(&b.tag)));
+
+    let fresh = key_terms(text, usize::MAX)
+        .into_iter()
+        .filter_map(|(term, mentions)| validate_tag(&term).ok().map(|tag| (tag, mentions)))
+        .filter(|(tag, _)| !current.contains(tag) && !vocabulary.contains(tag))
+        .map(|(tag, mentions)| TagSuggestion { tag, mentions, existing: false });
+
+    known.into_iter().chain(fresh).take(limit).collect()
+}
+
+/// Occurrences of `tag`'s words, in order, in `words`. `cell-bio` matches
+/// "cell bio".
+fn count_phrase(words: &[String], tag: &str) -> usize {
+    let parts: Vec<&str> = tag.split(['-', '_', '.']).filter(|p| !p.is_empty()).collect();
+    if parts.is_empty() {
+        return 0;
+    }
+    words
+        .windows(parts.len())
+        .filter(|window| window.iter().zip(&parts).all(|(w, p)| w == p))
+        .count()
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty())
+}
+
+fn count_sections(text: &str) -> usize {
+    let mut sections = 0;
+    let mut in_section = false;
+    for line in text.lines() {
+        let blank = line.trim().is_empty();
+        if !blank && !in_section {
+            sections += 1;
+        }
+        in_section = !blank;
+    }
+    sections
+}
+
+fn looks_binary(text: &str) -> bool {
+    let total = text.chars().count();
+    if total == 0 {
+        return false;
+    }
+    let odd = text
+        .chars()
+        .filter(|&c| c == '\u{FFFD}' || (c.is_control() && !matches!(c, '\n' | '\r' | '\t')))
+        .count();
+    odd as f64 / total as f64 > MAX_BINARY_RATIO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTES: &str = "Cell biology notes. Mitochondria and the cell membrane.
+Mitochondria produce energy; the membrane controls transport.
+Cell bio exam on Friday. Energy, energy.";
+
+    fn suggested(suggestions: &[TagSuggestion]) -> Vec<(&str, usize, bool)> {
+        suggestions.iter().map(|s| (s.tag.as_str(), s.mentions, s.existing)).collect()
+    }
+
+    #[test]
+    fn existing_tags_come_first_then_key_terms_by_mentions() {
+        let vocabulary = ["Cell Bio", "exam", "chemistry", "energy"].map(String::from);
+        let current = ["Exam".to_string()];
+
+        let suggestions = suggest_tags(NOTES, &vocabulary, &current, 5);
+
+        assert_eq!(
+            suggested(&suggestions),
+            [
+                ("energy", 3, true),
+                ("cell-bio", 1, true),
+                ("cell", 3, false),
+                ("membrane", 2, false),
+                ("mitochondria", 2, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_are_broken_alphabetically_and_the_limit_applies() {
+        let suggestions = suggest_tags(NOTES, &[], &[], 8);
+
+        let tags: Vec<&str> = suggestions.iter().map(|s| s.tag.as_str()).collect();
+        assert_eq!(tags, ["cell", "energy", "membrane", "mitochondria", "bio", "biology", "controls", "exam"]);
+        assert!(suggestions.iter().all(|s| !s.existing));
+        assert!(suggest_tags(NOTES, &[], &[], 0).is_empty());
+    }
+
+    #[test]
+    fn tags_already_on_the_file_are_never_suggested() {
+        let vocabulary = ["energy".to_string()];
+        let current = ["Energy".to_string(), "cell".to_string()];
+
+        let suggestions = suggest_tags(NOTES, &vocabulary, &current, usize::MAX);
+
+        assert!(suggestions.iter().all(|s| s.tag != "energy" && s.tag != "cell"));
+    }
+}
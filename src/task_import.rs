@@ -0,0 +1,872 @@
+use crate::due_time::{self, DueTimeRules};
+use crate::models::{parse_recurrence, parse_recurrence_rule, Reminder, TaskImportFormat, TaskImportRow, TaskImportStatus};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use csv::StringRecord;
+
+/// Date layouts Todoist writes for tasks with a fixed date, tried after the
+/// formats `parse_due_time` accepts.
+const CALENDAR_DATE_FORMATS: &[&str] = &["%b %d %Y", "%B %d %Y", "%d %b %Y", "%d %B %Y", "%m/%d/%Y", "%Y/%m/%d", "%d.%m.%Y"];
+
+/// Words that start a date relative to the export, which can't be pinned down.
+const RELATIVE_DATES: &[&str] = &["today", "tomorrow", "yesterday", "tonight", "next ", "this ", "in "];
+
+/// How exported tasks become reminders.
+pub struct TaskImportOptions {
+    pub include_completed: bool,
+    pub username: Option<String>,
+    /// Zone and hour for dates the export leaves them out of
+    pub rules: DueTimeRules,
+    /// How long before each task or event to remind
+    pub before: Duration,
+    pub now: DateTime<Utc>,
+}
+
+/// A row's report entry, with the reminder when it converted.
+pub type ConvertedTask = (TaskImportRow, Option<Reminder>);
+
+/// A task as read from an export, before it is converted.
+struct Task {
+    line: u64,
+    content: String,
+    description: Option<String>,
+    due: String,
+    timezone: Option<String>,
+    completed: bool,
+    /// The row type when it isn't a task (Todoist also exports sections and notes)
+    other_kind: Option<String>,
+}
+
+/// A VEVENT as read from an iCalendar file, before it is converted.
+struct Event {
+    line: u64,
+    uid: Option<String>,
+    summary: String,
+    description: Option<String>,
+    start: Option<Property>,
+    rrule: Option<String>,
+    /// Set on events that override one occurrence of a recurring event
+    recurrence_id: bool,
+    cancelled: bool,
+}
+
+/// An iCalendar content line, `NAME;PARAM=value:value`.
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim_matches('"'))
+    }
+}
+
+/// Reads a task export or calendar and converts every task or event it can.
+/// Each row gets a report entry; only rows that converted come with a
+/// reminder. Fails only when the data isn't a `format` file at all.
+pub fn convert_tasks(format: TaskImportFormat, data: &str, options: &TaskImportOptions) -> Result<Vec<ConvertedTask>, String> {
+    let tasks = match format {
+        TaskImportFormat::TodoistCsv => read_todoist(data)?,
+        TaskImportFormat::GoogleTasksCsv => read_google_tasks(data)?,
+        TaskImportFormat::Ics => {
+            return Ok(read_ics(data)?.into_iter().map(|event| convert_event(event, options)).collect());
+        }
+    };
+
+    Ok(tasks.into_iter().map(|task| convert(format, task, options)).collect())
+}
+
+fn read_todoist(data: &str) -> Result<Vec<Task>, String> {
+    let (headers, records) = read_csv(data)?;
+    let content = column(&headers, "CONTENT").ok_or("Not a Todoist export: there is no CONTENT column")?;
+    let kind = column(&headers, "TYPE");
+    let description = column(&headers, "DESCRIPTION");
+    let date = column(&headers, "DATE");
+    let timezone = column(&headers, "TIMEZONE");
+    let completed = ["IS_COMPLETED", "COMPLETED", "CHECKED"].iter().find_map(|name| column(&headers, name));
+
+    Ok(records
+        .iter()
+        .map(|record| {
+            let kind = field(record, kind).to_lowercase();
+            Task {
+                line: line_of(record),
+                content: field(record, Some(content)),
+                description: non_empty(field(record, description)),
+                due: field(record, date),
+                timezone: non_empty(field(record, timezone)),
+                completed: is_truthy(&field(record, completed)),
+                other_kind: (!kind.is_empty() && kind != "task").then_some(kind),
+            }
+        })
+        .collect())
+}
+
+fn read_google_tasks(data: &str) -> Result<Vec<Task>, String> {
+    let (headers, records) = read_csv(data)?;
+    let title = column(&headers, "Title").ok_or("Not a Google Tasks export: there is no Title column")?;
+    let notes = column(&headers, "Notes");
+    let due = column(&headers, "Due");
+    let status = column(&headers, "Status");
+    let completed = column(&headers, "Completed");
+
+    Ok(records
+        .iter()
+        .map(|record| Task {
+            line: line_of(record),
+            content: field(record, Some(title)),
+            description: non_empty(field(record, notes)),
+            due: field(record, due),
+            timezone: None,
+            completed: field(record, status).eq_ignore_ascii_case("completed") || is_truthy(&field(record, completed)),
+            other_kind: None,
+        })
+        .collect())
+}
+
+/// The header and the non-blank rows.
+fn read_csv(data: &str) -> Result<(StringRecord, Vec<StringRecord>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data.trim_start_matches('\u{feff}').as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Failed to read the CSV header: {}", e))?
+        .clone();
+    let records = reader
+        .records()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Malformed CSV: {}", e))?;
+
+    Ok((headers, records.into_iter().filter(|r| r.iter().any(|f| !f.is_empty())).collect()))
+}
+
+fn column(headers: &StringRecord, name: &str) -> Option<usize> {
+    headers.iter().position(|h| h.eq_ignore_ascii_case(name))
+}
+
+fn field(record: &StringRecord, index: Option<usize>) -> String {
+    index.and_then(|i| record.get(i)).unwrap_or_default().to_string()
+}
+
+fn line_of(record: &StringRecord) -> u64 {
+    record.position().map(|p| p.line()).unwrap_or_default()
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// Any value other than empty, false, 0, or no (completion dates count).
+fn is_truthy(value: &str) -> bool {
+    !matches!(value.to_lowercase().as_str(), "" | "false" | "0" | "no")
+}
+
+fn convert(format: TaskImportFormat, task: Task, options: &TaskImportOptions) -> ConvertedTask {
+    let report = |status, reason: String| TaskImportRow {
+        line: task.line,
+        task: task.content.clone(),
+        status,
+        due_time: None,
+        recurrence: None,
+        reason: Some(reason),
+    };
+    let skipped = |reason: String| (report(TaskImportStatus::Skipped, reason), None);
+    let failed = |reason: String| (report(TaskImportStatus::Failed, reason), None);
+
+    if let Some(kind) = &task.other_kind {
+        return skipped(format!("not a task ({})", kind));
+    }
+    if task.completed && !options.include_completed {
+        return skipped("completed".to_string());
+    }
+    if task.content.is_empty() {
+        return failed("the task has no text".to_string());
+    }
+    if task.due.is_empty() {
+        return failed("the task has no due date".to_string());
+    }
+
+    let rules = match task.timezone.as_deref().map(str::parse::<Tz>) {
+        None => options.rules,
+        Some(Ok(tz)) => DueTimeRules { default_timezone: Some(tz), ..options.rules },
+        Some(Err(_)) => return failed(format!("unknown timezone '{}'", task.timezone.as_deref().unwrap_or_default())),
+    };
+
+    let parsed = match format {
+        TaskImportFormat::TodoistCsv => parse_todoist_due(&task.due, &rules, options.now + options.before),
+        TaskImportFormat::GoogleTasksCsv => parse_google_due(&task.due, &rules).map(|due| (due, None)),
+        TaskImportFormat::Ics => unreachable!("calendars are converted by convert_event"),
+    };
+    let (due_time, recurrence) = match parsed {
+        Ok((due, recurrence)) => match due.checked_sub_signed(options.before) {
+            Some(due_time) => (due_time, recurrence),
+            None => return failed(format!("due date '{}' is out of range", task.due)),
+        },
+        Err(reason) => return failed(reason),
+    };
+    if recurrence.is_none() && due_time <= options.now && !task.completed {
+        return skipped(format!("was due {}, which has passed", due_time.to_rfc3339()));
+    }
+
+    let row = TaskImportRow {
+        line: task.line,
+        task: task.content.clone(),
+        status: TaskImportStatus::Converted,
+        due_time: Some(due_time),
+        recurrence: recurrence.clone(),
+        reason: None,
+    };
+
+    let mut reminder = Reminder::new(task.content, due_time, options.username.clone(), recurrence);
+    reminder.description = task.description;
+    if task.completed {
+        reminder.completed = true;
+        reminder.sent = true;
+    }
+
+    (row, Some(reminder))
+}
+
+/// Todoist's DATE column: a calendar date with an optional time, or a
+/// recurring `every ...` string (`every!` repeats after completion).
+fn parse_todoist_due(due: &str, rules: &DueTimeRules, now: DateTime<Utc>) -> Result<(DateTime<Utc>, Option<String>), String> {
+    let lower = due.trim().to_lowercase();
+    let lower = [("daily", "every day"), ("weekly", "every week"), ("hourly", "every hour")]
+        .iter()
+        .find_map(|(word, every)| lower.strip_prefix(word).map(|rest| format!("{}{}", every, rest)))
+        .unwrap_or(lower);
+
+    let recurring = match lower.strip_prefix("every!") {
+        Some(pattern) => Some((true, pattern.trim())),
+        None => lower.strip_prefix("every ").map(|pattern| (false, pattern.trim())),
+    };
+    if let Some((after_completion, pattern)) = recurring {
+        return parse_todoist_recurrence(pattern, after_completion, rules, now).map(|(due, rule)| (due, Some(rule)));
+    }
+
+    if RELATIVE_DATES.iter().any(|word| lower.starts_with(word)) {
+        return Err(format!("relative date '{}' can't be converted; give the task a calendar date before exporting", due));
+    }
+    if let Ok(time) = due_time::parse_due_time(due, rules) {
+        return Ok((time, None));
+    }
+    parse_calendar_date(&lower, rules)
+        .map(|time| (time, None))
+        .ok_or_else(|| format!("due date '{}' is not a date Todoist exports (e.g., 2025-05-01, May 1 2025 14:00, every day at 9am)", due))
+}
+
+/// `day`, `other week`, `3 hours`, or `monday`, optionally followed by
+/// `at <time>`. The first reminder is the next matching time after `now`.
+fn parse_todoist_recurrence(
+    pattern: &str,
+    after_completion: bool,
+    rules: &DueTimeRules,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, String), String> {
+    let (interval, time) = match pattern.split_once(" at ") {
+        Some((interval, time)) => (interval.trim(), Some(time.trim())),
+        None => (pattern, None),
+    };
+
+    let weekday = interval.parse::<Weekday>().ok();
+    let rule = match weekday {
+        Some(_) => Some("weekly".to_string()),
+        None => recurrence_for(interval),
+    }
+    .ok_or_else(|| format!("'every {}' has no equivalent recurrence (supported: every day, every week, every weekday name, every N minutes/hours/days/weeks)", interval))?;
+    let rule = if after_completion { format!("after_complete:{}", rule) } else { rule };
+    parse_recurrence_rule(&rule)?;
+
+    let time = match time {
+        Some(time) => parse_clock(time).ok_or_else(|| format!("'{}' is not a time of day", time))?,
+        None => NaiveTime::from_hms_opt(rules.default_hour, 0, 0).ok_or("the default hour is not a valid hour")?,
+    };
+
+    let today = match rules.default_timezone {
+        Some(tz) => now.with_timezone(&tz).date_naive(),
+        None => now.date_naive(),
+    };
+    let mut date = today;
+    loop {
+        let on_weekday = weekday.is_none_or(|day| date.weekday() == day);
+        if on_weekday {
+            let candidate = due_time::in_default_zone(date.and_time(time), rules).map_err(|e| e.to_string())?;
+            if candidate > now {
+                return Ok((candidate, rule));
+            }
+        }
+        if date - today > Duration::weeks(1) {
+            return Err(format!("no upcoming time matches 'every {}'", pattern));
+        }
+        date = date.succ_opt().ok_or("date out of range")?;
+    }
+}
+
+/// Our recurrence for a Todoist interval, if there is one.
+fn recurrence_for(interval: &str) -> Option<String> {
+    match interval {
+        "day" => return Some("daily".to_string()),
+        "week" => return Some("weekly".to_string()),
+        "hour" => return Some("60 minutes".to_string()),
+        "other day" => return Some("2 days".to_string()),
+        "other week" => return Some("14 days".to_string()),
+        _ => {}
+    }
+
+    let (amount, unit) = interval.split_once(' ')?;
+    let amount: i64 = amount.parse().ok().filter(|n| *n > 0)?;
+    match unit.trim().trim_end_matches('s') {
+        "min" | "minute" => Some(format!("{} minutes", amount)),
+        "hour" => Some(format!("{} minutes", amount * 60)),
+        "day" => Some(format!("{} days", amount)),
+        "week" => Some(format!("{} days", amount * 7)),
+        _ => None,
+    }
+}
+
+/// `May 1 2025`, `1 May 2025 14:00`, `05/01/2025 9am`, and similar. A date
+/// alone falls at the rules' default hour.
+fn parse_calendar_date(input: &str, rules: &DueTimeRules) -> Option<DateTime<Utc>> {
+    let cleaned = input.replace(',', " ");
+    let words: Vec<&str> = cleaned.split_whitespace().collect();
+
+    for split in (1..=words.len()).rev() {
+        let date_part = words[..split].join(" ");
+        let Some(date) = CALENDAR_DATE_FORMATS
+            .iter()
+            .find_map(|fmt| NaiveDate::parse_from_str(&date_part, fmt).ok())
+        else {
+            continue;
+        };
+
+        let time_part = words[split..].join(" ");
+        let time_part = time_part.strip_prefix("at ").unwrap_or(&time_part);
+        let naive: NaiveDateTime = if time_part.is_empty() {
+            date.and_hms_opt(rules.default_hour, 0, 0)?
+        } else {
+            date.and_time(parse_clock(time_part)?)
+        };
+        return due_time::in_default_zone(naive, rules).ok();
+    }
+    None
+}
+
+/// `14:00`, `9am`, `9:30 pm`, or `noon`.
+fn parse_clock(input: &str) -> Option<NaiveTime> {
+    let mut compact = input.to_lowercase().replace(' ', "");
+    if compact == "noon" {
+        return NaiveTime::from_hms_opt(12, 0, 0);
+    }
+    // chrono needs the minutes, so `9am` becomes `9:00am`
+    if !compact.contains(':') && (compact.ends_with("am") || compact.ends_with("pm")) {
+        compact.insert_str(compact.len() - 2, ":00");
+    }
+
+    ["%H:%M", "%H:%M:%S", "%I:%M%p"]
+        .iter()
+        .find_map(|fmt| NaiveTime::parse_from_str(&compact, fmt).ok())
+}
+
+/// Google Tasks only stores a due date; exports write it as midnight UTC,
+/// so only the date is kept and placed at the rules' default hour.
+fn parse_google_due(due: &str, rules: &DueTimeRules) -> Result<DateTime<Utc>, String> {
+    let invalid = || format!("due date '{}' is not a date (expected e.g. 2025-05-01 or 2025-05-01T00:00:00.000Z)", due);
+
+    let date = due
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or_else(invalid)?;
+    let naive = date.and_hms_opt(rules.default_hour, 0, 0).ok_or_else(invalid)?;
+
+    due_time::in_default_zone(naive, rules).map_err(|e| e.to_string())
+}
+
+/// The VEVENTs of an iCalendar file. Properties of nested components such as
+/// VALARM are ignored.
+fn read_ics(data: &str) -> Result<Vec<Event>, String> {
+    let lines = unfold_ics(data);
+    if !lines.iter().any(|(_, line)| line.eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("Not an iCalendar file: there is no BEGIN:VCALENDAR".to_string());
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<Event> = None;
+    let mut nested = 0;
+
+    for (line_number, line) in lines {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        let name = property.name.to_uppercase();
+
+        let Some(event) = current.as_mut() else {
+            if name == "BEGIN" && property.value.eq_ignore_ascii_case("VEVENT") {
+                current = Some(Event {
+                    line: line_number,
+                    uid: None,
+                    summary: String::new(),
+                    description: None,
+                    start: None,
+                    rrule: None,
+                    recurrence_id: false,
+                    cancelled: false,
+                });
+            }
+            continue;
+        };
+
+        match name.as_str() {
+            "BEGIN" => nested += 1,
+            "END" if nested > 0 => nested -= 1,
+            "END" => events.extend(current.take()),
+            _ if nested > 0 => {}
+            "UID" => event.uid = non_empty(property.value.trim().to_string()),
+            "SUMMARY" => event.summary = unescape_text(&property.value),
+            "DESCRIPTION" => event.description = non_empty(unescape_text(&property.value)),
+            "RRULE" => event.rrule = Some(property.value.trim().to_string()),
+            "RECURRENCE-ID" => event.recurrence_id = true,
+            "STATUS" => event.cancelled = property.value.trim().eq_ignore_ascii_case("CANCELLED"),
+            "DTSTART" => event.start = Some(property),
+            _ => {}
+        }
+    }
+
+    Ok(events)
+}
+
+/// Joins folded lines (continuations start with a space or tab), keeping
+/// the line number each logical line starts on.
+fn unfold_ics(data: &str) -> Vec<(u64, String)> {
+    let mut lines: Vec<(u64, String)> = Vec::new();
+
+    for (index, line) in data.trim_start_matches('\u{feff}').split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, previous))) => previous.push_str(continuation),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((index as u64 + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// Splits a content line at the first `:` and `;` outside double quotes.
+fn parse_property(line: &str) -> Option<Property> {
+    let mut in_quotes = false;
+    let mut separators = Vec::new();
+    let mut colon = None;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => separators.push(i),
+            ':' if !in_quotes => {
+                colon = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let colon = colon?;
+    let mut bounds = separators;
+    bounds.push(colon);
+    let name = line[..bounds[0]].trim().to_string();
+    let params = bounds
+        .windows(2)
+        .filter_map(|pair| line[pair[0] + 1..pair[1]].split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Some(Property {
+        name,
+        params,
+        value: line[colon + 1..].to_string(),
+    })
+}
+
+/// Undoes iCalendar TEXT escaping (`\n`, `\,`, `\;`, `\\`).
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.trim().chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn convert_event(event: Event, options: &TaskImportOptions) -> ConvertedTask {
+    let report = |status, reason: String| TaskImportRow {
+        line: event.line,
+        task: event.summary.clone(),
+        status,
+        due_time: None,
+        recurrence: None,
+        reason: Some(reason),
+    };
+    let skipped = |reason: String| (report(TaskImportStatus::Skipped, reason), None);
+    let failed = |reason: String| (report(TaskImportStatus::Failed, reason), None);
+
+    if event.cancelled {
+        return skipped("cancelled".to_string());
+    }
+    if event.recurrence_id {
+        return skipped("changes a single occurrence of a recurring event, which isn't supported".to_string());
+    }
+    if event.summary.is_empty() {
+        return failed("the event has no SUMMARY".to_string());
+    }
+    let Some(start) = &event.start else {
+        return failed("the event has no DTSTART".to_string());
+    };
+
+    let (start, weekday) = match parse_ics_start(start, &options.rules) {
+        Ok(start) => start,
+        Err(reason) => return failed(reason),
+    };
+    let (recurrence, note) = match event.rrule.as_deref().map(|rrule| rrule_to_recurrence(rrule, weekday)) {
+        None => (None, None),
+        Some(Ok((recurrence, note))) => (Some(recurrence), note),
+        Some(Err(reason)) => return failed(reason),
+    };
+
+    let Some(mut due_time) = start.checked_sub_signed(options.before) else {
+        return failed(format!("start '{}' is out of range", start.to_rfc3339()));
+    };
+    match recurrence.as_deref().map(parse_recurrence) {
+        Some(Ok(interval)) if due_time <= options.now => {
+            // Start from the first occurrence still ahead
+            let step = interval.num_seconds().max(1);
+            let missed = (options.now - due_time).num_seconds() / step + 1;
+            due_time += Duration::seconds(step * missed);
+        }
+        Some(Err(reason)) => return failed(reason),
+        Some(Ok(_)) => {}
+        None if due_time <= options.now => {
+            return skipped(format!("was due {}, which has passed", due_time.to_rfc3339()));
+        }
+        None => {}
+    }
+
+    let row = TaskImportRow {
+        line: event.line,
+        task: event.summary.clone(),
+        status: TaskImportStatus::Converted,
+        due_time: Some(due_time),
+        recurrence: recurrence.clone(),
+        reason: note,
+    };
+
+    let mut reminder = Reminder::new(event.summary, due_time, options.username.clone(), recurrence);
+    reminder.description = event.description;
+    reminder.external_id = event.uid;
+
+    (row, Some(reminder))
+}
+
+/// DTSTART in UTC, plus the weekday it falls on in its own zone. Handles
+/// UTC (`...Z`), `TZID=` local times, floating times (read like other
+/// times without a zone), and all-day dates (at the default hour).
+fn parse_ics_start(start: &Property, rules: &DueTimeRules) -> Result<(DateTime<Utc>, Weekday), String> {
+    let value = start.value.trim();
+    let invalid = || format!("DTSTART '{}' is not an iCalendar date or date-time", value);
+
+    let rules = match start.param("TZID") {
+        Some(tzid) => {
+            let tz: Tz = tzid.parse().map_err(|_| format!("unknown TZID '{}' (expected an IANA name like Europe/Berlin)", tzid))?;
+            DueTimeRules { default_timezone: Some(tz), ..*rules }
+        }
+        None => *rules,
+    };
+
+    if start.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+        let naive = date.and_hms_opt(rules.default_hour, 0, 0).ok_or_else(invalid)?;
+        let due = due_time::in_default_zone(naive, &rules).map_err(|e| e.to_string())?;
+        return Ok((due, date.weekday()));
+    }
+
+    let (local, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(local) => (local, true),
+        None => (value, false),
+    };
+    let naive = NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+
+    let due = if utc {
+        naive.and_utc()
+    } else {
+        due_time::in_default_zone(naive, &rules).map_err(|e| e.to_string())?
+    };
+    Ok((due, naive.weekday()))
+}
+
+/// Our recurrence for an RRULE, with a note about anything dropped. Fails
+/// for rules we can't repeat on a fixed interval (monthly, several weekdays).
+fn rrule_to_recurrence(rrule: &str, start_weekday: Weekday) -> Result<(String, Option<String>), String> {
+    let unsupported = |why: String| format!("RRULE '{}' has no equivalent recurrence: {}", rrule, why);
+
+    let parts: Vec<(String, String)> = rrule
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.trim().to_uppercase(), value.trim().to_uppercase()))
+        .collect();
+    let get = |key: &str| parts.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+
+    let freq = get("FREQ").ok_or_else(|| unsupported("it has no FREQ".to_string()))?;
+    let interval: i64 = match get("INTERVAL") {
+        Some(interval) => interval.parse().ok().filter(|n| *n > 0).ok_or_else(|| unsupported(format!("INTERVAL={} is not a positive number", interval)))?,
+        None => 1,
+    };
+
+    for (key, value) in &parts {
+        match key.as_str() {
+            "FREQ" | "INTERVAL" | "COUNT" | "UNTIL" | "WKST" => {}
+            "BYDAY" if freq == "WEEKLY" => {
+                if value.contains(',') {
+                    return Err(unsupported("it repeats on several days of the week".to_string()));
+                }
+                if ics_weekday(value) != Some(start_weekday) {
+                    return Err(unsupported(format!("BYDAY={} is not the weekday of DTSTART", value)));
+                }
+            }
+            _ => return Err(unsupported(format!("{} is not supported", key))),
+        }
+    }
+
+    let recurrence = match freq {
+        "MINUTELY" => format!("{} minutes", interval),
+        "HOURLY" => format!("{} minutes", interval * 60),
+        "DAILY" if interval == 1 => "daily".to_string(),
+        "DAILY" => format!("{} days", interval),
+        "WEEKLY" if interval == 1 => "weekly".to_string(),
+        "WEEKLY" => format!("{} days", interval * 7),
+        other => return Err(unsupported(format!("FREQ={} is not supported", other))),
+    };
+
+    let note = ["UNTIL", "COUNT"]
+        .iter()
+        .find(|key| get(key).is_some())
+        .map(|key| format!("{} ignored; the reminder repeats until deleted", key));
+
+    Ok((recurrence, note))
+}
+
+fn ics_weekday(day: &str) -> Option<Weekday> {
+    match day {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TaskImportStatus::{Converted, Failed, Skipped};
+
+    fn utc(input: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(input).unwrap().with_timezone(&Utc)
+    }
+
+    fn options() -> TaskImportOptions {
+        TaskImportOptions {
+            include_completed: false,
+            username: Some("alice".to_string()),
+            rules: DueTimeRules::default(),
+            before: Duration::zero(),
+            now: utc("2030-04-01T10:00:00Z"),
+        }
+    }
+
+    /// (line, task, status) per row, for comparing whole reports at once.
+    fn statuses(converted: &[ConvertedTask]) -> Vec<(u64, &str, TaskImportStatus)> {
+        converted.iter().map(|(row, _)| (row.line, row.task.as_str(), row.status)).collect()
+    }
+
+    const TODOIST: &str = "\u{feff}TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,AUTHOR,RESPONSIBLE,DATE,DATE_LANG,TIMEZONE
+section,Exams,,,,,,,,
+task,\"Revise chapter 3, part 2\",Bring notes,4,1,,,2030-05-01 14:00,en,Europe/Berlin
+task,Water plants,,1,1,,,every day at 9am,en,
+task,Call dentist,,1,1,,,tomorrow,en,
+task,Old thing,,1,1,,,2020-01-01,en,
+task,No date,,1,1,,,,en,
+";
+
+    #[test]
+    fn todoist_export_converts_dated_and_recurring_tasks() {
+        let converted = convert_tasks(TaskImportFormat::TodoistCsv, TODOIST, &options()).unwrap();
+
+        assert_eq!(
+            statuses(&converted),
+            [
+                (2, "Exams", Skipped),
+                (3, "Revise chapter 3, part 2", Converted),
+                (4, "Water plants", Converted),
+                (5, "Call dentist", Failed),
+                (6, "Old thing", Skipped),
+                (7, "No date", Failed),
+            ]
+        );
+
+        let revise = converted[1].1.as_ref().unwrap();
+        assert_eq!(revise.due_time, utc("2030-05-01T12:00:00Z"));
+        assert_eq!(revise.description.as_deref(), Some("Bring notes"));
+        assert_eq!(revise.username.as_deref(), Some("alice"));
+
+        let plants = converted[2].1.as_ref().unwrap();
+        assert_eq!(plants.recurrence.as_deref(), Some("daily"));
+        assert_eq!(plants.due_time, utc("2030-04-02T09:00:00Z"));
+
+        assert!(converted[3].0.reason.as_deref().unwrap().contains("relative date"));
+    }
+
+    const GOOGLE_TASKS: &str = "Title,Notes,Due,Status,Completed
+Buy milk,Semi-skimmed,2030-04-10T00:00:00.000Z,needsAction,
+Send forms,,2030-04-11T00:00:00.000Z,completed,2030-03-30T08:00:00.000Z
+Bad date,,someday,needsAction,
+";
+
+    #[test]
+    fn google_tasks_export_keeps_the_date_at_the_default_hour() {
+        let converted = convert_tasks(TaskImportFormat::GoogleTasksCsv, GOOGLE_TASKS, &options()).unwrap();
+
+        assert_eq!(
+            statuses(&converted),
+            [(2, "Buy milk", Converted), (3, "Send forms", Skipped), (4, "Bad date", Failed)]
+        );
+        let milk = converted[0].1.as_ref().unwrap();
+        assert_eq!(milk.due_time, utc("2030-04-10T09:00:00Z"));
+        assert_eq!(milk.description.as_deref(), Some("Semi-skimmed"));
+    }
+
+    #[test]
+    fn completed_tasks_can_be_imported_as_done() {
+        let options = TaskImportOptions { include_completed: true, ..options() };
+
+        let converted = convert_tasks(TaskImportFormat::GoogleTasksCsv, GOOGLE_TASKS, &options).unwrap();
+
+        let forms = converted[1].1.as_ref().unwrap();
+        assert!(forms.completed && forms.sent);
+    }
+
+    #[test]
+    fn remind_before_moves_the_due_time_earlier() {
+        let options = TaskImportOptions { before: Duration::days(1), ..options() };
+
+        let converted = convert_tasks(TaskImportFormat::GoogleTasksCsv, GOOGLE_TASKS, &options).unwrap();
+
+        assert_eq!(converted[0].0.due_time, Some(utc("2030-04-09T09:00:00Z")));
+    }
+
+    #[test]
+    fn exports_without_the_key_column_are_rejected() {
+        assert!(convert_tasks(TaskImportFormat::TodoistCsv, GOOGLE_TASKS, &options()).is_err());
+        assert!(convert_tasks(TaskImportFormat::GoogleTasksCsv, TODOIST, &options()).is_err());
+    }
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:exam@example.com\r
+SUMMARY:Maths exam\\, room 4\r
+DTSTART;VALUE=DATE:20300415\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:study@example.com\r
+SUMMARY:Study group\r
+DESCRIPTION:Bring last week's\\n\r
+  notes\r
+DTSTART;TZID=Europe/Berlin:20300101T180000\r
+RRULE:FREQ=WEEKLY;BYDAY=TU;COUNT=10\r
+BEGIN:VALARM\r
+SUMMARY:Not the event\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Rent\r
+DTSTART:20300405T090000Z\r
+RRULE:FREQ=MONTHLY\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Called off\r
+DTSTART:20300420T100000Z\r
+STATUS:CANCELLED\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn calendar_all_day_event_falls_at_the_default_hour() {
+        let converted = convert_tasks(TaskImportFormat::Ics, CALENDAR, &options()).unwrap();
+
+        let (row, exam) = &converted[0];
+        assert_eq!((row.line, row.status), (3, Converted));
+        let exam = exam.as_ref().unwrap();
+        assert_eq!(exam.message, "Maths exam, room 4");
+        assert_eq!(exam.due_time, utc("2030-04-15T09:00:00Z"));
+        assert_eq!(exam.recurrence, None);
+        assert_eq!(exam.external_id.as_deref(), Some("exam@example.com"));
+    }
+
+    #[test]
+    fn calendar_weekly_event_starts_at_the_next_occurrence() {
+        let converted = convert_tasks(TaskImportFormat::Ics, CALENDAR, &options()).unwrap();
+
+        let (row, study) = &converted[1];
+        assert_eq!(row.status, Converted);
+        assert!(row.reason.as_deref().unwrap().starts_with("COUNT ignored"));
+        let study = study.as_ref().unwrap();
+        assert_eq!(study.message, "Study group");
+        assert_eq!(study.description.as_deref(), Some("Bring last week's\n notes"));
+        assert_eq!(study.recurrence.as_deref(), Some("weekly"));
+        // 18:00 in Berlin in January, stepped forward to the first Tuesday after `now`
+        assert_eq!(study.due_time, utc("2030-04-02T17:00:00Z"));
+    }
+
+    #[test]
+    fn calendar_rows_that_cannot_repeat_or_are_cancelled_are_reported() {
+        let converted = convert_tasks(TaskImportFormat::Ics, CALENDAR, &options()).unwrap();
+
+        assert_eq!(
+            statuses(&converted),
+            [(3, "Maths exam, room 4", Converted), (8, "Study group", Converted), (19, "Rent", Failed), (24, "Called off", Skipped)]
+        );
+        assert!(converted[2].0.reason.as_deref().unwrap().contains("FREQ=MONTHLY"));
+    }
+
+    #[test]
+    fn remind_before_past_the_earliest_time_fails_the_row() {
+        let options = TaskImportOptions { before: Duration::MAX, ..options() };
+
+        for (format, data) in [(TaskImportFormat::Ics, CALENDAR), (TaskImportFormat::GoogleTasksCsv, GOOGLE_TASKS)] {
+            let converted = convert_tasks(format, data, &options).unwrap();
+
+            let (row, reminder) = &converted[0];
+            assert_eq!(row.status, Failed, "{:?}", format);
+            assert!(row.reason.as_deref().unwrap().contains("out of range"), "{:?}", format);
+            assert!(reminder.is_none());
+        }
+    }
+}
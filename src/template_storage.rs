@@ -0,0 +1,75 @@
+use crate::models::ReminderTemplate;
+use crate::storage::{read_json, write_json, StorageError};
+use std::path::{Path, PathBuf};
+use tokio::sync::RwLock;
+
+const TEMPLATES_FILE: &str = "templates.json";
+
+pub struct TemplateStorage {
+    templates: RwLock<Vec<ReminderTemplate>>,
+    path: PathBuf,
+}
+
+impl TemplateStorage {
+    /// Opens the templates file that sits beside the reminder storage file.
+    pub fn open_beside(storage_path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        Self::open(storage_path.as_ref().with_file_name(TEMPLATES_FILE))
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, StorageError> {
+        let path = path.into();
+        let templates = read_json(&path)?;
+
+        Ok(Self {
+            templates: RwLock::new(templates),
+            path,
+        })
+    }
+
+    /// Templates owned by `username` (or the unowned ones when `None`), by name.
+    pub async fn list(&self, username: Option<&str>) -> Result<Vec<ReminderTemplate>, StorageError> {
+        let templates = self.templates.read().await;
+
+        let mut owned: Vec<ReminderTemplate> = templates
+            .iter()
+            .filter(|t| t.username.as_deref() == username)
+            .cloned()
+            .collect();
+        owned.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(owned)
+    }
+
+    pub async fn get(&self, name: &str, username: Option<&str>) -> Result<ReminderTemplate, StorageError> {
+        let templates = self.templates.read().await;
+
+        templates
+            .iter()
+            .find(|t| t.name == name && t.username.as_deref() == username)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(format!("Template '{}'", name)))
+    }
+
+    pub async fn add(&self, template: ReminderTemplate) -> Result<ReminderTemplate, StorageError> {
+        let mut templates = self.templates.write().await;
+
+        if templates.iter().any(|t| t.name == template.name && t.username == template.username) {
+            return Err(StorageError::Conflict(format!("A template named '{}' already exists", template.name)));
+        }
+
+        templates.push(template.clone());
+        write_json(&self.path, &*templates)?;
+        Ok(template)
+    }
+
+    pub async fn delete(&self, name: &str, username: Option<&str>) -> Result<(), StorageError> {
+        let mut templates = self.templates.write().await;
+        let before = templates.len();
+        templates.retain(|t| !(t.name == name && t.username.as_deref() == username));
+
+        if templates.len() == before {
+            return Err(StorageError::NotFound(format!("Template '{}'", name)));
+        }
+
+        write_json(&self.path, &*templates)
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::{bail, Context, Result};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Builds the HTTPS server config from PEM files. Each file is checked on its
+/// own first so a bad path or a key that doesn't belong to the certificate is
+/// reported as such, rather than as a generic handshake failure later.
+pub fn load_server_config(cert_path: &Path, key_path: &Path) -> Result<rustls::ServerConfig> {
+    let cert_pem = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read TLS certificate {}", cert_path.display()))?;
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("TLS certificate {} is not valid PEM", cert_path.display()))?;
+    if certs.is_empty() {
+        bail!("TLS certificate {} contains no certificates", cert_path.display());
+    }
+
+    let key_pem = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read TLS key {}", key_path.display()))?;
+    let key = PrivateKeyDer::from_pem_slice(&key_pem)
+        .with_context(|| format!("TLS key {} does not contain a PEM private key", key_path.display()))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .context("Failed to set up TLS")?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .with_context(|| {
+            format!(
+                "TLS key {} cannot be used with certificate {}",
+                key_path.display(),
+                cert_path.display()
+            )
+        })?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
+}
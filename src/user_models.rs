@@ -1,6 +1,6 @@
-use chrono::{DateTime, Utc};
+use crate::ids;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -8,15 +8,28 @@ pub struct User {
     pub username: String,
     pub password_hash: String,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub preferences: UserPreferences,
+}
+
+/// Defaults applied to new study notifications when the matching flag is omitted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserPreferences {
+    /// IANA timezone name used for due times given without an offset
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub default_recurrence: Option<String>,
 }
 
 impl User {
     pub fn new(username: String, password_hash: String) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: ids::next_id(),
             username,
             password_hash,
             created_at: Utc::now(),
+            preferences: UserPreferences::default(),
         }
     }
 }
@@ -30,17 +43,51 @@ pub struct UploadedFile {
     pub uploaded_at: DateTime<Utc>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl UploadedFile {
     pub fn new(user_id: String, filename: String, content: String) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: ids::next_id(),
             user_id,
             filename,
             content,
             uploaded_at: Utc::now(),
             tags: Vec::new(),
+            description: None,
+            archived: false,
+            starred: false,
+            updated_at: None,
         }
     }
 }
+
+/// Days a user studied, plus the daily nudge reminder that nags them if they don't.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StudyActivity {
+    /// Sorted, deduplicated days in the user's timezone
+    #[serde(default)]
+    pub days: Vec<NaiveDate>,
+    #[serde(default)]
+    pub nudge_reminder_id: Option<String>,
+}
+
+/// A set of changes applied to an uploaded file in one write. Fields left as
+/// `None` are untouched.
+#[derive(Debug, Clone, Default)]
+pub struct FileUpdate {
+    pub filename: Option<String>,
+    pub description: Option<String>,
+    pub archived: Option<bool>,
+    pub starred: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    pub content: Option<String>,
+}
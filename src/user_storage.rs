@@ -1,48 +1,128 @@
-use crate::user_models::{User, UploadedFile};
-use anyhow::{Context, Result, bail};
-use std::fs;
-use std::path::Path;
+use crate::encryption::{self, ContentCipher};
+use crate::storage::{read_json, write_json, StorageError};
+use crate::user_models::{FileUpdate, StudyActivity, User, UploadedFile, UserPreferences};
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::sync::RwLock;
 
 const USERS_FILE: &str = "users.json";
 const FILES_FILE: &str = "uploaded_files.json";
+const ACTIVITY_FILE: &str = "study_activity.json";
+const DEFAULT_QUOTA_MB: u64 = 20;
+const MAX_TAG_LENGTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagMatch {
+    /// File has at least one of the included tags
+    Any,
+    /// File has every included tag
+    All,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of a literal
+    pub regex: bool,
+    pub case_sensitive: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub file_id: String,
+    pub filename: String,
+    /// Byte ranges of each match within the file content
+    pub matches: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserStats {
+    pub file_count: usize,
+    pub bytes_used: u64,
+    pub quota_bytes: u64,
+}
 
 pub struct UserStorage {
     users: RwLock<Vec<User>>,
     files: RwLock<Vec<UploadedFile>>,
+    usage: RwLock<HashMap<String, u64>>,
+    activity: RwLock<HashMap<String, StudyActivity>>,
+    quota_bytes: u64,
+    /// Encrypts file content on disk when `QUIZ_ENCRYPTION_KEY` is set
+    cipher: Option<ContentCipher>,
+    /// Directory holding the users, files, and activity files
+    dir: PathBuf,
 }
 
 impl UserStorage {
-    pub fn new() -> Result<Self> {
-        let users = if Path::new(USERS_FILE).exists() {
-            let data = fs::read_to_string(USERS_FILE)
-                .context("Failed to read users file")?;
-            serde_json::from_str(&data)
-                .context("Failed to parse users file")?
-        } else {
-            Vec::new()
-        };
+    /// Loads the data files from the working directory, with the quota from
+    /// `QUIZ_USER_QUOTA_MB` and the key from `QUIZ_ENCRYPTION_KEY`.
+    pub fn new() -> Result<Self, StorageError> {
+        let quota_mb = std::env::var("QUIZ_USER_QUOTA_MB")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_QUOTA_MB);
 
-        let files = if Path::new(FILES_FILE).exists() {
-            let data = fs::read_to_string(FILES_FILE)
-                .context("Failed to read files file")?;
-            serde_json::from_str(&data)
-                .context("Failed to parse files file")?
-        } else {
-            Vec::new()
-        };
+        Self::open_in(".", quota_bytes(quota_mb), ContentCipher::from_env()?)
+    }
+
+    /// Loads the data files from `dir`, starting empty where they don't exist yet.
+    pub fn open_in(dir: impl Into<PathBuf>, quota_bytes: u64, cipher: Option<ContentCipher>) -> Result<Self, StorageError> {
+        let dir = dir.into();
+        let users: Vec<User> = read_json(&dir.join(USERS_FILE))?;
+        let mut files: Vec<UploadedFile> = read_json(&dir.join(FILES_FILE))?;
+        let activity: HashMap<String, StudyActivity> = read_json(&dir.join(ACTIVITY_FILE))?;
+
+        for file in &mut files {
+            match &cipher {
+                Some(cipher) => file.content = cipher.decrypt(&file.content)?,
+                None if encryption::is_encrypted(&file.content) => {
+                    return Err(StorageError::Encryption(format!(
+                        "{} holds encrypted files; set {} to load them",
+                        FILES_FILE,
+                        encryption::KEY_ENV
+                    )));
+                }
+                None => {}
+            }
+        }
+
+        // Tags written before normalization existed are normalized once here
+        // and saved below, merging any that collapse to the same tag
+        let mut tags_migrated = false;
+        for file in &mut files {
+            let normalized = dedup_tags(file.tags.iter().map(|t| normalize_tag(t)).filter(|t| !t.is_empty()));
+            if normalized != file.tags {
+                file.tags = normalized;
+                tags_migrated = true;
+            }
+        }
+
+        let mut usage: HashMap<String, u64> = HashMap::new();
+        for file in &files {
+            *usage.entry(file.user_id.clone()).or_insert(0) += file.content.len() as u64;
+        }
+
+        if tags_migrated {
+            write_files(&dir.join(FILES_FILE), cipher.as_ref(), &files)?;
+        }
 
         Ok(Self {
             users: RwLock::new(users),
             files: RwLock::new(files),
+            usage: RwLock::new(usage),
+            activity: RwLock::new(activity),
+            quota_bytes,
+            cipher,
+            dir,
         })
     }
 
-    pub async fn create_user(&self, user: User) -> Result<User> {
+    pub async fn create_user(&self, user: User) -> Result<User, StorageError> {
         let mut users = self.users.write().await;
         
         if users.iter().any(|u| u.username == user.username) {
-            bail!("Username already exists");
+            return Err(StorageError::Conflict(format!("Username '{}' already exists", user.username)));
         }
         
         users.push(user.clone());
@@ -50,69 +130,821 @@ impl UserStorage {
         Ok(user)
     }
 
-    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
+    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<User>, StorageError> {
         let users = self.users.read().await;
         Ok(users.iter().find(|u| u.username == username).cloned())
     }
 
-    pub async fn add_file(&self, file: UploadedFile) -> Result<UploadedFile> {
+    /// Changes a user's username. Names are compared case-insensitively so
+    /// `Alice` cannot be claimed while `alice` exists.
+    pub async fn rename_user(&self, user_id: &str, new_username: &str) -> Result<(), StorageError> {
+        let mut users = self.users.write().await;
+
+        if new_username.is_empty() {
+            return Err(StorageError::Invalid("Username cannot be empty".to_string()));
+        }
+        if users.iter().any(|u| u.id != user_id && u.username.eq_ignore_ascii_case(new_username)) {
+            return Err(StorageError::Conflict(format!("Username '{}' already exists", new_username)));
+        }
+
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == user_id)
+            .ok_or_else(|| StorageError::NotFound(format!("User {}", user_id)))?;
+
+        user.username = new_username.to_string();
+        self.save_users_to_disk(&users)?;
+        Ok(())
+    }
+
+    pub async fn set_preferences(&self, user_id: &str, preferences: UserPreferences) -> Result<UserPreferences, StorageError> {
+        let mut users = self.users.write().await;
+
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == user_id)
+            .ok_or_else(|| StorageError::NotFound(format!("User {}", user_id)))?;
+
+        user.preferences = preferences.clone();
+        self.save_users_to_disk(&users)?;
+        Ok(preferences)
+    }
+
+    pub async fn get_study_activity(&self, user_id: &str) -> Result<StudyActivity, StorageError> {
+        let activity = self.activity.read().await;
+        Ok(activity.get(user_id).cloned().unwrap_or_default())
+    }
+
+    /// Records that the user studied on `day`. Recording the same day twice is a no-op.
+    pub async fn record_study_day(&self, user_id: &str, day: NaiveDate) -> Result<(), StorageError> {
+        let mut activity = self.activity.write().await;
+        let entry = activity.entry(user_id.to_string()).or_default();
+
+        if let Err(pos) = entry.days.binary_search(&day) {
+            entry.days.insert(pos, day);
+            self.save_activity_to_disk(&activity)?;
+        }
+        Ok(())
+    }
+
+    pub async fn set_nudge_reminder(&self, user_id: &str, reminder_id: Option<String>) -> Result<(), StorageError> {
+        let mut activity = self.activity.write().await;
+        activity.entry(user_id.to_string()).or_default().nudge_reminder_id = reminder_id;
+        self.save_activity_to_disk(&activity)
+    }
+
+    pub async fn add_file(&self, mut file: UploadedFile) -> Result<UploadedFile, StorageError> {
+        file.tags = validate_tags(&file.tags)?;
         let mut files = self.files.write().await;
+        let mut usage = self.usage.write().await;
+
+        let incoming = file.content.len() as u64;
+        let current = usage.get(&file.user_id).copied().unwrap_or(0);
+        self.check_quota(current, incoming)?;
+
         files.push(file.clone());
         self.save_files_to_disk(&files)?;
+        usage.insert(file.user_id.clone(), current + incoming);
         Ok(file)
     }
 
-    pub async fn get_user_files(&self, user_id: &str) -> Result<Vec<UploadedFile>> {
+    pub async fn get_user_stats(&self, user_id: &str) -> Result<UserStats, StorageError> {
+        let files = self.files.read().await;
+        let usage = self.usage.read().await;
+
+        Ok(UserStats {
+            file_count: files.iter().filter(|f| f.user_id == user_id).count(),
+            bytes_used: usage.get(user_id).copied().unwrap_or(0),
+            quota_bytes: self.quota_bytes,
+        })
+    }
+
+    /// The user's files, oldest upload first (ties broken by id) so listings
+    /// don't depend on storage order.
+    pub async fn get_user_files(&self, user_id: &str) -> Result<Vec<UploadedFile>, StorageError> {
         let files = self.files.read().await;
-        Ok(files.iter().filter(|f| f.user_id == user_id).cloned().collect())
+        let mut owned: Vec<UploadedFile> = files.iter().filter(|f| f.user_id == user_id).cloned().collect();
+        sort_by_upload(&mut owned);
+        Ok(owned)
     }
 
-    pub async fn get_file_by_id(&self, file_id: &str, user_id: &str) -> Result<Option<UploadedFile>> {
+    pub async fn get_file_by_id(&self, file_id: &str, user_id: &str) -> Result<Option<UploadedFile>, StorageError> {
         let files = self.files.read().await;
         Ok(files.iter().find(|f| f.id == file_id && f.user_id == user_id).cloned())
     }
 
-    pub async fn add_tag_to_file(&self, file_id: &str, user_id: &str, tag: String) -> Result<()> {
+    /// Files owned by `user_id` tagged `tag`, oldest first.
+    pub async fn get_files_by_tag(&self, user_id: &str, tag: &str) -> Result<Vec<UploadedFile>, StorageError> {
+        let include = [normalize_tag(tag)];
+        let files = self.files.read().await;
+        Ok(files_with_tags(&files, user_id, &include, &[], TagMatch::Any))
+    }
+
+    /// Files owned by `user_id` matching `include` under `match_mode` and carrying
+    /// none of the `exclude` tags. An empty `include` matches every file.
+    pub async fn get_files_by_tags(
+        &self,
+        user_id: &str,
+        include: &[String],
+        exclude: &[String],
+        match_mode: TagMatch,
+    ) -> Result<Vec<UploadedFile>, StorageError> {
+        let include: Vec<String> = include.iter().map(|t| normalize_tag(t)).collect();
+        let exclude: Vec<String> = exclude.iter().map(|t| normalize_tag(t)).collect();
+        let files = self.files.read().await;
+        Ok(files_with_tags(&files, user_id, &include, &exclude, match_mode))
+    }
+
+    pub async fn search_files(&self, user_id: &str, query: &str, options: SearchOptions) -> Result<Vec<SearchResult>, StorageError> {
+        if query.is_empty() {
+            return Err(StorageError::Invalid("Search query cannot be empty".to_string()));
+        }
+
+        let pattern = if options.regex { query.to_string() } else { regex::escape(query) };
+        let matcher = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| StorageError::Invalid(format!("Invalid search pattern: {}", e)))?;
+
+        let files = self.files.read().await;
+
+        Ok(files
+            .iter()
+            .filter(|f| f.user_id == user_id)
+            .filter_map(|f| {
+                let matches: Vec<(usize, usize)> = matcher
+                    .find_iter(&f.content)
+                    .filter(|m| !m.is_empty())
+                    .map(|m| (m.start(), m.end()))
+                    .collect();
+
+                if matches.is_empty() {
+                    None
+                } else {
+                    Some(SearchResult {
+                        file_id: f.id.clone(),
+                        filename: f.filename.clone(),
+                        matches,
+                    })
+                }
+            })
+            .collect())
+    }
+
+    pub async fn add_tag_to_file(&self, file_id: &str, user_id: &str, tag: String) -> Result<(), StorageError> {
+        let tag = validate_tag(&tag)?;
         let mut files = self.files.write().await;
-        
+
         if let Some(file) = files.iter_mut().find(|f| f.id == file_id && f.user_id == user_id) {
             if !file.tags.contains(&tag) {
                 file.tags.push(tag);
                 self.save_files_to_disk(&files)?;
             }
         } else {
-            bail!("File not found");
+            return Err(StorageError::NotFound(format!("File {}", file_id)));
         }
         
         Ok(())
     }
 
-    pub async fn remove_tag_from_file(&self, file_id: &str, user_id: &str, tag: &str) -> Result<()> {
+    pub async fn remove_tag_from_file(&self, file_id: &str, user_id: &str, tag: &str) -> Result<(), StorageError> {
         let mut files = self.files.write().await;
         
         if let Some(file) = files.iter_mut().find(|f| f.id == file_id && f.user_id == user_id) {
-            file.tags.retain(|t| t != tag);
+            let tag = normalize_tag(tag);
+            file.tags.retain(|t| normalize_tag(t) != tag);
             self.save_files_to_disk(&files)?;
         } else {
-            bail!("File not found");
+            return Err(StorageError::NotFound(format!("File {}", file_id)));
         }
         
         Ok(())
     }
 
-    fn save_users_to_disk(&self, users: &[User]) -> Result<()> {
-        let json = serde_json::to_string_pretty(users)
-            .context("Failed to serialize users")?;
-        fs::write(USERS_FILE, json)
-            .context("Failed to write to users file")?;
-        Ok(())
+    pub async fn update_file(&self, file_id: &str, user_id: &str, changes: FileUpdate) -> Result<UploadedFile, StorageError> {
+        // Everything is checked before the first field changes, so a bad
+        // update leaves the file as it was
+        let tags = changes.tags.as_deref().map(validate_tags).transpose()?;
+
+        let mut files = self.files.write().await;
+        let mut usage = self.usage.write().await;
+
+        let index = files
+            .iter()
+            .position(|f| f.id == file_id && f.user_id == user_id)
+            .ok_or_else(|| StorageError::NotFound(format!("File {}", file_id)))?;
+
+        if let Some(ref filename) = changes.filename {
+            if filename.trim().is_empty() {
+                return Err(StorageError::Invalid("Filename cannot be empty".to_string()));
+            }
+            if files.iter().any(|f| f.user_id == user_id && f.id != file_id && &f.filename == filename) {
+                return Err(StorageError::Conflict(format!("A file named '{}' already exists", filename)));
+            }
+        }
+
+        let mut new_usage = None;
+        if let Some(ref content) = changes.content {
+            if content.trim().is_empty() {
+                return Err(StorageError::Invalid("File content cannot be empty".to_string()));
+            }
+            let current = usage.get(user_id).copied().unwrap_or(0);
+            let without_old = current.saturating_sub(files[index].content.len() as u64);
+            self.check_quota(without_old, content.len() as u64)?;
+            new_usage = Some(without_old + content.len() as u64);
+        }
+
+        let file = &mut files[index];
+        if let Some(filename) = changes.filename {
+            file.filename = filename;
+        }
+        if let Some(description) = changes.description {
+            file.description = Some(description);
+        }
+        if let Some(archived) = changes.archived {
+            file.archived = archived;
+        }
+        if let Some(starred) = changes.starred {
+            file.starred = starred;
+        }
+        if let Some(tags) = tags {
+            file.tags = tags;
+        }
+        if let Some(content) = changes.content {
+            file.content = content;
+        }
+        file.updated_at = Some(Utc::now());
+        let updated = file.clone();
+
+        self.save_files_to_disk(&files)?;
+        if let Some(bytes) = new_usage {
+            usage.insert(user_id.to_string(), bytes);
+        }
+
+        Ok(updated)
     }
 
-    fn save_files_to_disk(&self, files: &[UploadedFile]) -> Result<()> {
-        let json = serde_json::to_string_pretty(files)
-            .context("Failed to serialize files")?;
-        fs::write(FILES_FILE, json)
-            .context("Failed to write to files file")?;
+    /// Deletes the user's files with the given ids in one write. Returns the
+    /// deleted files; ids that don't match one of the user's files are skipped.
+    pub async fn delete_files(&self, user_id: &str, file_ids: &[String]) -> Result<Vec<UploadedFile>, StorageError> {
+        let mut files = self.files.write().await;
+        let mut usage = self.usage.write().await;
+
+        let (deleted, kept): (Vec<UploadedFile>, Vec<UploadedFile>) = files
+            .iter()
+            .cloned()
+            .partition(|f| f.user_id == user_id && file_ids.contains(&f.id));
+        if deleted.is_empty() {
+            return Ok(deleted);
+        }
+
+        self.save_files_to_disk(&kept)?;
+        *files = kept;
+
+        let freed: u64 = deleted.iter().map(|f| f.content.len() as u64).sum();
+        let current = usage.get(user_id).copied().unwrap_or(0);
+        usage.insert(user_id.to_string(), current.saturating_sub(freed));
+        Ok(deleted)
+    }
+
+    fn check_quota(&self, current: u64, incoming: u64) -> Result<(), StorageError> {
+        if current + incoming > self.quota_bytes {
+            return Err(StorageError::QuotaExceeded(format!(
+                "Storage quota exceeded: {} used + {} incoming exceeds the {} limit",
+                format_bytes(current),
+                format_bytes(incoming),
+                format_bytes(self.quota_bytes)
+            )));
+        }
         Ok(())
     }
+
+    fn save_users_to_disk(&self, users: &[User]) -> Result<(), StorageError> {
+        write_json(&self.dir.join(USERS_FILE), users)
+    }
+
+    fn save_activity_to_disk(&self, activity: &HashMap<String, StudyActivity>) -> Result<(), StorageError> {
+        write_json(&self.dir.join(ACTIVITY_FILE), activity)
+    }
+
+    fn save_files_to_disk(&self, files: &[UploadedFile]) -> Result<(), StorageError> {
+        write_files(&self.dir.join(FILES_FILE), self.cipher.as_ref(), files)
+    }
+}
+
+/// Metadata stays readable; only `content` is encrypted.
+fn write_files(path: &Path, cipher: Option<&ContentCipher>, files: &[UploadedFile]) -> Result<(), StorageError> {
+    let Some(cipher) = cipher else {
+        return write_json(path, files);
+    };
+
+    let sealed = files
+        .iter()
+        .map(|file| {
+            Ok(UploadedFile {
+                content: cipher.encrypt(&file.content)?,
+                ..file.clone()
+            })
+        })
+        .collect::<Result<Vec<_>, StorageError>>()?;
+    write_json(path, &sealed)
+}
+
+/// Clones of the files owned by `user_id` that match `include` under
+/// `match_mode` and carry none of `exclude`, oldest first. Both tag lists must
+/// already be normalized; an empty `include` matches every file. Called with
+/// the files lock held so only matching files are cloned.
+fn files_with_tags(
+    files: &[UploadedFile],
+    user_id: &str,
+    include: &[String],
+    exclude: &[String],
+    match_mode: TagMatch,
+) -> Vec<UploadedFile> {
+    let mut matched: Vec<UploadedFile> = files
+        .iter()
+        .filter(|f| f.user_id == user_id)
+        .filter(|f| {
+            let tags: Vec<String> = f.tags.iter().map(|t| normalize_tag(t)).collect();
+            let included = include.is_empty()
+                || match match_mode {
+                    TagMatch::Any => include.iter().any(|t| tags.contains(t)),
+                    TagMatch::All => include.iter().all(|t| tags.contains(t)),
+                };
+            included && !exclude.iter().any(|t| tags.contains(t))
+        })
+        .cloned()
+        .collect();
+    sort_by_upload(&mut matched);
+    matched
+}
+
+/// Oldest upload first, ties broken by id so the order is stable.
+fn sort_by_upload(files: &mut [UploadedFile]) {
+    files.sort_by(|a, b| a.uploaded_at.cmp(&b.uploaded_at).then_with(|| a.id.cmp(&b.id)));
+}
+
+/// Canonical form tags are stored and compared in: trimmed, lowercased, and
+/// with each run of inner whitespace turned into one dash, so `Cell  Bio`
+/// becomes `cell-bio`.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Normalizes `tag` and checks it is 1 to 40 characters of letters, digits,
+/// `-`, `_`, or `.`.
+pub fn validate_tag(tag: &str) -> Result<String, StorageError> {
+    let normalized = normalize_tag(tag);
+
+    if normalized.is_empty() {
+        return Err(StorageError::Invalid("Tag cannot be empty".to_string()));
+    }
+    let length = normalized.chars().count();
+    if length > MAX_TAG_LENGTH {
+        return Err(StorageError::Invalid(format!(
+            "Tag '{}' is {} characters long; tags can have at most {}",
+            normalized, length, MAX_TAG_LENGTH
+        )));
+    }
+    if let Some(c) = normalized.chars().find(|&c| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))) {
+        return Err(StorageError::Invalid(format!(
+            "Tag '{}' contains '{}'; tags may only use letters, digits, '-', '_', and '.'",
+            normalized, c
+        )));
+    }
+
+    Ok(normalized)
+}
+
+/// `validate_tag` for each tag, dropping duplicates.
+pub fn validate_tags(tags: &[String]) -> Result<Vec<String>, StorageError> {
+    let tags = tags.iter().map(|t| validate_tag(t)).collect::<Result<Vec<_>, _>>()?;
+    Ok(dedup_tags(tags))
+}
+
+/// Keeps the first of each tag, in order.
+fn dedup_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for tag in tags {
+        if !unique.contains(&tag) {
+            unique.push(tag);
+        }
+    }
+    unique
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    if mb >= 1.0 {
+        format!("{:.1} MB", mb)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+/// `quota_mb` in bytes; a quota too large to count in bytes is unlimited.
+fn quota_bytes(quota_mb: u64) -> u64 {
+    quota_mb.saturating_mul(1024 * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn storage(quota_bytes: u64) -> (TempDir, UserStorage) {
+        let dir = TempDir::new().unwrap();
+        let storage = UserStorage::open_in(dir.path(), quota_bytes, None).unwrap();
+        (dir, storage)
+    }
+
+    fn file(user_id: &str, filename: &str, content: &str) -> UploadedFile {
+        UploadedFile::new(user_id.to_string(), filename.to_string(), content.to_string())
+    }
+
+    #[tokio::test]
+    async fn user_files_come_back_oldest_first_whatever_the_insert_order() {
+        let _ids = crate::ids::install(crate::ids::SequentialIds::new("file"));
+        let (_dir, storage) = storage(1024);
+        let at = |minute: u32| Utc.with_ymd_and_hms(2030, 1, 1, 9, minute, 0).unwrap();
+        // Ids are handed out in this order: file-1 .. file-4
+        let uploads = [("late.txt", at(30)), ("tie-b.txt", at(10)), ("early.txt", at(0)), ("tie-a.txt", at(10))];
+        for (name, uploaded_at) in uploads {
+            let mut upload = file("u1", name, "x");
+            upload.uploaded_at = uploaded_at;
+            storage.add_file(upload).await.unwrap();
+        }
+        storage.add_file(file("u2", "other.txt", "x")).await.unwrap();
+
+        let names: Vec<String> = storage.get_user_files("u1").await.unwrap().into_iter().map(|f| f.filename).collect();
+
+        // Equal upload times fall back to id order, so tie-b (file-2) precedes tie-a (file-4)
+        assert_eq!(names, ["early.txt", "tie-b.txt", "tie-a.txt", "late.txt"]);
+    }
+
+    #[tokio::test]
+    async fn upload_filling_the_quota_exactly_is_accepted() {
+        let (_dir, storage) = storage(10);
+
+        storage.add_file(file("u1", "a.txt", "12345")).await.unwrap();
+        storage.add_file(file("u1", "b.txt", "67890")).await.unwrap();
+
+        let stats = storage.get_user_stats("u1").await.unwrap();
+        assert_eq!(stats.bytes_used, 10);
+        assert_eq!(stats.quota_bytes, 10);
+        assert_eq!(stats.file_count, 2);
+    }
+
+    #[tokio::test]
+    async fn upload_one_byte_over_the_quota_is_rejected() {
+        let (_dir, storage) = storage(10);
+        storage.add_file(file("u1", "a.txt", "12345")).await.unwrap();
+
+        let err = storage.add_file(file("u1", "b.txt", "678901")).await.unwrap_err();
+
+        assert!(matches!(err, StorageError::QuotaExceeded(_)), "{err:?}");
+        assert_eq!(storage.get_user_stats("u1").await.unwrap().bytes_used, 5);
+        assert_eq!(storage.get_user_files("u1").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn quota_is_per_user() {
+        let (_dir, storage) = storage(5);
+        storage.add_file(file("u1", "a.txt", "12345")).await.unwrap();
+
+        storage.add_file(file("u2", "a.txt", "12345")).await.unwrap();
+
+        assert_eq!(storage.get_user_stats("u2").await.unwrap().bytes_used, 5);
+    }
+
+    #[tokio::test]
+    async fn deleting_a_file_frees_its_bytes() {
+        let (_dir, storage) = storage(5);
+        let stored = storage.add_file(file("u1", "a.txt", "12345")).await.unwrap();
+
+        storage.delete_files("u1", &[stored.id]).await.unwrap();
+
+        assert_eq!(storage.get_user_stats("u1").await.unwrap().bytes_used, 0);
+        storage.add_file(file("u1", "b.txt", "67890")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn replacing_content_counts_only_the_new_size() {
+        let (_dir, storage) = storage(10);
+        let stored = storage.add_file(file("u1", "a.txt", "123456")).await.unwrap();
+
+        let changes = FileUpdate { content: Some("1234567890".to_string()), ..Default::default() };
+        storage.update_file(&stored.id, "u1", changes).await.unwrap();
+        assert_eq!(storage.get_user_stats("u1").await.unwrap().bytes_used, 10);
+
+        let changes = FileUpdate { content: Some("12345678901".to_string()), ..Default::default() };
+        let err = storage.update_file(&stored.id, "u1", changes).await.unwrap_err();
+        assert!(matches!(err, StorageError::QuotaExceeded(_)), "{err:?}");
+        assert_eq!(storage.get_user_stats("u1").await.unwrap().bytes_used, 10);
+    }
+
+    #[tokio::test]
+    async fn usage_is_recomputed_when_reopened() {
+        let (dir, storage) = storage(10);
+        storage.add_file(file("u1", "a.txt", "1234")).await.unwrap();
+        drop(storage);
+
+        let reopened = UserStorage::open_in(dir.path(), 10, None).unwrap();
+
+        assert_eq!(reopened.get_user_stats("u1").await.unwrap().bytes_used, 4);
+    }
+
+    #[tokio::test]
+    async fn update_file_applies_several_changes_at_once() {
+        let (_dir, storage) = storage(1024);
+        let stored = storage.add_file(file("u1", "a.txt", "old")).await.unwrap();
+
+        let changes = FileUpdate {
+            filename: Some("b.txt".to_string()),
+            description: Some("notes".to_string()),
+            archived: Some(true),
+            starred: Some(true),
+            tags: Some(vec!["Cell Bio".to_string()]),
+            content: Some("new content".to_string()),
+        };
+        let updated = storage.update_file(&stored.id, "u1", changes).await.unwrap();
+
+        assert_eq!(updated.filename, "b.txt");
+        assert_eq!(updated.description.as_deref(), Some("notes"));
+        assert!(updated.archived && updated.starred);
+        assert_eq!(updated.tags, vec!["cell-bio"]);
+        assert_eq!(updated.content, "new content");
+        assert!(updated.updated_at.is_some());
+
+        let fetched = storage.get_file_by_id(&stored.id, "u1").await.unwrap().unwrap();
+        assert_eq!(fetched.filename, "b.txt");
+        assert_eq!(fetched.content, "new content");
+    }
+
+    #[tokio::test]
+    async fn update_file_leaves_omitted_fields_alone() {
+        let (_dir, storage) = storage(1024);
+        let mut original = file("u1", "a.txt", "content");
+        original.description = Some("keep".to_string());
+        let stored = storage.add_file(original).await.unwrap();
+
+        let changes = FileUpdate { starred: Some(true), ..Default::default() };
+        let updated = storage.update_file(&stored.id, "u1", changes).await.unwrap();
+
+        assert!(updated.starred);
+        assert_eq!(updated.filename, "a.txt");
+        assert_eq!(updated.description.as_deref(), Some("keep"));
+        assert_eq!(updated.content, "content");
+    }
+
+    #[tokio::test]
+    async fn update_file_of_unknown_file_or_other_user_is_not_found() {
+        let (_dir, storage) = storage(1024);
+        let stored = storage.add_file(file("u1", "a.txt", "content")).await.unwrap();
+
+        let err = storage.update_file("missing", "u1", FileUpdate::default()).await.unwrap_err();
+        assert!(matches!(err, StorageError::NotFound(_)), "{err:?}");
+
+        let err = storage.update_file(&stored.id, "u2", FileUpdate::default()).await.unwrap_err();
+        assert!(matches!(err, StorageError::NotFound(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn update_file_rejects_a_name_the_user_already_has() {
+        let (_dir, storage) = storage(1024);
+        storage.add_file(file("u1", "a.txt", "one")).await.unwrap();
+        let second = storage.add_file(file("u1", "b.txt", "two")).await.unwrap();
+
+        let changes = FileUpdate { filename: Some("a.txt".to_string()), starred: Some(true), ..Default::default() };
+        let err = storage.update_file(&second.id, "u1", changes).await.unwrap_err();
+
+        assert!(matches!(err, StorageError::Conflict(_)), "{err:?}");
+        let unchanged = storage.get_file_by_id(&second.id, "u1").await.unwrap().unwrap();
+        assert_eq!(unchanged.filename, "b.txt");
+        assert!(!unchanged.starred);
+    }
+
+    #[test]
+    fn tags_are_normalized_and_deduplicated() {
+        assert_eq!(validate_tag("  Cell   Bio ").unwrap(), "cell-bio");
+        assert_eq!(validate_tag("ÉTUDES").unwrap(), "études");
+
+        let tags = ["Exam", "exam", " EXAM ", "cell bio", "Cell-Bio"].map(String::from);
+        assert_eq!(validate_tags(&tags).unwrap(), ["exam", "cell-bio"]);
+    }
+
+    #[test]
+    fn tags_that_are_blank_too_long_or_punctuated_are_rejected() {
+        let too_long = "a".repeat(MAX_TAG_LENGTH + 1);
+        for tag in ["", "   ", "\t\n", too_long.as_str(), "c++", "a/b", "#exam"] {
+            assert!(matches!(validate_tag(tag), Err(StorageError::Invalid(_))), "{:?}", tag);
+        }
+        assert!(validate_tag(&"a".repeat(MAX_TAG_LENGTH)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn adding_a_tag_merges_with_its_existing_spelling() {
+        let (_dir, storage) = storage(1024);
+        let stored = storage.add_file(file("u1", "a.txt", "content")).await.unwrap();
+
+        storage.add_tag_to_file(&stored.id, "u1", "Cell Bio".to_string()).await.unwrap();
+        storage.add_tag_to_file(&stored.id, "u1", "cell-bio".to_string()).await.unwrap();
+        storage.add_tag_to_file(&stored.id, "u1", "Exam".to_string()).await.unwrap();
+
+        let fetched = storage.get_file_by_id(&stored.id, "u1").await.unwrap().unwrap();
+        assert_eq!(fetched.tags, ["cell-bio", "exam"]);
+        let err = storage.add_tag_to_file(&stored.id, "u1", "  ".to_string()).await.unwrap_err();
+        assert!(matches!(err, StorageError::Invalid(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn update_file_with_a_bad_tag_changes_nothing() {
+        let (_dir, storage) = storage(1024);
+        let mut original = file("u1", "a.txt", "content");
+        original.tags = vec!["exam".to_string()];
+        let stored = storage.add_file(original).await.unwrap();
+
+        let changes = FileUpdate {
+            filename: Some("b.txt".to_string()),
+            starred: Some(true),
+            content: Some("new content".to_string()),
+            tags: Some(vec!["biology".to_string(), "   ".to_string()]),
+            ..Default::default()
+        };
+        let err = storage.update_file(&stored.id, "u1", changes).await.unwrap_err();
+
+        assert!(matches!(err, StorageError::Invalid(_)), "{err:?}");
+        let unchanged = storage.get_file_by_id(&stored.id, "u1").await.unwrap().unwrap();
+        assert_eq!(unchanged.filename, "a.txt");
+        assert!(!unchanged.starred);
+        assert_eq!(unchanged.content, "content");
+        assert_eq!(unchanged.tags, ["exam"]);
+        assert_eq!(storage.get_user_stats("u1").await.unwrap().bytes_used, 7);
+    }
+
+    /// u1 has `bio` (Biology, Exam), `chem` (chemistry, exam), and `notes`
+    /// (untagged); u2 has a `bio` file of its own.
+    async fn tagged_files() -> (TempDir, UserStorage) {
+        let (dir, storage) = storage(1024);
+        for (user_id, filename, tags) in [
+            ("u1", "bio", vec!["Biology", "Exam"]),
+            ("u1", "chem", vec!["chemistry", "exam"]),
+            ("u1", "notes", vec![]),
+            ("u2", "bio", vec!["biology"]),
+        ] {
+            let mut f = file(user_id, filename, "content");
+            f.tags = tags.into_iter().map(str::to_string).collect();
+            storage.add_file(f).await.unwrap();
+        }
+        (dir, storage)
+    }
+
+    async fn names_with_tags(storage: &UserStorage, include: &[&str], exclude: &[&str], match_mode: TagMatch) -> Vec<String> {
+        let include: Vec<String> = include.iter().map(|t| t.to_string()).collect();
+        let exclude: Vec<String> = exclude.iter().map(|t| t.to_string()).collect();
+        let mut names: Vec<String> = storage
+            .get_files_by_tags("u1", &include, &exclude, match_mode)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.filename)
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn tag_filter_any_matches_files_with_one_of_the_tags() {
+        let (_dir, storage) = tagged_files().await;
+        assert_eq!(names_with_tags(&storage, &["biology", "chemistry"], &[], TagMatch::Any).await, ["bio", "chem"]);
+    }
+
+    #[tokio::test]
+    async fn tag_filter_all_needs_every_tag() {
+        let (_dir, storage) = tagged_files().await;
+        assert_eq!(names_with_tags(&storage, &["exam", "biology"], &[], TagMatch::All).await, ["bio"]);
+        assert!(names_with_tags(&storage, &["biology", "chemistry"], &[], TagMatch::All).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tag_filter_exclusion_wins_over_inclusion() {
+        let (_dir, storage) = tagged_files().await;
+        assert_eq!(names_with_tags(&storage, &["exam"], &["chemistry"], TagMatch::Any).await, ["bio"]);
+    }
+
+    #[tokio::test]
+    async fn tag_filter_with_only_exclusions_keeps_untagged_files() {
+        let (_dir, storage) = tagged_files().await;
+        assert_eq!(names_with_tags(&storage, &[], &["exam"], TagMatch::All).await, ["notes"]);
+    }
+
+    #[tokio::test]
+    async fn tag_filter_ignores_case_and_spacing() {
+        let (_dir, storage) = tagged_files().await;
+        assert_eq!(names_with_tags(&storage, &["  EXAM "], &["BIOLOGY"], TagMatch::Any).await, ["chem"]);
+    }
+
+    #[tokio::test]
+    async fn single_tag_lookup_matches_the_multi_tag_filter() {
+        let (_dir, storage) = tagged_files().await;
+
+        let single: Vec<String> = storage.get_files_by_tag("u1", "Exam").await.unwrap().into_iter().map(|f| f.id).collect();
+        let multi: Vec<String> = storage
+            .get_files_by_tags("u1", &["exam".to_string()], &[], TagMatch::Any)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.id)
+            .collect();
+
+        assert_eq!(single.len(), 2);
+        assert_eq!(single, multi);
+    }
+
+    #[tokio::test]
+    async fn search_reports_every_match_in_a_file() {
+        let (_dir, storage) = storage(1024);
+        storage.add_file(file("u1", "a.txt", "cell wall, cell membrane, Cell")).await.unwrap();
+
+        let results = storage.search_files("u1", "cell", SearchOptions::default()).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].filename, "a.txt");
+        assert_eq!(results[0].matches, vec![(0, 4), (11, 15), (26, 30)]);
+    }
+
+    #[tokio::test]
+    async fn search_folds_case_beyond_ascii() {
+        let (_dir, storage) = storage(1024);
+        let content = "ÉCOLE and école; ΣΟΦΙΑ";
+        storage.add_file(file("u1", "a.txt", content)).await.unwrap();
+
+        let results = storage.search_files("u1", "école", SearchOptions::default()).await.unwrap();
+        let found: Vec<&str> = results[0].matches.iter().map(|&(start, end)| &content[start..end]).collect();
+        assert_eq!(found, ["ÉCOLE", "école"]);
+
+        let results = storage.search_files("u1", "σοφια", SearchOptions::default()).await.unwrap();
+        assert_eq!(results[0].matches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn case_sensitive_search_skips_other_cases() {
+        let (_dir, storage) = storage(1024);
+        storage.add_file(file("u1", "a.txt", "Cell cell")).await.unwrap();
+
+        let options = SearchOptions { case_sensitive: true, ..Default::default() };
+        let results = storage.search_files("u1", "Cell", options).await.unwrap();
+
+        assert_eq!(results[0].matches, vec![(0, 4)]);
+    }
+
+    #[tokio::test]
+    async fn search_treats_the_query_literally_unless_asked_for_a_regex() {
+        let (_dir, storage) = storage(1024);
+        storage.add_file(file("u1", "a.txt", "a+b and aab")).await.unwrap();
+
+        let literal = storage.search_files("u1", "a+b", SearchOptions::default()).await.unwrap();
+        assert_eq!(literal[0].matches, vec![(0, 3)]);
+
+        let options = SearchOptions { regex: true, ..Default::default() };
+        let regex = storage.search_files("u1", "a+b", options).await.unwrap();
+        assert_eq!(regex[0].matches, vec![(8, 11)]);
+    }
+
+    #[tokio::test]
+    async fn search_only_looks_at_the_users_files() {
+        let (_dir, storage) = storage(1024);
+        storage.add_file(file("u2", "a.txt", "cell")).await.unwrap();
+
+        assert!(storage.search_files("u1", "cell", SearchOptions::default()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_rejects_empty_and_invalid_queries() {
+        let (_dir, storage) = storage(1024);
+
+        let err = storage.search_files("u1", "", SearchOptions::default()).await.unwrap_err();
+        assert!(matches!(err, StorageError::Invalid(_)), "{err:?}");
+
+        let options = SearchOptions { regex: true, ..Default::default() };
+        let err = storage.search_files("u1", "(", options).await.unwrap_err();
+        assert!(matches!(err, StorageError::Invalid(_)), "{err:?}");
+    }
+
+    #[test]
+    fn bytes_are_formatted_in_binary_units() {
+        assert_eq!(format_bytes(512), "0.5 KB");
+        assert_eq!(format_bytes(14 * 1024 * 1024 + 210 * 1024), "14.2 MB");
+        assert_eq!(format_bytes(20 * 1024 * 1024), "20.0 MB");
+    }
+
+    #[test]
+    fn quota_too_large_to_count_in_bytes_saturates() {
+        assert_eq!(quota_bytes(20), 20 * 1024 * 1024);
+        assert_eq!(quota_bytes(u64::MAX), u64::MAX);
+    }
 }
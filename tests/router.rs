@@ -0,0 +1,471 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{
+        header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, LOCATION},
+        Method, Request, StatusCode,
+    },
+    Router,
+};
+use chrono::{DateTime, Duration, Utc};
+use reminder_service::auth::sign_token;
+use reminder_service::config::Config;
+use reminder_service::models::Reminder;
+use reminder_service::preference_storage::PreferenceStorage;
+use reminder_service::server::{build_router, notification_service, AppState};
+use reminder_service::storage::ReminderStorage;
+use reminder_service::template_storage::TemplateStorage;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+const SECRET: &str = "router-test-secret";
+
+fn app() -> (TempDir, Arc<AppState>, Router) {
+    app_with(&Config::default())
+}
+
+fn app_with(config: &Config) -> (TempDir, Arc<AppState>, Router) {
+    let dir = TempDir::new().unwrap();
+    let storage_path = dir.path().join("reminders.json");
+    let storage = Arc::new(ReminderStorage::open(&storage_path).unwrap());
+    let templates = Arc::new(TemplateStorage::open_beside(&storage_path).unwrap());
+    let preferences = Arc::new(PreferenceStorage::open_beside(&storage_path).unwrap());
+    let state = Arc::new(AppState::from_config(storage, templates, preferences, config));
+    let router = build_router(state.clone());
+    (dir, state, router)
+}
+
+async fn send(router: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    send_as(router, None, method, uri, body).await
+}
+
+/// `send` with a bearer token for `principal`, signed with `SECRET`.
+async fn send_as(router: &Router, principal: Option<&str>, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(principal) = principal {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", sign_token(principal, SECRET, Utc::now())));
+    }
+    let request = match body {
+        Some(body) => request.header(CONTENT_TYPE, "application/json").body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = if bytes.is_empty() { Value::Null } else { serde_json::from_slice(&bytes).unwrap() };
+    (status, body)
+}
+
+async fn create(router: &Router, body: Value) -> Value {
+    let (status, created) = send(router, Method::POST, "/reminders", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", created);
+    created
+}
+
+#[tokio::test]
+async fn created_reminder_is_listed() {
+    let (_dir, _state, router) = app();
+
+    let (status, created) = send(
+        &router,
+        Method::POST,
+        "/reminders",
+        Some(json!({ "message": "Water the plants", "due_time": "2099-01-01T09:00:00Z" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, listed) = send(&router, Method::GET, "/reminders", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let reminders = listed["reminders"].as_array().unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0]["id"], created["id"]);
+    assert_eq!(reminders[0]["message"], "Water the plants");
+}
+
+#[tokio::test]
+async fn body_limit_too_large_to_count_in_bytes_does_not_overflow() {
+    let mut config = Config::default();
+    config.limits.max_body_kb = usize::MAX;
+    let (_dir, _state, router) = app_with(&config);
+
+    create(&router, json!({ "message": "Water the plants", "due_time": "2099-01-01T09:00:00Z" })).await;
+}
+
+#[tokio::test]
+async fn invalid_create_requests_are_rejected_with_bad_request() {
+    let (_dir, _state, router) = app();
+
+    for body in [
+        json!({ "message": "", "due_time": "2099-01-01T09:00:00Z" }),
+        json!({ "message": "Stretch", "due_time": "someday" }),
+        json!({ "message": "Stretch", "due_time": "2099-01-01T09:00:00Z", "recurrence": "fortnightly-ish" }),
+        json!({ "message": "Stretch", "due_time": "2099-01-01T09:00:00Z", "color": "blue-ish" }),
+    ] {
+        let (status, error) = send(&router, Method::POST, "/reminders", Some(body.clone())).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        assert_eq!(error["code"], "bad_request", "{}", body);
+    }
+
+    let (_, listed) = send(&router, Method::GET, "/reminders", None).await;
+    assert!(listed["reminders"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn huge_recurrence_interval_is_a_bad_request() {
+    let (_dir, _state, router) = app();
+
+    let (status, error) = send(
+        &router,
+        Method::POST,
+        "/reminders",
+        Some(json!({ "message": "Stretch", "due_time": "2099-01-01T09:00:00Z", "recurrence": "9999999999999 days" })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "bad_request");
+}
+
+#[tokio::test]
+async fn one_liner_with_a_huge_offset_is_a_bad_request() {
+    let (_dir, _state, router) = app();
+
+    let (status, error) = send(
+        &router,
+        Method::POST,
+        "/reminders/parse",
+        Some(json!({ "text": "remind me to stretch in 999999999999999 minutes" })),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "bad_request");
+}
+
+#[tokio::test]
+async fn unknown_reminder_is_not_found() {
+    let (_dir, _state, router) = app();
+
+    let (status, error) = send(&router, Method::GET, "/reminders/does-not-exist", None).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(error["code"], "not_found");
+}
+
+#[tokio::test]
+async fn snooze_pushes_the_reminder_back() {
+    let (_dir, _state, router) = app();
+    let created = create(&router, json!({ "message": "Stretch", "due_time": "2099-01-01T09:00:00Z" })).await;
+    let uri = format!("/reminders/{}/snooze", created["id"].as_str().unwrap());
+
+    let before = Utc::now();
+    let (status, snoozed) = send(&router, Method::POST, &uri, Some(json!({ "minutes": 10 }))).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let until: DateTime<Utc> = snoozed["snoozed_until"].as_str().unwrap().parse().unwrap();
+    assert!(until >= before + Duration::minutes(10) && until <= Utc::now() + Duration::minutes(10));
+}
+
+#[tokio::test]
+async fn snooze_rejects_out_of_range_minutes() {
+    let (_dir, _state, router) = app();
+    let created = create(&router, json!({ "message": "Stretch", "due_time": "2099-01-01T09:00:00Z" })).await;
+    let uri = format!("/reminders/{}/snooze", created["id"].as_str().unwrap());
+
+    for minutes in [json!(0), json!(-5), json!(i64::MAX), json!(9_999_999_999_999_i64)] {
+        let (status, error) = send(&router, Method::POST, &uri, Some(json!({ "minutes": minutes }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", minutes);
+        assert_eq!(error["code"], "bad_request", "{}", minutes);
+    }
+
+    let (_, reminder) = send(&router, Method::GET, &format!("/reminders/{}", created["id"].as_str().unwrap()), None).await;
+    assert!(reminder["snoozed_until"].is_null());
+}
+
+fn stored(days_ago: i64, sent: bool, recurrence: Option<&str>) -> Reminder {
+    let mut reminder = Reminder::new(
+        format!("{} days ago", days_ago),
+        Utc::now() - Duration::days(days_ago),
+        None,
+        recurrence.map(str::to_string),
+    );
+    reminder.sent = sent;
+    reminder
+}
+
+#[tokio::test]
+async fn purge_only_removes_old_sent_one_off_reminders() {
+    let (_dir, state, router) = app();
+    let old_sent = state.storage.add_reminder(stored(30, true, None)).await.unwrap();
+    let old_pending = state.storage.add_reminder(stored(30, false, None)).await.unwrap();
+    let old_recurring = state.storage.add_reminder(stored(30, true, Some("daily"))).await.unwrap();
+    let recent_sent = state.storage.add_reminder(stored(1, true, None)).await.unwrap();
+
+    let request = json!({ "sent": true, "older_than_days": 7, "dry_run": true });
+    let (status, preview) = send(&router, Method::POST, "/reminders/purge", Some(request)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(preview["removed"], 1);
+    assert_eq!(state.storage.get_all_reminders().await.unwrap().len(), 4);
+
+    let request = json!({ "sent": true, "older_than_days": 7 });
+    let (status, purged) = send(&router, Method::POST, "/reminders/purge", Some(request)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(purged["removed"], 1);
+    assert_eq!(purged["reminders"][0]["id"], old_sent.id.as_str());
+
+    let mut kept: Vec<String> = state.storage.get_all_reminders().await.unwrap().into_iter().map(|r| r.id).collect();
+    let mut expected = vec![old_pending.id, old_recurring.id, recent_sent.id];
+    kept.sort();
+    expected.sort();
+    assert_eq!(kept, expected);
+}
+
+#[tokio::test]
+async fn purge_rejects_an_out_of_range_age() {
+    let (_dir, state, router) = app();
+    state.storage.add_reminder(stored(30, true, None)).await.unwrap();
+
+    let request = json!({ "sent": true, "older_than_days": u32::MAX });
+    let (status, error) = send(&router, Method::POST, "/reminders/purge", Some(request)).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "bad_request");
+    assert_eq!(state.storage.get_all_reminders().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn tags_survive_create_and_fetch() {
+    let (_dir, _state, router) = app();
+    let created = create(
+        &router,
+        json!({ "message": "Revise", "due_time": "2099-01-01T09:00:00Z", "tags": [" exam ", "", "math"] }),
+    )
+    .await;
+    assert_eq!(created["tags"], json!(["exam", "math"]));
+
+    let (status, fetched) = send(&router, Method::GET, &format!("/reminders/{}", created["id"].as_str().unwrap()), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["tags"], json!(["exam", "math"]));
+
+    create(&router, json!({ "message": "Untagged", "due_time": "2099-01-01T10:00:00Z" })).await;
+    let (_, listed) = send(&router, Method::GET, "/reminders?tag=exam", None).await;
+    let ids: Vec<&Value> = listed["reminders"].as_array().unwrap().iter().map(|r| &r["id"]).collect();
+    assert_eq!(ids, [&created["id"]]);
+}
+
+#[tokio::test]
+async fn create_returns_the_stored_reminder_and_its_location() {
+    let (_dir, _state, router) = app();
+    let body = json!({
+        "message": "Revise",
+        "due_time": "2099-01-01 09:00",
+        "title": "Exam prep",
+        "recurrence": "weekly",
+        "tags": ["exam"],
+        "color": "#AABBCC",
+        "link": "https://example.com/notes",
+        "group_id": "exam-prep",
+        "timezone": "Europe/Berlin",
+    });
+    let request = Request::post("/reminders")
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let location = response.headers()[LOCATION].to_str().unwrap().to_string();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let created: Value = serde_json::from_slice(&bytes).unwrap();
+
+    assert_eq!(location, format!("/reminders/{}", created["id"].as_str().unwrap()));
+    let (status, fetched) = send(&router, Method::GET, &location, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["reminder"], fetched);
+    assert_eq!(fetched["due_time"], "2099-01-01T08:00:00Z");
+}
+
+#[tokio::test]
+async fn request_settings_beat_preferences_which_beat_the_config() {
+    let mut config = Config::default();
+    config.scheduling.default_timezone = Some("America/New_York".to_string());
+    let (_dir, _state, router) = app_with(&config);
+    let (status, _) = send(
+        &router,
+        Method::PUT,
+        "/preferences/alice",
+        Some(json!({ "timezone": "Europe/Berlin", "lead_offsets": ["1d"] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let from_config = create(&router, json!({ "message": "Revise", "due_time": "2099-07-01 09:00", "username": "bob" })).await;
+    assert_eq!(from_config["due_time"], "2099-07-01T13:00:00+00:00");
+    assert!(from_config["lead_reminders"].is_null());
+
+    let from_preferences = create(&router, json!({ "message": "Revise", "due_time": "2099-07-01 09:00", "username": "alice" })).await;
+    assert_eq!(from_preferences["due_time"], "2099-07-01T07:00:00+00:00");
+    assert_eq!(from_preferences["lead_reminders"].as_array().unwrap().len(), 1);
+
+    let from_request = create(
+        &router,
+        json!({
+            "message": "Revise",
+            "due_time": "2099-07-01 09:00",
+            "username": "alice",
+            "timezone": "Asia/Tokyo",
+            "lead_offsets": [],
+        }),
+    )
+    .await;
+    assert_eq!(from_request["due_time"], "2099-07-01T00:00:00+00:00");
+    assert!(from_request["lead_reminders"].is_null());
+}
+
+#[tokio::test]
+async fn healthz_reports_a_stopped_notification_loop() {
+    let (_dir, state, router) = app();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let notifier = tokio::spawn(notification_service(
+        state.storage.clone(),
+        std::time::Duration::from_millis(10),
+        None,
+        None,
+        state.notifier_health.clone(),
+        shutdown_rx,
+    ));
+
+    let (status, health) = send(&router, Method::GET, "/healthz", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(health["status"], "ok");
+
+    shutdown_tx.send(true).unwrap();
+    notifier.await.unwrap();
+
+    let (status, health) = send(&router, Method::GET, "/healthz", None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(health["status"], "stopped");
+}
+
+#[tokio::test]
+async fn list_is_csv_when_asked_for_and_the_query_wins_over_accept() {
+    let (_dir, _state, router) = app();
+    create(&router, json!({ "message": "milk, eggs", "due_time": "2099-01-01T09:00:00Z" })).await;
+
+    let list = |accept: &'static str, uri: &'static str| {
+        let router = router.clone();
+        async move {
+            let request = Request::get(uri).header(ACCEPT, accept).body(Body::empty()).unwrap();
+            let response = router.oneshot(request).await.unwrap();
+            let content_type = response.headers()[CONTENT_TYPE].to_str().unwrap().to_string();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (content_type, String::from_utf8(body.to_vec()).unwrap())
+        }
+    };
+
+    let (content_type, body) = list("application/json;q=0.5, text/csv", "/reminders").await;
+    assert!(content_type.starts_with("text/csv"));
+    assert!(body.starts_with("id,message,due_time,"));
+    assert!(body.contains(",\"milk, eggs\","));
+
+    let (content_type, _) = list("text/csv", "/reminders?format=json").await;
+    assert_eq!(content_type, "application/json");
+
+    let (content_type, _) = list("application/json", "/reminders?format=csv").await;
+    assert!(content_type.starts_with("text/csv"));
+}
+
+/// An app with authentication on and `admin` as the only admin.
+fn authenticated_app() -> (TempDir, Arc<AppState>, Router) {
+    let mut config = Config::default();
+    config.server.shared_secret = Some(SECRET.to_string());
+    config.server.admins = vec!["admin".to_string()];
+    app_with(&config)
+}
+
+#[tokio::test]
+async fn only_admins_change_shared_templates() {
+    let (_dir, _state, router) = authenticated_app();
+    let template = json!({ "name": "standup", "message": "Daily standup" });
+
+    let (status, _) = send_as(&router, Some("ann"), Method::POST, "/templates", Some(template.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, created) = send_as(&router, Some("admin"), Method::POST, "/templates", Some(template)).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["username"], Value::Null);
+
+    let (status, _) = send_as(&router, Some("ann"), Method::DELETE, "/templates/standup", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, listed) = send_as(&router, Some("ann"), Method::GET, "/templates", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed["templates"][0]["name"], "standup", "shared templates stay readable");
+
+    let (status, _) = send_as(&router, Some("admin"), Method::DELETE, "/templates/standup", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn users_only_reach_their_own_templates() {
+    let (_dir, _state, router) = authenticated_app();
+    let template = json!({ "name": "review", "message": "Weekly review", "username": "ann" });
+
+    let (status, _) = send_as(&router, Some("bob"), Method::POST, "/templates", Some(template.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send_as(&router, Some("ann"), Method::POST, "/templates", Some(template)).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _) = send_as(&router, Some("bob"), Method::GET, "/templates?username=ann", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send_as(&router, Some("bob"), Method::DELETE, "/templates/review?username=ann", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send_as(
+        &router,
+        Some("bob"),
+        Method::POST,
+        "/templates/review/instantiate",
+        Some(json!({ "due_time": "2099-01-01T09:00:00Z", "username": "ann" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, instantiated) = send_as(
+        &router,
+        Some("ann"),
+        Method::POST,
+        "/templates/review/instantiate",
+        Some(json!({ "due_time": "2099-01-01T09:00:00Z", "username": "ann" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", instantiated);
+    assert_eq!(instantiated["reminder"]["owner"], "ann");
+}
+
+#[tokio::test]
+async fn patch_keeps_completion_anchored_reminders_on_a_fixed_interval() {
+    let (_dir, _state, router) = app();
+    let created = create(
+        &router,
+        json!({
+            "message": "Water the plants",
+            "due_time": "2099-01-01T09:00:00Z",
+            "recurrence": "3 days",
+            "recurrence_anchor": "completion",
+        }),
+    )
+    .await;
+    let uri = format!("/reminders/{}", created["id"].as_str().unwrap());
+
+    let (status, error) = send(&router, Method::PATCH, &uri, Some(json!({ "recurrence": "after_complete:2d" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["code"], "bad_request");
+    let (_, stored) = send(&router, Method::GET, &uri, None).await;
+    assert_eq!(stored["recurrence"], "3 days");
+
+    let (status, updated) = send(&router, Method::PATCH, &uri, Some(json!({ "recurrence": "2 days" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["recurrence"], "2 days");
+    let (_, report) = send(&router, Method::POST, "/admin/validate", None).await;
+    assert_eq!(report["invalid"], json!([]), "{}", report);
+}
@@ -0,0 +1,96 @@
+use axum_server::tls_rustls::RustlsConfig;
+use reminder_service::config::Config;
+use reminder_service::models::CreateReminderRequest;
+use reminder_service::preference_storage::PreferenceStorage;
+use reminder_service::reminder_client::ReminderClient;
+use reminder_service::server::{build_router, AppState};
+use reminder_service::storage::ReminderStorage;
+use reminder_service::template_storage::TemplateStorage;
+use reminder_service::tls::load_server_config;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Self-signed for `localhost` and 127.0.0.1, valid for a century, generated with
+/// `openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes`.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+/// Serves the router over HTTPS on an ephemeral port.
+async fn serve_https(dir: &TempDir) -> SocketAddr {
+    let storage_path = dir.path().join("reminders.json");
+    let state = AppState::from_config(
+        Arc::new(ReminderStorage::open(&storage_path).unwrap()),
+        Arc::new(TemplateStorage::open_beside(&storage_path).unwrap()),
+        Arc::new(PreferenceStorage::open_beside(&storage_path).unwrap()),
+        &Config::default(),
+    );
+    let tls = load_server_config(&fixture("localhost.crt"), &fixture("localhost.key")).unwrap();
+
+    let handle = axum_server::Handle::new();
+    let listening = handle.clone();
+    let app = build_router(Arc::new(state));
+    tokio::spawn(async move {
+        axum_server::bind_rustls("127.0.0.1:0".parse().unwrap(), RustlsConfig::from_config(Arc::new(tls)))
+            .handle(handle)
+            .serve(app.into_make_service())
+            .await
+    });
+    listening.listening().await.expect("HTTPS server failed to start")
+}
+
+#[tokio::test]
+async fn client_round_trips_over_https_with_a_self_signed_certificate() {
+    let dir = TempDir::new().unwrap();
+    let addr = serve_https(&dir).await;
+    let client = ReminderClient::new(format!("https://localhost:{}", addr.port())).insecure(true);
+
+    let created = client
+        .create(&CreateReminderRequest {
+            message: "Renew the certificate".to_string(),
+            due_time: "2099-01-01T09:00:00Z".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let listed = client.list().await.unwrap();
+
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, created.id);
+}
+
+#[tokio::test]
+async fn verifying_client_rejects_the_self_signed_certificate() {
+    let dir = TempDir::new().unwrap();
+    let addr = serve_https(&dir).await;
+    let client = ReminderClient::new(format!("https://localhost:{}", addr.port()));
+
+    let error = client.list().await.unwrap_err();
+
+    assert!(error.is_connect(), "{}", error);
+}
+
+#[test]
+fn key_that_does_not_match_the_certificate_is_reported() {
+    let error = load_server_config(&fixture("localhost.crt"), &fixture("other.key")).unwrap_err();
+
+    assert!(error.to_string().contains("cannot be used with certificate"), "{:#}", error);
+}
+
+#[test]
+fn files_that_are_not_pem_are_reported() {
+    let dir = TempDir::new().unwrap();
+    let garbage = dir.path().join("garbage.pem");
+    std::fs::write(&garbage, "not a certificate").unwrap();
+
+    let error = load_server_config(&garbage, &fixture("localhost.key")).unwrap_err();
+    assert!(error.to_string().contains("contains no certificates"), "{:#}", error);
+
+    let error = load_server_config(&fixture("localhost.crt"), &garbage).unwrap_err();
+    assert!(error.to_string().contains("does not contain a PEM private key"), "{:#}", error);
+
+    let error = load_server_config(&dir.path().join("missing.pem"), &fixture("localhost.key")).unwrap_err();
+    assert!(error.to_string().starts_with("Failed to read TLS certificate"), "{:#}", error);
+}